@@ -0,0 +1,187 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
+
+use anyhow::{Context, bail};
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{HeaderMap, header, request::Parts},
+};
+
+use crate::axummain::state::AppState;
+
+/// Longest user agent kept, longer ones are truncated
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+// Where a request comes from, used to track logins
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl FromRequestParts<AppState> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|user_agent| !user_agent.is_empty())
+            .map(|user_agent| user_agent.chars().take(MAX_USER_AGENT_LENGTH).collect());
+
+        // Also picks up `MockConnectInfo` in the tests
+        let peer = ConnectInfo::<SocketAddr>::from_request_parts(parts, state)
+            .await
+            .ok()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        Ok(ClientInfo {
+            ip: client_ip(&parts.headers, peer, &state.trusted_proxies).map(|ip| ip.to_string()),
+            user_agent,
+        })
+    }
+}
+
+/// Reverse proxies allowed to set `X-Real-IP`, as addresses or CIDR blocks
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<(IpAddr, u8)>);
+
+impl TrustedProxies {
+    pub fn parse(entries: &[String]) -> anyhow::Result<Self> {
+        entries
+            .iter()
+            .map(|entry| {
+                let (addr, prefix) = match entry.split_once('/') {
+                    Some((addr, prefix)) => (addr, Some(prefix)),
+                    None => (entry.as_str(), None),
+                };
+                let addr: IpAddr = addr
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("Invalid trusted proxy address {entry}"))?;
+                let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+                let prefix = match prefix {
+                    Some(prefix) => prefix
+                        .trim()
+                        .parse()
+                        .with_context(|| format!("Invalid trusted proxy prefix {entry}"))?,
+                    None => max_prefix,
+                };
+                if prefix > max_prefix {
+                    bail!("Invalid trusted proxy prefix {entry}");
+                }
+                Ok((addr, prefix))
+            })
+            .collect::<anyhow::Result<_>>()
+            .map(Self)
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|(network, prefix)| match (network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(*prefix)).unwrap_or(0);
+                u32::from(*network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(*prefix)).unwrap_or(0);
+                u128::from(*network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        })
+    }
+}
+
+/// The API runs behind nginx which sets `X-Real-IP`. The header is only trusted when the
+/// request comes from one of the trusted proxies, otherwise anyone reaching the API
+/// directly could pick their address. The socket address is used in every other case.
+pub fn client_ip(
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    trusted_proxies: &TrustedProxies,
+) -> Option<IpAddr> {
+    let peer = peer?;
+    if !trusted_proxies.contains(peer) {
+        return Some(peer);
+    }
+
+    headers
+        .get("X-Real-IP")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|ip| ip.trim().parse().ok())
+        .or(Some(peer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_client_ip() {
+        let proxy = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let trusted = TrustedProxies::parse(&["10.0.0.0/24".to_string()]).unwrap();
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_ip(&headers, None, &trusted), None);
+        assert_eq!(client_ip(&headers, Some(proxy), &trusted), Some(proxy));
+
+        headers.insert("X-Real-IP", "203.0.113.7".parse().unwrap());
+        assert_eq!(
+            client_ip(&headers, Some(proxy), &trusted),
+            Some("203.0.113.7".parse().unwrap())
+        );
+
+        // Without a known peer the header cannot be trusted
+        assert_eq!(client_ip(&headers, None, &trusted), None);
+
+        headers.insert("X-Real-IP", "not an ip".parse().unwrap());
+        assert_eq!(client_ip(&headers, Some(proxy), &trusted), Some(proxy));
+    }
+
+    #[test]
+    fn test_client_ip_from_untrusted_peer() {
+        let client = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 4));
+        let trusted = TrustedProxies::parse(&["10.0.0.0/24".to_string()]).unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Real-IP", "203.0.113.7".parse().unwrap());
+
+        assert_eq!(client_ip(&headers, Some(client), &trusted), Some(client));
+        assert_eq!(
+            client_ip(&headers, Some(client), &TrustedProxies::default()),
+            Some(client)
+        );
+    }
+
+    #[test]
+    fn test_trusted_proxies() {
+        let trusted = TrustedProxies::parse(&[
+            "172.16.0.0/12".to_string(),
+            "127.0.0.1".to_string(),
+            "fd00::/8".to_string(),
+        ])
+        .unwrap();
+
+        assert!(trusted.contains("172.20.0.5".parse().unwrap()));
+        assert!(!trusted.contains("172.32.0.5".parse().unwrap()));
+        assert!(trusted.contains("127.0.0.1".parse().unwrap()));
+        assert!(!trusted.contains("127.0.0.2".parse().unwrap()));
+        assert!(trusted.contains("::ffff:127.0.0.1".parse().unwrap()));
+        assert!(trusted.contains("fd12::1".parse().unwrap()));
+        assert!(!trusted.contains("fe80::1".parse().unwrap()));
+
+        let everything = TrustedProxies::parse(&["0.0.0.0/0".to_string()]).unwrap();
+        assert!(everything.contains("8.8.8.8".parse().unwrap()));
+
+        assert!(TrustedProxies::parse(&["10.0.0.0/33".to_string()]).is_err());
+        assert!(TrustedProxies::parse(&["nginx".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use entities::{SecurityAlert, users};
+use redis::{AsyncCommands, RedisError};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{auth::client_info::ClientInfo, axummain::state::AppState};
+
+fn account_failures_key(user_id: &Uuid) -> String {
+    format!("login_failures:account:{}", user_id)
+}
+
+fn ip_failures_key(ip: &str) -> String {
+    format!("login_failures:ip:{}", ip)
+}
+
+fn account_lock_key(user_id: &Uuid) -> String {
+    format!("login_lock:account:{}", user_id)
+}
+
+/// Seconds left before the IP can try to log in again, if it failed too many times
+pub async fn ip_blocked_for(state: &AppState, ip: &str) -> Result<Option<u64>, RedisError> {
+    let key = ip_failures_key(ip);
+    let mut con = state.redis.clone();
+
+    let failures: Option<u32> = con.get(&key).await?;
+    if failures.unwrap_or(0) < state.login_security.max_failed_attempts_per_ip {
+        return Ok(None);
+    }
+
+    let ttl: i64 = con.ttl(&key).await?;
+    Ok(Some(ttl.max(1) as u64))
+}
+
+/// Seconds left before the account is unlocked, if it is locked
+pub async fn account_locked_for(
+    state: &AppState,
+    user_id: &Uuid,
+) -> Result<Option<u64>, RedisError> {
+    let mut con = state.redis.clone();
+    let ttl: i64 = con.ttl(account_lock_key(user_id)).await?;
+
+    // -2 when the key does not exist
+    Ok((ttl > 0).then_some(ttl as u64))
+}
+
+/// Count a failed login for the IP and the account, locking the account when it reaches
+/// the threshold. Returns true if the account just got locked.
+pub async fn record_failed_login(
+    state: &AppState,
+    user_id: Option<&Uuid>,
+    ip: Option<&str>,
+) -> Result<bool, RedisError> {
+    let settings = &state.login_security;
+    let mut con = state.redis.clone();
+
+    if let Some(ip) = ip {
+        increment_failures(
+            &mut con,
+            &ip_failures_key(ip),
+            settings.failed_attempts_window_secs,
+        )
+        .await?;
+    }
+
+    let Some(user_id) = user_id else {
+        return Ok(false);
+    };
+
+    let failures = increment_failures(
+        &mut con,
+        &account_failures_key(user_id),
+        settings.failed_attempts_window_secs,
+    )
+    .await?;
+
+    if failures < settings.max_failed_attempts_per_account {
+        return Ok(false);
+    }
+
+    warn!(
+        "Locking account {} for {} seconds after {} failed logins",
+        user_id, settings.lockout_secs, failures
+    );
+    con.set_ex::<_, _, ()>(account_lock_key(user_id), 1, settings.lockout_secs)
+        .await?;
+    con.del::<_, ()>(account_failures_key(user_id)).await?;
+
+    Ok(true)
+}
+
+/// Forget the failed logins of an account after a successful one
+pub async fn clear_failed_logins(state: &AppState, user_id: &Uuid) -> Result<(), RedisError> {
+    let mut con = state.redis.clone();
+    con.del(account_failures_key(user_id)).await
+}
+
+async fn increment_failures(
+    con: &mut redis::aio::ConnectionManager,
+    key: &str,
+    window_secs: u64,
+) -> Result<u32, RedisError> {
+    let failures: u32 = con.incr(key, 1).await?;
+    // The window starts with the first failure
+    if failures == 1 {
+        con.expire::<_, ()>(key, window_secs as i64).await?;
+    }
+    Ok(failures)
+}
+
+/// Tell the user their account got locked, guests have no real address to write to
+pub async fn send_lockout_alert(state: &AppState, user: &users::Model, client: &ClientInfo) {
+    if !user.email_verified {
+        return;
+    }
+
+    let alert = SecurityAlert::AccountLocked {
+        locked_minutes: state.login_security.lockout_secs.div_ceil(60),
+        ip: client.ip.clone(),
+    };
+
+    if let Err(err) = state
+        .jobs
+        .email_job
+        .send_security_alert_email(&user.email, &user.username, alert)
+        .await
+    {
+        error!("Failed to send lockout email: {}", err);
+    }
+}
+
+/// Remember the device used to log in and alert the user the first time a new one is seen.
+/// The very first device of an account is not worth an alert.
+pub async fn track_login_device(state: &AppState, user: &users::Model, client: &ClientInfo) {
+    let Some(user_agent) = client.user_agent.as_deref() else {
+        return;
+    };
+
+    let repository = &state.repositories.user_login_device_repository;
+
+    let devices = match repository.find_all_by_user(&user.id).await {
+        Ok(devices) => devices,
+        Err(err) => {
+            error!("Failed to fetch login devices: {}", err);
+            return;
+        }
+    };
+
+    let is_first_device = devices.is_empty();
+    if let Some(device) = devices.into_iter().find(|d| d.user_agent == user_agent) {
+        if let Err(err) = repository.touch(device, client.ip.clone()).await {
+            error!("Failed to update login device: {}", err);
+        }
+        return;
+    }
+
+    if let Err(err) = repository
+        .create(&user.id, user_agent, client.ip.clone())
+        .await
+    {
+        error!("Failed to save login device: {}", err);
+        return;
+    }
+
+    if is_first_device || !user.email_verified {
+        return;
+    }
+
+    info!("User {} logged in from a new device", user.id);
+    let alert = SecurityAlert::NewDeviceLogin {
+        user_agent: user_agent.to_string(),
+        ip: client.ip.clone(),
+    };
+
+    if let Err(err) = state
+        .jobs
+        .email_job
+        .send_security_alert_email(&user.email, &user.username, alert)
+        .await
+    {
+        error!("Failed to send new device email: {}", err);
+    }
+}
@@ -3,7 +3,7 @@ use axum::{
     extract::{FromRef, FromRequestParts},
     http::{HeaderMap, StatusCode, request::Parts},
 };
-use entities::users::Model as User;
+use entities::{sea_orm_active_enums::UserGroup, users::Model as User};
 use uuid::Uuid;
 
 // For protected routes - requires valid JWT
@@ -18,6 +18,10 @@ pub struct OptionalAuth(pub Option<User>);
 #[derive(Debug)]
 pub struct RequireVerifiedAuth(pub User);
 
+// For moderation routes - requires a verified member of the admin group
+#[derive(Debug)]
+pub struct RequireAdmin(pub User);
+
 impl<S> FromRequestParts<S> for RequireAuth
 where
     AppState: FromRef<S>,
@@ -119,7 +123,34 @@ where
     }
 }
 
-fn extract_token_from_headers(headers: &HeaderMap) -> Option<&str> {
+impl<S> FromRequestParts<S> for RequireAdmin
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let RequireVerifiedAuth(user) =
+            RequireVerifiedAuth::from_request_parts(parts, state).await?;
+
+        let app_state = AppState::from_ref(state);
+        let is_admin = app_state
+            .repositories
+            .user_group_repository
+            .is_user_id_in_group(&user.id, UserGroup::AdminGroup)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        if !is_admin {
+            return Err(StatusCode::FORBIDDEN);
+        }
+
+        Ok(RequireAdmin(user))
+    }
+}
+
+pub fn extract_token_from_headers(headers: &HeaderMap) -> Option<&str> {
     let auth_header = headers.get("Authorization")?.to_str().ok()?;
 
     auth_header.strip_prefix("Token ")
@@ -159,7 +190,17 @@ mod tests {
             .get_connection_manager()
             .await
             .unwrap();
-        AppState::new(db.clone(), redis, jwt_secret).await.unwrap()
+        AppState::new(
+            db.clone(),
+            redis,
+            jwt_secret,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap()
     }
 
     fn create_request_parts(token: Option<&str>) -> Parts {
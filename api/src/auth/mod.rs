@@ -1,6 +1,11 @@
+pub mod client_info;
 pub mod jwt;
+pub mod login_security;
 pub mod middleware;
 pub mod password;
+pub mod password_policy;
 pub mod refresh_token;
 pub mod resource_authorization;
+pub mod totp;
+pub mod two_factor;
 pub mod user_view_authorization;
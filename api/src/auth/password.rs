@@ -1,11 +1,133 @@
-use bcrypt::{DEFAULT_COST, hash, verify};
+use argon2::{
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
+    password_hash::SaltString,
+};
+use entities::env_loader::Argon2Settings;
+use rand::RngCore;
+use std::fmt;
+
+/// Cheapest parameters, for values that are not secret or already random like the
+/// default guest password
+pub const LOW_COST_HASHING: Argon2Settings = Argon2Settings {
+    memory_kib: Params::MIN_M_COST * 4,
+    iterations: 1,
+    parallelism: 1,
+};
+
+#[derive(Debug)]
+pub enum PasswordError {
+    Argon2(argon2::password_hash::Error),
+    Bcrypt(bcrypt::BcryptError),
+    /// The stored hash was made with a scheme we do not know
+    UnknownScheme,
+}
+
+impl fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordError::Argon2(err) => write!(f, "argon2 error: {}", err),
+            PasswordError::Bcrypt(err) => write!(f, "bcrypt error: {}", err),
+            PasswordError::UnknownScheme => write!(f, "unknown password hash scheme"),
+        }
+    }
+}
+
+impl std::error::Error for PasswordError {}
+
+impl From<argon2::password_hash::Error> for PasswordError {
+    fn from(err: argon2::password_hash::Error) -> Self {
+        PasswordError::Argon2(err)
+    }
+}
+
+impl From<bcrypt::BcryptError> for PasswordError {
+    fn from(err: bcrypt::BcryptError) -> Self {
+        PasswordError::Bcrypt(err)
+    }
+}
+
+/// Hash schemes found in the database, bcrypt hashes predate Argon2id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashScheme {
+    Argon2id,
+    Bcrypt,
+}
+
+impl HashScheme {
+    pub fn detect(password_hash: &str) -> Option<Self> {
+        if password_hash.starts_with("$argon2id$") {
+            Some(HashScheme::Argon2id)
+        } else if ["$2a$", "$2b$", "$2x$", "$2y$"]
+            .iter()
+            .any(|prefix| password_hash.starts_with(prefix))
+        {
+            Some(HashScheme::Bcrypt)
+        } else {
+            None
+        }
+    }
+}
+
+fn argon2(settings: &Argon2Settings) -> Result<Argon2<'static>, PasswordError> {
+    let params = Params::new(
+        settings.memory_kib,
+        settings.iterations,
+        settings.parallelism,
+        None,
+    )
+    .map_err(|err| PasswordError::Argon2(err.into()))?;
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+}
+
+fn hash_password(password: &str, settings: &Argon2Settings) -> Result<String, PasswordError> {
+    let mut salt = [0u8; 16];
+    rand::rng().fill_bytes(&mut salt);
+    let salt = SaltString::encode_b64(&salt)?;
+
+    Ok(argon2(settings)?
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+fn verify_password(password: &str, password_hash: &str) -> Result<bool, PasswordError> {
+    match HashScheme::detect(password_hash) {
+        Some(HashScheme::Argon2id) => {
+            // The parameters used are read from the hash itself
+            let parsed = PasswordHash::new(password_hash)?;
+            match Argon2::default().verify_password(password.as_bytes(), &parsed) {
+                Ok(()) => Ok(true),
+                Err(argon2::password_hash::Error::Password) => Ok(false),
+                Err(err) => Err(err.into()),
+            }
+        }
+        Some(HashScheme::Bcrypt) => Ok(bcrypt::verify(password, password_hash)?),
+        None => Err(PasswordError::UnknownScheme),
+    }
+}
+
+/// Whether the hash should be replaced by a new one made with the current settings
+pub fn needs_rehash(password_hash: &str, settings: &Argon2Settings) -> bool {
+    if HashScheme::detect(password_hash) != Some(HashScheme::Argon2id) {
+        return true;
+    }
+
+    let Ok(parsed) = PasswordHash::new(password_hash) else {
+        return true;
+    };
+    let Ok(params) = Params::try_from(&parsed) else {
+        return true;
+    };
+
+    params.m_cost() != settings.memory_kib
+        || params.t_cost() != settings.iterations
+        || params.p_cost() != settings.parallelism
+}
 
 pub async fn hash_password_async(
     password: String,
-    cost: Option<u32>,
-) -> Result<String, bcrypt::BcryptError> {
-    let cost = cost.unwrap_or(DEFAULT_COST);
-    tokio::task::spawn_blocking(move || hash(password, cost))
+    settings: Argon2Settings,
+) -> Result<String, PasswordError> {
+    tokio::task::spawn_blocking(move || hash_password(&password, &settings))
         .await
         .expect("password hashing task was cancelled")
 }
@@ -13,8 +135,8 @@ pub async fn hash_password_async(
 pub async fn verify_password_async(
     password: String,
     password_hash: String,
-) -> Result<bool, bcrypt::BcryptError> {
-    tokio::task::spawn_blocking(move || verify(password, &password_hash))
+) -> Result<bool, PasswordError> {
+    tokio::task::spawn_blocking(move || verify_password(&password, &password_hash))
         .await
         .expect("password verification task was cancelled")
 }
@@ -27,11 +149,12 @@ mod tests {
     async fn test_hash_and_verify_password() {
         let password = "my_secret_pwd".to_string();
 
-        let hashed = hash_password_async(password.clone(), Some(4))
+        let hashed = hash_password_async(password.clone(), LOW_COST_HASHING)
             .await
             .expect("failed to hash password");
 
         assert_ne!(password, hashed);
+        assert_eq!(HashScheme::detect(&hashed), Some(HashScheme::Argon2id));
 
         let is_valid = verify_password_async(password, hashed)
             .await
@@ -45,7 +168,7 @@ mod tests {
         let password = "correct_pwd".to_string();
         let wrong = "wrong_pwd".to_string();
 
-        let hashed = hash_password_async(password.clone(), Some(4))
+        let hashed = hash_password_async(password.clone(), LOW_COST_HASHING)
             .await
             .expect("failed to hash password");
 
@@ -55,4 +178,48 @@ mod tests {
 
         assert!(!is_valid);
     }
+
+    #[tokio::test]
+    async fn test_verify_legacy_bcrypt_password() {
+        let password = "legacy_pwd".to_string();
+        let hashed = bcrypt::hash(&password, 4).unwrap();
+
+        assert_eq!(HashScheme::detect(&hashed), Some(HashScheme::Bcrypt));
+        assert!(needs_rehash(&hashed, &LOW_COST_HASHING));
+        assert!(
+            verify_password_async(password, hashed.clone())
+                .await
+                .unwrap()
+        );
+        assert!(
+            !verify_password_async("wrong".to_string(), hashed)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_needs_rehash_when_parameters_change() {
+        let hashed = hash_password_async("my_secret_pwd".to_string(), LOW_COST_HASHING)
+            .await
+            .unwrap();
+
+        assert!(!needs_rehash(&hashed, &LOW_COST_HASHING));
+        assert!(needs_rehash(
+            &hashed,
+            &Argon2Settings {
+                iterations: 2,
+                ..LOW_COST_HASHING
+            }
+        ));
+    }
+
+    #[test]
+    fn test_unknown_scheme() {
+        assert_eq!(HashScheme::detect("plaintext"), None);
+        assert!(matches!(
+            verify_password("plaintext", "plaintext"),
+            Err(PasswordError::UnknownScheme)
+        ));
+    }
 }
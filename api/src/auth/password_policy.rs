@@ -0,0 +1,145 @@
+use std::{collections::HashSet, fmt};
+
+use anyhow::Context;
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use entities::env_loader::{Argon2Settings, PasswordSettings};
+use serde_json::json;
+
+/// Rules a new password has to follow, and how it is hashed
+#[derive(Debug, Default)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub hashing: Argon2Settings,
+    /// Lowercased known breached passwords
+    breached: HashSet<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PasswordPolicyError {
+    TooShort(usize),
+    TooLong(usize),
+    Breached,
+}
+
+impl fmt::Display for PasswordPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordPolicyError::TooShort(min) => {
+                write!(f, "Password must be at least {} characters", min)
+            }
+            PasswordPolicyError::TooLong(max) => {
+                write!(f, "Password must be at most {} characters", max)
+            }
+            PasswordPolicyError::Breached => write!(
+                f,
+                "This password appeared in a data breach, please choose another one"
+            ),
+        }
+    }
+}
+
+impl IntoResponse for PasswordPolicyError {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": self.to_string()})),
+        )
+            .into_response()
+    }
+}
+
+impl PasswordPolicy {
+    pub fn from_settings(settings: &PasswordSettings) -> anyhow::Result<Self> {
+        let breached = match &settings.breached_passwords_file {
+            Some(path) => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read breached passwords file {path}"))?;
+                parse_breached_passwords(&content)
+            }
+            None => HashSet::new(),
+        };
+
+        Ok(Self {
+            min_length: settings.min_length,
+            max_length: settings.max_length,
+            hashing: settings.argon2,
+            breached,
+        })
+    }
+
+    pub fn check(&self, password: &str) -> Result<(), PasswordPolicyError> {
+        let length = password.chars().count();
+        if length < self.min_length {
+            return Err(PasswordPolicyError::TooShort(self.min_length));
+        }
+        if self.max_length > 0 && length > self.max_length {
+            return Err(PasswordPolicyError::TooLong(self.max_length));
+        }
+        if self.breached.contains(&password.to_lowercase()) {
+            return Err(PasswordPolicyError::Breached);
+        }
+        Ok(())
+    }
+}
+
+/// One password per line, empty lines and `#` comments are ignored
+fn parse_breached_passwords(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 8,
+            max_length: 16,
+            hashing: Argon2Settings::default(),
+            breached: parse_breached_passwords("# comment\npassword123\n\n  Sunshine1  \n"),
+        }
+    }
+
+    #[test]
+    fn test_password_length() {
+        let policy = policy();
+        assert_eq!(policy.check("short"), Err(PasswordPolicyError::TooShort(8)));
+        assert_eq!(
+            policy.check("a very long password indeed"),
+            Err(PasswordPolicyError::TooLong(16))
+        );
+        assert_eq!(policy.check("correct horse"), Ok(()));
+    }
+
+    #[test]
+    fn test_breached_password() {
+        let policy = policy();
+        assert_eq!(
+            policy.check("Password123"),
+            Err(PasswordPolicyError::Breached)
+        );
+        assert_eq!(
+            policy.check("sunshine1"),
+            Err(PasswordPolicyError::Breached)
+        );
+        assert!(!policy.breached.contains("# comment"));
+    }
+
+    #[test]
+    fn test_shipped_breached_passwords_file() {
+        let content = include_str!("../../../config/breached_passwords.txt");
+        let breached = parse_breached_passwords(content);
+        assert!(breached.contains("password"));
+        assert!(breached.contains("12345678"));
+    }
+}
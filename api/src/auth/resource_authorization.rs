@@ -1,78 +1,114 @@
-use crate::auth::middleware::RequireAuth;
+use crate::auth::middleware::RequireVerifiedAuth;
+use crate::auth::user_view_authorization::{ViewAuthorizationError, WatchScope};
 use crate::axummain::state::AppState;
 use axum::{
-    extract::{FromRef, FromRequestParts},
+    extract::{FromRef, FromRequestParts, Path},
     http::{StatusCode, request::Parts},
 };
+use entities::users::Model as User;
+use serde::Deserialize;
+use std::marker::PhantomData;
+use tracing::error;
 use uuid::Uuid;
 
+/// Marker type selecting the watch scope checked by `ViewUserData`
+pub trait ViewScope {
+    const SCOPE: WatchScope;
+}
+
+#[derive(Debug)]
+pub struct WeightScope;
+
+impl ViewScope for WeightScope {
+    const SCOPE: WatchScope = WatchScope::Weight;
+}
+
+#[derive(Debug)]
+pub struct MealsScope;
+
+impl ViewScope for MealsScope {
+    const SCOPE: WatchScope = WatchScope::Meals;
+}
+
+#[derive(Debug)]
+pub struct GymScope;
+
+impl ViewScope for GymScope {
+    const SCOPE: WatchScope = WatchScope::Gym;
+}
+
+#[derive(Debug)]
+pub struct BodyInfosScope;
+
+impl ViewScope for BodyInfosScope {
+    const SCOPE: WatchScope = WatchScope::BodyInfos;
+}
+
 /// Extractor that verifies the authenticated user has permission to view a specific user's data
-/// 
-/// **NOTE**: This is a future implementation pattern. Currently, handlers should use the
-/// `check_view_permission` helper or call `app_state.services.authorization.can_view_user_data()`
-/// directly, since path parameter extraction requires more complex handling.
-/// 
-/// The current implementation assumes the target user ID is already in request extensions,
-/// which requires middleware to set it up. For now, prefer using direct authorization checks
-/// in handlers as demonstrated in `user_weight.rs`.
-/// 
-/// Future usage example (requires additional setup):
+///
+/// The target user is read from the `{user_id}` path parameter, so it can be used on any
+/// `/api/users/{user_id}/...` route. Other path parameters of the route are ignored.
+/// The type parameter selects the scope the watch permission must grant.
+///
+/// Usage:
 /// ```ignore
 /// async fn get_user_data(
-///     ViewUserData(target_user_id): ViewUserData,
-///     RequireAuth(requesting_user): RequireAuth,
+///     ViewUserData { requesting_user, target_user_id, .. }: ViewUserData<WeightScope>,
 /// ) -> Result<Json<Response>, StatusCode> {
-///     // target_user_id is guaranteed to be viewable by requesting_user
+///     // target_user_id's weights are guaranteed to be viewable by requesting_user
 /// }
 /// ```
 #[derive(Debug)]
-pub struct ViewUserData(pub Uuid);
+pub struct ViewUserData<S: ViewScope> {
+    pub requesting_user: User,
+    pub target_user_id: Uuid,
+    scope: PhantomData<S>,
+}
 
-impl<S> FromRequestParts<S> for ViewUserData
+#[derive(Debug, Deserialize)]
+struct UserIdPath {
+    user_id: Uuid,
+}
+
+impl<S, V> FromRequestParts<S> for ViewUserData<V>
 where
     AppState: FromRef<S>,
     S: Send + Sync,
+    V: ViewScope,
 {
     type Rejection = StatusCode;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         // First, extract the authenticated user
-        let RequireAuth(requesting_user) =
-            RequireAuth::from_request_parts(parts, state)
-                .await
-                .map_err(|_| StatusCode::UNAUTHORIZED)?;
+        let RequireVerifiedAuth(requesting_user) =
+            RequireVerifiedAuth::from_request_parts(parts, state).await?;
 
         // Extract target user ID from path parameter
-        let target_user_id = parts
-            .extensions
-            .get::<Uuid>()
-            .copied()
-            .ok_or(StatusCode::BAD_REQUEST)?;
+        let Path(UserIdPath { user_id }) = Path::<UserIdPath>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
 
         // Get app state
         let app_state = AppState::from_ref(state);
 
         // Check authorization
-        let can_view = app_state
+        app_state
             .services
             .authorization
-            .can_view_user_data(&requesting_user.id, &target_user_id)
+            .verify_view_permission(&requesting_user.id, &user_id, V::SCOPE)
             .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            .map_err(|err| match err {
+                ViewAuthorizationError::Forbidden => StatusCode::FORBIDDEN,
+                ViewAuthorizationError::DatabaseError(msg) => {
+                    error!("Failed to check view permission: {}", msg);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            })?;
 
-        if !can_view {
-            return Err(StatusCode::FORBIDDEN);
-        }
-
-        Ok(ViewUserData(target_user_id))
+        Ok(ViewUserData {
+            requesting_user,
+            target_user_id: user_id,
+            scope: PhantomData,
+        })
     }
 }
-
-#[cfg(test)]
-mod tests {
-    // Note: Integration tests for ViewUserData extractor are complex because they require
-    // mocking multiple database queries (user lookup + permission check). The core authorization
-    // logic is thoroughly tested in the user_view_authorization module tests.
-    // 
-    // For real-world testing of this extractor, use integration tests with a test database.
-}
@@ -0,0 +1,125 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+
+/// Issuer shown in the authenticator apps
+const ISSUER: &str = "DimDim Health";
+/// Standard TOTP parameters, the only ones supported by every authenticator app
+const DIGITS: u32 = 6;
+const PERIOD_SECS: u64 = 30;
+/// Number of periods accepted before and after the current one, for clock drift
+const ALLOWED_DRIFT: u64 = 1;
+
+/// Generate a random 160 bits secret, base32 encoded as expected by authenticator apps
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; 20];
+    rand::rng().fill_bytes(&mut secret);
+    BASE32_NOPAD.encode(&secret)
+}
+
+/// URI to put in the QR code scanned by the authenticator app
+pub fn otpauth_uri(secret: &str, account: &str) -> String {
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={DIGITS}&period={PERIOD_SECS}",
+        issuer = encode_uri_component(ISSUER),
+        account = encode_uri_component(account),
+    )
+}
+
+/// Check a code against the secret at the given unix time
+///
+/// Returns the time step the code belongs to, so that callers can refuse a code
+/// that was already used.
+pub fn verify_code(secret: &str, code: &str, unix_time: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    let current_step = unix_time / PERIOD_SECS;
+
+    (current_step.saturating_sub(ALLOWED_DRIFT)..=current_step + ALLOWED_DRIFT)
+        .find(|step| format_code(hotp(&key, *step)) == code)
+}
+
+/// Code expected by the secret at the given unix time
+pub fn code_at(secret: &str, unix_time: u64) -> Option<String> {
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    Some(format_code(hotp(&key, unix_time / PERIOD_SECS)))
+}
+
+/// HOTP value of a counter (RFC 4226)
+fn hotp(key: &[u8], counter: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    binary % 10u32.pow(DIGITS)
+}
+
+fn format_code(value: u32) -> String {
+    format!("{:0width$}", value, width = DIGITS as usize)
+}
+
+fn encode_uri_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'@' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Secret of the RFC 6238 test vectors, "12345678901234567890"
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn test_rfc_6238_vectors() {
+        // The RFC gives 8 digits codes, these are their last 6 digits
+        assert_eq!(code_at(RFC_SECRET, 59).unwrap(), "287082");
+        assert_eq!(code_at(RFC_SECRET, 1111111109).unwrap(), "081804");
+        assert_eq!(code_at(RFC_SECRET, 1234567890).unwrap(), "005924");
+        assert_eq!(code_at(RFC_SECRET, 2000000000).unwrap(), "279037");
+    }
+
+    #[test]
+    fn test_verify_code_with_drift() {
+        let now = 1234567890;
+        let code = code_at(RFC_SECRET, now).unwrap();
+
+        assert_eq!(verify_code(RFC_SECRET, &code, now), Some(now / PERIOD_SECS));
+        assert!(verify_code(RFC_SECRET, &code, now + PERIOD_SECS).is_some());
+        assert!(verify_code(RFC_SECRET, &code, now - PERIOD_SECS).is_some());
+        assert!(verify_code(RFC_SECRET, &code, now + 3 * PERIOD_SECS).is_none());
+        assert!(verify_code(RFC_SECRET, "12345", now).is_none());
+        assert!(verify_code(RFC_SECRET, "abcdef", now).is_none());
+    }
+
+    #[test]
+    fn test_generate_secret_and_uri() {
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        assert!(BASE32_NOPAD.decode(secret.as_bytes()).is_ok());
+
+        let uri = otpauth_uri(&secret, "john@example.com");
+        assert!(uri.starts_with("otpauth://totp/DimDim%20Health:john@example.com?"));
+        assert!(uri.contains(&format!("secret={secret}")));
+    }
+}
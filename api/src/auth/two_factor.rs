@@ -0,0 +1,122 @@
+use data_encoding::{BASE32_NOPAD, HEXLOWER};
+use rand::RngCore;
+use redis::{AsyncCommands, RedisError, aio::ConnectionManager};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::utils::token_generator::generate_verification_token;
+
+/// Time given to the user to type the code of their authenticator app after the password
+pub const CHALLENGE_TTL_SECS: u64 = 300;
+/// Wrong codes accepted for one challenge before the password has to be typed again
+const MAX_CHALLENGE_ATTEMPTS: u32 = 5;
+
+pub const RECOVERY_CODES_COUNT: usize = 10;
+
+fn challenge_key(token: &str) -> String {
+    format!("2fa_challenge:{}", token)
+}
+
+fn challenge_attempts_key(token: &str) -> String {
+    format!("2fa_challenge_attempts:{}", token)
+}
+
+/// Create the short-lived token proving the password was right, exchanged with a code
+pub async fn create_challenge(
+    redis: &ConnectionManager,
+    user_id: &Uuid,
+) -> Result<String, RedisError> {
+    let token = generate_verification_token();
+    let mut con = redis.clone();
+    con.set_ex::<_, _, ()>(
+        challenge_key(&token),
+        user_id.to_string(),
+        CHALLENGE_TTL_SECS,
+    )
+    .await?;
+    Ok(token)
+}
+
+/// User waiting for the second step of this challenge, if it is still valid
+pub async fn find_challenge(
+    redis: &ConnectionManager,
+    token: &str,
+) -> Result<Option<Uuid>, RedisError> {
+    let mut con = redis.clone();
+    let user_id: Option<String> = con.get(challenge_key(token)).await?;
+    Ok(user_id.and_then(|user_id| Uuid::parse_str(&user_id).ok()))
+}
+
+/// Count a wrong code, the challenge is dropped once it had too many
+pub async fn record_challenge_failure(
+    redis: &ConnectionManager,
+    token: &str,
+) -> Result<(), RedisError> {
+    let mut con = redis.clone();
+    let attempts_key = challenge_attempts_key(token);
+
+    let attempts: u32 = con.incr(&attempts_key, 1).await?;
+    if attempts == 1 {
+        con.expire::<_, ()>(&attempts_key, CHALLENGE_TTL_SECS as i64)
+            .await?;
+    }
+    if attempts >= MAX_CHALLENGE_ATTEMPTS {
+        consume_challenge(redis, token).await?;
+    }
+    Ok(())
+}
+
+/// A challenge can only be used once
+pub async fn consume_challenge(redis: &ConnectionManager, token: &str) -> Result<(), RedisError> {
+    let mut con = redis.clone();
+    con.del(&[challenge_key(token), challenge_attempts_key(token)])
+        .await
+}
+
+/// Generate recovery codes like `abcde-fghij`, only shown once to the user
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::rng();
+    (0..RECOVERY_CODES_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 7];
+            rng.fill_bytes(&mut bytes);
+            let code = BASE32_NOPAD.encode(&bytes).to_lowercase();
+            format!("{}-{}", &code[..5], &code[5..10])
+        })
+        .collect()
+}
+
+/// Recovery codes are random enough for a plain SHA-256, unlike passwords
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    HEXLOWER.encode(&Sha256::digest(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_recovery_codes() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODES_COUNT);
+        assert!(codes.iter().all(|code| code.len() == 11));
+
+        let mut unique = codes.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), codes.len());
+    }
+
+    #[test]
+    fn test_hash_recovery_code_is_normalized() {
+        let hash = hash_recovery_code("abcde-fghij");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_recovery_code(" ABCDE fghij "));
+        assert_ne!(hash, hash_recovery_code("abcde-fghik"));
+    }
+}
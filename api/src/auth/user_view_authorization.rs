@@ -1,6 +1,28 @@
 use crate::repositories::user_watch_permission_repository::UserWatchPermissionRepository;
+use entities::{user_watch_permissions, users};
 use uuid::Uuid;
 
+/// Data domain a watch permission can give access to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchScope {
+    Weight,
+    Meals,
+    Gym,
+    BodyInfos,
+}
+
+impl WatchScope {
+    /// Check if the permission grants this scope (expiry is not checked)
+    pub fn is_granted_by(self, permission: &user_watch_permissions::Model) -> bool {
+        match self {
+            WatchScope::Weight => permission.can_view_weight,
+            WatchScope::Meals => permission.can_view_meals,
+            WatchScope::Gym => permission.can_view_gym,
+            WatchScope::BodyInfos => permission.can_view_body_infos,
+        }
+    }
+}
+
 /// Service for checking if a user can view another user's data
 #[derive(Clone)]
 pub struct UserViewAuthorization {
@@ -14,14 +36,16 @@ impl UserViewAuthorization {
         }
     }
 
-    /// Check if the requesting user can view the target user's data
+    /// Check if the requesting user can view the target user's data in the given scope
     /// Returns true if:
     /// - The requesting user is viewing their own data (requesting_user_id == target_user_id)
-    /// - The requesting user has watch permission for the target user
+    /// - The requesting user has a non-expired watch permission for the target user
+    ///   that grants the scope
     pub async fn can_view_user_data(
         &self,
         requesting_user_id: &Uuid,
         target_user_id: &Uuid,
+        scope: WatchScope,
     ) -> Result<bool, sea_orm::DbErr> {
         // Users can always view their own data
         if requesting_user_id == target_user_id {
@@ -34,27 +58,17 @@ impl UserViewAuthorization {
             .find_by_user_ids(target_user_id, requesting_user_id)
             .await?;
 
-        Ok(permission.is_some())
+        Ok(permission.is_some_and(|p| !p.is_expired() && scope.is_granted_by(&p)))
     }
 
-    /// Get all user IDs that the requesting user can view (including themselves)
-    pub async fn get_viewable_user_ids(
+    /// Get all users the requesting user is currently watching, with their permission
+    pub async fn get_watched_users(
         &self,
         requesting_user_id: &Uuid,
-    ) -> Result<Vec<Uuid>, sea_orm::DbErr> {
-        let mut viewable_ids = vec![*requesting_user_id];
-
-        // Get all users that the requesting user is watching
-        let watched_users = self
-            .watch_permission_repo
+    ) -> Result<Vec<(user_watch_permissions::Model, users::Model)>, sea_orm::DbErr> {
+        self.watch_permission_repo
             .find_all_watching(requesting_user_id)
-            .await?;
-
-        for user in watched_users {
-            viewable_ids.push(user.id);
-        }
-
-        Ok(viewable_ids)
+            .await
     }
 
     /// Verify that the requesting user can view the target user's data
@@ -63,9 +77,10 @@ impl UserViewAuthorization {
         &self,
         requesting_user_id: &Uuid,
         target_user_id: &Uuid,
+        scope: WatchScope,
     ) -> Result<(), ViewAuthorizationError> {
         let can_view = self
-            .can_view_user_data(requesting_user_id, target_user_id)
+            .can_view_user_data(requesting_user_id, target_user_id, scope)
             .await
             .map_err(|e| ViewAuthorizationError::DatabaseError(e.to_string()))?;
 
@@ -102,8 +117,7 @@ impl std::error::Error for ViewAuthorizationError {}
 mod tests {
     use super::*;
     use crate::repositories::user_watch_permission_repository::UserWatchPermissionRepository;
-    use chrono::Utc;
-    use entities::user_watch_permissions;
+    use chrono::{Duration, Utc};
     use sea_orm::{DatabaseBackend, MockDatabase};
 
     fn create_mock_permission(
@@ -114,6 +128,11 @@ mod tests {
             user_watched_id,
             user_watching_id,
             created_at: Utc::now().into(),
+            can_view_weight: true,
+            can_view_meals: true,
+            can_view_gym: true,
+            can_view_body_infos: true,
+            expires_at: None,
         }
     }
 
@@ -124,7 +143,9 @@ mod tests {
         let auth = UserViewAuthorization::new(repo);
 
         let user_id = Uuid::new_v4();
-        let result = auth.can_view_user_data(&user_id, &user_id).await;
+        let result = auth
+            .can_view_user_data(&user_id, &user_id, WatchScope::Weight)
+            .await;
 
         assert!(result.is_ok());
         assert!(result.unwrap());
@@ -144,7 +165,7 @@ mod tests {
         let auth = UserViewAuthorization::new(repo);
 
         let result = auth
-            .can_view_user_data(&user_watching, &user_watched)
+            .can_view_user_data(&user_watching, &user_watched, WatchScope::Weight)
             .await;
 
         assert!(result.is_ok());
@@ -164,7 +185,7 @@ mod tests {
         let auth = UserViewAuthorization::new(repo);
 
         let result = auth
-            .can_view_user_data(&user_watching, &user_watched)
+            .can_view_user_data(&user_watching, &user_watched, WatchScope::Weight)
             .await;
 
         assert!(result.is_ok());
@@ -179,7 +200,9 @@ mod tests {
         let repo = UserWatchPermissionRepository::new(db);
         let auth = UserViewAuthorization::new(repo);
 
-        let result = auth.verify_view_permission(&user_id, &user_id).await;
+        let result = auth
+            .verify_view_permission(&user_id, &user_id, WatchScope::Gym)
+            .await;
 
         assert!(result.is_ok());
     }
@@ -197,10 +220,60 @@ mod tests {
         let auth = UserViewAuthorization::new(repo);
 
         let result = auth
-            .verify_view_permission(&user_watching, &user_watched)
+            .verify_view_permission(&user_watching, &user_watched, WatchScope::Gym)
             .await;
 
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), ViewAuthorizationError::Forbidden);
     }
+
+    #[tokio::test]
+    async fn test_cannot_view_without_scope() {
+        let user_watching = Uuid::new_v4();
+        let user_watched = Uuid::new_v4();
+        let permission = user_watch_permissions::Model {
+            can_view_meals: false,
+            ..create_mock_permission(user_watched, user_watching)
+        };
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![permission.clone()], vec![permission]])
+            .into_connection();
+
+        let repo = UserWatchPermissionRepository::new(db);
+        let auth = UserViewAuthorization::new(repo);
+
+        let meals = auth
+            .can_view_user_data(&user_watching, &user_watched, WatchScope::Meals)
+            .await;
+        assert!(!meals.unwrap());
+
+        let weight = auth
+            .can_view_user_data(&user_watching, &user_watched, WatchScope::Weight)
+            .await;
+        assert!(weight.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_cannot_view_with_expired_permission() {
+        let user_watching = Uuid::new_v4();
+        let user_watched = Uuid::new_v4();
+        let permission = user_watch_permissions::Model {
+            expires_at: Some((Utc::now() - Duration::days(1)).into()),
+            ..create_mock_permission(user_watched, user_watching)
+        };
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![permission]])
+            .into_connection();
+
+        let repo = UserWatchPermissionRepository::new(db);
+        let auth = UserViewAuthorization::new(repo);
+
+        let result = auth
+            .verify_view_permission(&user_watching, &user_watched, WatchScope::Weight)
+            .await;
+
+        assert_eq!(result.unwrap_err(), ViewAuthorizationError::Forbidden);
+    }
 }
@@ -1,3 +1,4 @@
+pub mod rate_limit;
 pub mod router;
 pub mod server;
 pub mod state;
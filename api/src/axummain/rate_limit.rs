@@ -0,0 +1,232 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    Json,
+    body::{Body, to_bytes},
+    extract::{FromRequestParts, Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use entities::env_loader::{RateLimitRule, RateLimitSettings, RouteRateLimit};
+use redis::aio::ConnectionManager;
+use serde_json::json;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{
+    auth::{client_info::ClientInfo, jwt::validate_token, middleware::extract_token_from_headers},
+    axummain::state::AppState,
+};
+
+/// Bodies of the rate limited routes are small JSON documents
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// Routes protected by the rate limiter, each one has its own counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitedRoute {
+    Login,
+    Register,
+    RegisterGuest,
+    ForgotPassword,
+    MagicLink,
+    SearchUsers,
+}
+
+impl RateLimitedRoute {
+    fn name(self) -> &'static str {
+        match self {
+            RateLimitedRoute::Login => "login",
+            RateLimitedRoute::Register => "register",
+            RateLimitedRoute::RegisterGuest => "register_guest",
+            RateLimitedRoute::ForgotPassword => "forgot_password",
+            RateLimitedRoute::MagicLink => "magic_link",
+            RateLimitedRoute::SearchUsers => "search_users",
+        }
+    }
+
+    fn limits(self, settings: &RateLimitSettings) -> &RouteRateLimit {
+        match self {
+            RateLimitedRoute::Login => &settings.login,
+            RateLimitedRoute::Register => &settings.register,
+            RateLimitedRoute::RegisterGuest => &settings.register_guest,
+            RateLimitedRoute::ForgotPassword => &settings.forgot_password,
+            RateLimitedRoute::MagicLink => &settings.magic_link,
+            RateLimitedRoute::SearchUsers => &settings.search_users,
+        }
+    }
+}
+
+/// Sliding window rate limiting, applied with `middleware::from_fn_with_state`
+///
+/// Requests are counted by client IP and by targeted account (the email sent in the
+/// body, or the authenticated user for the search). A limited request gets a 429 with
+/// a `Retry-After` header. Redis errors never block a request.
+pub async fn rate_limit(
+    State((state, route)): State<(AppState, RateLimitedRoute)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let settings = state.rate_limits.clone();
+    if !settings.enabled {
+        return next.run(request).await;
+    }
+
+    let limits = route.limits(&settings);
+    let (mut parts, body) = request.into_parts();
+    let Ok(client) = ClientInfo::from_request_parts(&mut parts, &state).await;
+
+    // The account is only known from the body for the auth routes, so it has to be
+    // buffered and put back for the handler
+    let (account, body) = match route {
+        RateLimitedRoute::Login | RateLimitedRoute::Register => {
+            let bytes = match to_bytes(body, MAX_BODY_SIZE).await {
+                Ok(bytes) => bytes,
+                Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+            };
+            (email_from_body(&bytes, Some("user")), Body::from(bytes))
+        }
+        RateLimitedRoute::ForgotPassword | RateLimitedRoute::MagicLink => {
+            let bytes = match to_bytes(body, MAX_BODY_SIZE).await {
+                Ok(bytes) => bytes,
+                Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+            };
+            (email_from_body(&bytes, None), Body::from(bytes))
+        }
+        RateLimitedRoute::SearchUsers => (
+            extract_token_from_headers(&parts.headers)
+                .and_then(|token| validate_token(token, &state.jwt_secret).ok())
+                .map(|claims| claims.sub),
+            body,
+        ),
+        RateLimitedRoute::RegisterGuest => (None, body),
+    };
+
+    let checks = [
+        limits.per_ip.zip(
+            client
+                .ip
+                .map(|ip| format!("rate_limit:{}:ip:{}", route.name(), ip)),
+        ),
+        limits
+            .per_account
+            .zip(account.map(|account| format!("rate_limit:{}:account:{}", route.name(), account))),
+    ];
+
+    for (rule, key) in checks.into_iter().flatten() {
+        match hit(&state.redis, &key, rule).await {
+            Ok(None) => {}
+            Ok(Some(retry_after)) => {
+                warn!("Rate limit reached for {}", key);
+                return too_many_requests(retry_after);
+            }
+            Err(err) => error!("Failed to check rate limit for {}: {}", key, err),
+        }
+    }
+
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// Read the email of the request body, nested under `wrapper` when given
+fn email_from_body(bytes: &[u8], wrapper: Option<&str>) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_slice(bytes).ok()?;
+    let value = match wrapper {
+        Some(wrapper) => value.get(wrapper)?,
+        None => &value,
+    };
+
+    let email = value.get("email")?.as_str()?.trim().to_lowercase();
+    (!email.is_empty()).then_some(email)
+}
+
+/// Record a request in the window of `key`
+///
+/// Returns the number of seconds to wait when the request is over the limit, in which
+/// case it is not counted.
+async fn hit(
+    redis: &ConnectionManager,
+    key: &str,
+    rule: RateLimitRule,
+) -> Result<Option<u64>, redis::RedisError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let window_ms = rule.window_secs * 1000;
+    let member = format!("{}-{}", now, Uuid::new_v4());
+
+    let mut con = redis.clone();
+    let (count,): (u64,) = redis::pipe()
+        .atomic()
+        .zrembyscore(key, 0, now.saturating_sub(window_ms))
+        .ignore()
+        .zadd(key, &member, now)
+        .ignore()
+        .zcard(key)
+        .expire(key, rule.window_secs as i64)
+        .ignore()
+        .query_async(&mut con)
+        .await?;
+
+    let max_requests = u64::from(rule.max_requests);
+    if count <= max_requests {
+        return Ok(None);
+    }
+
+    redis::cmd("ZREM")
+        .arg(key)
+        .arg(&member)
+        .query_async::<()>(&mut con)
+        .await?;
+
+    // A slot is freed when the request allowing the window to hold one more leaves it
+    let index = (count - 1 - max_requests) as isize;
+    let freed: Vec<(String, f64)> = redis::cmd("ZRANGE")
+        .arg(key)
+        .arg(index)
+        .arg(index)
+        .arg("WITHSCORES")
+        .query_async(&mut con)
+        .await?;
+
+    let retry_after_ms = freed
+        .first()
+        .map(|(_, score)| (*score as u64 + window_ms).saturating_sub(now))
+        .unwrap_or(window_ms);
+
+    Ok(Some(retry_after_ms.div_ceil(1000).max(1)))
+}
+
+fn too_many_requests(retry_after: u64) -> Response {
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(json!({"error": "Too many requests, please try again later"})),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_from_body() {
+        let body = br#"{"user": {"email": " John@Example.com ", "password": "secret"}}"#;
+        assert_eq!(
+            email_from_body(body, Some("user")),
+            Some("john@example.com".to_string())
+        );
+        assert_eq!(email_from_body(body, None), None);
+
+        let body = br#"{"email": "john@example.com"}"#;
+        assert_eq!(
+            email_from_body(body, None),
+            Some("john@example.com".to_string())
+        );
+        assert_eq!(email_from_body(b"not json", None), None);
+    }
+}
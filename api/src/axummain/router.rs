@@ -1,4 +1,5 @@
 use axum::http::{HeaderValue, Method, header};
+use axum::middleware::from_fn_with_state;
 use axum::routing::{delete, post, put};
 use axum::{Router, routing::get};
 use tower_http::cors::CorsLayer;
@@ -6,36 +7,69 @@ use tower_http::set_header::SetResponseHeaderLayer;
 use tower_http::trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer};
 use tracing::Level;
 
+use crate::axummain::rate_limit::{RateLimitedRoute, rate_limit};
 use crate::axummain::state::AppState;
+use crate::handlers::account_deletion::{get_account_deletion, request_account_deletion};
 use crate::handlers::auth::{
     current_user, forgot_password, login, logout, refresh_token, register, register_guest,
-    reset_password, verify_email,
+    reset_password, upgrade_guest, verify_email,
+};
+use crate::handlers::data_export::{download_data_export, request_data_export};
+use crate::handlers::data_import::{
+    apply_data_import, create_data_import, get_data_import, get_data_imports,
 };
 use crate::handlers::food_item::{
-    create_food_item, delete_food_item, get_food_items, update_food_item,
+    create_food_item, delete_food_item, get_food_items, share_food_item, update_food_item,
 };
 use crate::handlers::gym::{
-    create_gym_exercise, create_gym_session, create_gym_set, delete_gym_exercise,
-    delete_gym_session, delete_gym_set, get_gym_exercise, get_gym_exercises, get_gym_session,
-    get_gym_sessions, get_gym_sets, update_gym_exercise, update_gym_session, update_gym_set,
+    bulk_gym_sets, create_gym_exercise, create_gym_session, create_gym_set, delete_gym_exercise,
+    delete_gym_session, delete_gym_set, get_gym_exercise, get_gym_exercise_history,
+    get_gym_exercise_record_history, get_gym_exercise_records, get_gym_exercises, get_gym_session,
+    get_gym_session_details, get_gym_sessions, get_gym_sets, get_gym_volume,
+    get_other_user_gym_sessions, get_other_user_gym_sets, share_gym_exercise, update_gym_exercise,
+    update_gym_session, update_gym_set,
 };
+use crate::handlers::magic_link::{request_magic_link, verify_magic_link};
 use crate::handlers::meal::{
     add_meal_item, create_meal, delete_meal, delete_meal_item, get_meal_items, get_meals,
-    update_meal, update_meal_item,
+    get_other_user_meal_items, get_other_user_meals, update_meal, update_meal_item,
+};
+use crate::handlers::moderation::{
+    approve_food_item, approve_gym_exercise, get_pending_food_items, get_pending_gym_exercises,
+    reject_food_item, reject_gym_exercise,
 };
 use crate::handlers::server_health::server_health_check;
 use crate::handlers::settings::update_settings;
+use crate::handlers::training_program::{
+    create_training_program, delete_training_program, get_today_workout, get_training_program,
+    get_training_programs, update_training_program,
+};
+use crate::handlers::two_factor::{
+    confirm_two_factor, disable_two_factor, get_two_factor_status, regenerate_recovery_codes,
+    setup_two_factor, verify_two_factor,
+};
 use crate::handlers::user_group::{
     get_public_group_members, get_user_groups, join_public_group, leave_public_group,
 };
+use crate::handlers::user_info::get_other_user_infos;
 use crate::handlers::user_watch_permissions::{
     get_watchers, get_watching, grant_watch_permission, revoke_watch_permission, search_users,
+    update_watch_permission,
 };
 use crate::handlers::user_weight::{
     create_user_weight, delete_user_weight, get_other_user_last_weight,
     get_other_user_weight_infos, get_other_user_weights, get_user_last_weight,
     get_user_weight_infos, get_user_weights, update_user_weight,
 };
+use crate::handlers::watch_request::{
+    accept_watch_request, decline_watch_request, get_incoming_watch_requests,
+    get_outgoing_watch_requests, send_watch_request,
+};
+use crate::handlers::watching::get_watching_overview;
+use crate::handlers::workout_template::{
+    create_workout_template, delete_workout_template, get_workout_template, get_workout_templates,
+    start_workout_template, update_workout_template,
+};
 
 pub fn get_main_router(app_state: AppState) -> Router {
     // Configure CORS - adjust allowed origins for production
@@ -67,19 +101,55 @@ pub fn get_main_router(app_state: AppState) -> Router {
             },
         );
 
+    // Rate limiting of the sensitive routes, configured in the settings
+    let limited =
+        |route: RateLimitedRoute| from_fn_with_state((app_state.clone(), route), rate_limit);
+
     Router::new()
         // Health check route
         .route("/health", get(server_health_check))
         // Auth routes
-        .route("/api/users", post(register))
-        .route("/api/users/guest", post(register_guest))
-        .route("/api/users/login", post(login))
+        .route(
+            "/api/users",
+            post(register).route_layer(limited(RateLimitedRoute::Register)),
+        )
+        .route(
+            "/api/users/guest",
+            post(register_guest).route_layer(limited(RateLimitedRoute::RegisterGuest)),
+        )
+        .route("/api/users/guest/upgrade", post(upgrade_guest))
+        .route(
+            "/api/users/login",
+            post(login).route_layer(limited(RateLimitedRoute::Login)),
+        )
         .route("/api/user", get(current_user))
+        .route("/api/user/deletion", get(get_account_deletion))
+        .route("/api/user/deletion", post(request_account_deletion))
+        .route("/api/user/export", post(request_data_export))
+        .route("/api/user/export/{token}", get(download_data_export))
         .route("/api/auth/verify-email", get(verify_email))
-        .route("/api/auth/forgot-password", post(forgot_password))
+        .route(
+            "/api/auth/forgot-password",
+            post(forgot_password).route_layer(limited(RateLimitedRoute::ForgotPassword)),
+        )
+        .route(
+            "/api/auth/magic-link",
+            post(request_magic_link).route_layer(limited(RateLimitedRoute::MagicLink)),
+        )
+        .route("/api/auth/magic-link/verify", post(verify_magic_link))
         .route("/api/auth/reset-password", post(reset_password))
         .route("/api/auth/refresh-token", post(refresh_token))
         .route("/api/auth/logout", post(logout))
+        // Two-factor authentication routes
+        .route("/api/auth/2fa", get(get_two_factor_status))
+        .route("/api/auth/2fa/setup", post(setup_two_factor))
+        .route("/api/auth/2fa/confirm", post(confirm_two_factor))
+        .route("/api/auth/2fa/verify", post(verify_two_factor))
+        .route("/api/auth/2fa/disable", post(disable_two_factor))
+        .route(
+            "/api/auth/2fa/recovery-codes",
+            post(regenerate_recovery_codes),
+        )
         // User weight routes
         .route("/api/user/weights", post(create_user_weight))
         .route("/api/user/weights", get(get_user_weights))
@@ -97,11 +167,27 @@ pub fn get_main_router(app_state: AppState) -> Router {
             "/api/users/{user_id}/weights/infos",
             get(get_other_user_weight_infos),
         )
+        // View other user's meals, gym sessions and body infos (requires scoped watch permission)
+        .route("/api/users/{user_id}/meals", get(get_other_user_meals))
+        .route(
+            "/api/users/{user_id}/meals/{meal_id}/items",
+            get(get_other_user_meal_items),
+        )
+        .route(
+            "/api/users/{user_id}/gym/sessions",
+            get(get_other_user_gym_sessions),
+        )
+        .route(
+            "/api/users/{user_id}/gym/sessions/{session_id}/sets",
+            get(get_other_user_gym_sets),
+        )
+        .route("/api/users/{user_id}/infos", get(get_other_user_infos))
         // Food item routes
         .route("/api/food-items", post(create_food_item))
         .route("/api/food-items", get(get_food_items))
         .route("/api/food-items/{id}", put(update_food_item))
         .route("/api/food-items/{id}", delete(delete_food_item))
+        .route("/api/food-items/{id}/share", post(share_food_item))
         // Meal routes
         .route("/api/meals", post(create_meal))
         .route("/api/meals", get(get_meals))
@@ -127,29 +213,78 @@ pub fn get_main_router(app_state: AppState) -> Router {
         )
         .route("/api/user-groups/myself", get(get_user_groups))
         // User watch permissions routes
-        .route("/api/users/search", get(search_users))
+        .route(
+            "/api/users/search",
+            get(search_users).route_layer(limited(RateLimitedRoute::SearchUsers)),
+        )
         .route("/api/watch-permissions/watchers", get(get_watchers))
         .route("/api/watch-permissions/watching", get(get_watching))
         .route("/api/watch-permissions/grant", post(grant_watch_permission))
+        .route(
+            "/api/watch-permissions/update",
+            post(update_watch_permission),
+        )
         .route(
             "/api/watch-permissions/revoke",
             post(revoke_watch_permission),
         )
+        // Watch request routes
+        .route("/api/watch-requests", post(send_watch_request))
+        .route(
+            "/api/watch-requests/incoming",
+            get(get_incoming_watch_requests),
+        )
+        .route(
+            "/api/watch-requests/outgoing",
+            get(get_outgoing_watch_requests),
+        )
+        .route(
+            "/api/watch-requests/{id}/accept",
+            post(accept_watch_request),
+        )
+        .route(
+            "/api/watch-requests/{id}/decline",
+            post(decline_watch_request),
+        )
+        // Watching dashboard routes
+        .route("/api/watching/overview", get(get_watching_overview))
         // Gym exercise routes
         .route("/api/gym/exercises", post(create_gym_exercise))
         .route("/api/gym/exercises", get(get_gym_exercises))
         .route("/api/gym/exercises/{id}", get(get_gym_exercise))
         .route("/api/gym/exercises/{id}", put(update_gym_exercise))
         .route("/api/gym/exercises/{id}", delete(delete_gym_exercise))
+        .route("/api/gym/exercises/{id}/share", post(share_gym_exercise))
+        .route(
+            "/api/gym/exercises/{id}/records",
+            get(get_gym_exercise_records),
+        )
+        .route(
+            "/api/gym/exercises/{id}/records/history",
+            get(get_gym_exercise_record_history),
+        )
+        .route(
+            "/api/gym/exercises/{id}/history",
+            get(get_gym_exercise_history),
+        )
+        .route("/api/gym/volume", get(get_gym_volume))
         // Gym session routes
         .route("/api/gym/sessions", post(create_gym_session))
         .route("/api/gym/sessions", get(get_gym_sessions))
         .route("/api/gym/sessions/{id}", get(get_gym_session))
+        .route(
+            "/api/gym/sessions/{id}/details",
+            get(get_gym_session_details),
+        )
         .route("/api/gym/sessions/{id}", put(update_gym_session))
         .route("/api/gym/sessions/{id}", delete(delete_gym_session))
         // Gym set routes
         .route("/api/gym/sessions/{session_id}/sets", post(create_gym_set))
         .route("/api/gym/sessions/{session_id}/sets", get(get_gym_sets))
+        .route(
+            "/api/gym/sessions/{session_id}/sets/bulk",
+            post(bulk_gym_sets),
+        )
         .route(
             "/api/gym/sessions/{session_id}/sets/{set_id}",
             put(update_gym_set),
@@ -158,6 +293,47 @@ pub fn get_main_router(app_state: AppState) -> Router {
             "/api/gym/sessions/{session_id}/sets/{set_id}",
             delete(delete_gym_set),
         )
+        // Workout template routes
+        .route("/api/gym/templates", post(create_workout_template))
+        .route("/api/gym/templates", get(get_workout_templates))
+        .route("/api/gym/templates/{id}", get(get_workout_template))
+        .route("/api/gym/templates/{id}", put(update_workout_template))
+        .route("/api/gym/templates/{id}", delete(delete_workout_template))
+        .route(
+            "/api/gym/templates/{id}/start",
+            post(start_workout_template),
+        )
+        // Training program routes
+        .route("/api/gym/programs", post(create_training_program))
+        .route("/api/gym/programs", get(get_training_programs))
+        .route("/api/gym/programs/today", get(get_today_workout))
+        .route("/api/gym/programs/{id}", get(get_training_program))
+        .route("/api/gym/programs/{id}", put(update_training_program))
+        .route("/api/gym/programs/{id}", delete(delete_training_program))
+        // Moderation routes
+        .route("/api/moderation/exercises", get(get_pending_gym_exercises))
+        .route(
+            "/api/moderation/exercises/{id}/approve",
+            post(approve_gym_exercise),
+        )
+        .route(
+            "/api/moderation/exercises/{id}/reject",
+            post(reject_gym_exercise),
+        )
+        .route("/api/moderation/food-items", get(get_pending_food_items))
+        .route(
+            "/api/moderation/food-items/{id}/approve",
+            post(approve_food_item),
+        )
+        .route(
+            "/api/moderation/food-items/{id}/reject",
+            post(reject_food_item),
+        )
+        // Import routes
+        .route("/api/imports", post(create_data_import))
+        .route("/api/imports", get(get_data_imports))
+        .route("/api/imports/{id}", get(get_data_import))
+        .route("/api/imports/{id}/apply", post(apply_data_import))
         // Settings routes
         .route("/api/settings", put(update_settings))
         // Set application state
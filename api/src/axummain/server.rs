@@ -1,3 +1,5 @@
+use std::net::SocketAddr;
+
 use entities::env_loader::Settings;
 use tokio::signal;
 use tracing::{error, info, warn};
@@ -54,9 +56,13 @@ pub async fn axum_main() {
     );
 
     // Graceful shutdown with signal handling
-    if let Err(e) = axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
+    // The client address is needed by the rate limiter
+    if let Err(e) = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
     {
         error!(error = %e, "Server error");
     }
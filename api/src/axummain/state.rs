@@ -1,8 +1,15 @@
 use std::sync::Arc;
 
-use crate::{jobs::Jobs, repositories::Repositories, services::Services};
+use crate::{
+    auth::{client_info::TrustedProxies, password_policy::PasswordPolicy},
+    jobs::Jobs,
+    repositories::Repositories,
+    services::Services,
+};
 use axum::extract::FromRef;
-use entities::env_loader::Settings;
+use entities::env_loader::{
+    AccountDeletionSettings, LoginSecuritySettings, PasswordSettings, RateLimitSettings, Settings,
+};
 use migration::{Migrator, MigratorTrait};
 use redis::{RedisError, aio::ConnectionManager};
 use sea_orm::{ConnectOptions, Database, DatabaseConnection};
@@ -18,6 +25,11 @@ pub struct AppState {
     pub jobs: Arc<Jobs>,
 
     pub jwt_secret: String,
+    pub trusted_proxies: Arc<TrustedProxies>,
+    pub rate_limits: Arc<RateLimitSettings>,
+    pub login_security: Arc<LoginSecuritySettings>,
+    pub password_policy: Arc<PasswordPolicy>,
+    pub account_deletion: Arc<AccountDeletionSettings>,
 }
 
 impl AppState {
@@ -38,15 +50,38 @@ impl AppState {
         let redis = get_redis_connection(&settings.redis_url).await?;
 
         let jwt_secret = settings.jwt_secret.clone();
+        let trusted_proxies = TrustedProxies::parse(&settings.trusted_proxies)?;
 
-        AppState::new(db, redis, jwt_secret).await
+        let rate_limits = settings.rate_limits.clone();
+        let login_security = settings.login_security.clone();
+        let passwords = settings.passwords.clone();
+        let account_deletion = settings.account_deletion.clone();
+
+        let mut state = AppState::new(
+            db,
+            redis,
+            jwt_secret,
+            rate_limits,
+            login_security,
+            passwords,
+            account_deletion,
+        )
+        .await?;
+        state.trusted_proxies = Arc::new(trusted_proxies);
+
+        Ok(state)
     }
 
     pub async fn new(
         db: DatabaseConnection,
         redis: ConnectionManager,
         jwt_secret: String,
+        rate_limits: RateLimitSettings,
+        login_security: LoginSecuritySettings,
+        passwords: PasswordSettings,
+        account_deletion: AccountDeletionSettings,
     ) -> anyhow::Result<Self> {
+        let password_policy = Arc::new(PasswordPolicy::from_settings(&passwords)?);
         let jobs = Arc::new(Jobs::new(redis.clone()));
         let repositories = Arc::new(Repositories::new(db.clone()));
         let services = Arc::new(Services::new(db.clone()));
@@ -60,6 +95,12 @@ impl AppState {
             services,
             jobs,
             jwt_secret,
+            // Nobody is trusted to set the client address unless configured
+            trusted_proxies: Default::default(),
+            rate_limits: Arc::new(rate_limits),
+            login_security: Arc::new(login_security),
+            password_policy,
+            account_deletion: Arc::new(account_deletion),
         })
     }
 }
@@ -0,0 +1,372 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{NaiveDate, Weekday};
+use entities::{
+    exercise_muscle, gym_set,
+    sea_orm_active_enums::{MuscleEnum, MuscleRoleEnum, OneRepMaxFormulaEnum},
+};
+use sea_orm::{Iterable, prelude::Decimal};
+use uuid::Uuid;
+
+use crate::schemas::gym_schemas::{
+    ExerciseHistoryResponse, GymExerciseResponse, GymSetResponse, MuscleVolumeResponse,
+    SessionExerciseResponse, WeeklyVolumeResponse,
+};
+
+/// Summary of every session of an exercise, `sets` being sorted chronologically and
+/// grouped by session. Only working sets are counted.
+pub fn exercise_history(
+    sets: Vec<(NaiveDate, gym_set::Model)>,
+    formula: &OneRepMaxFormulaEnum,
+) -> Vec<ExerciseHistoryResponse> {
+    sets.chunk_by(|(_, a), (_, b)| a.session_id == b.session_id)
+        .filter_map(|session| {
+            let done: Vec<&gym_set::Model> = session
+                .iter()
+                .map(|(_, set)| set)
+                .filter(|set| set.is_working_set())
+                .collect();
+            // Heaviest set, the one with the most repetitions among equals
+            let top_set = done
+                .iter()
+                .max_by_key(|set| (set.weight_kg, set.repetitions))?;
+
+            Some(ExerciseHistoryResponse {
+                session_id: top_set.session_id,
+                date: session[0].0,
+                top_set: GymSetResponse::with_formula((*top_set).clone(), formula),
+                total_sets: done.len() as i64,
+                total_reps: done.iter().map(|set| set.repetitions as i64).sum(),
+                total_volume: done.iter().map(|set| set.volume()).sum(),
+                estimated_one_rep_max: done
+                    .iter()
+                    .filter_map(|set| set.estimated_one_rep_max(formula))
+                    .max(),
+            })
+        })
+        .collect()
+}
+
+/// Sets of a session grouped by exercise, in the order the exercises were first logged.
+/// Totals only count working sets and are compared with `previous`, the working sets
+/// of the previous session of each exercise.
+pub fn session_exercises(
+    date: NaiveDate,
+    sets: Vec<gym_set::Model>,
+    exercises: Vec<GymExerciseResponse>,
+    previous: Vec<(NaiveDate, gym_set::Model)>,
+    formula: &OneRepMaxFormulaEnum,
+) -> Vec<SessionExerciseResponse> {
+    let mut sets_by_exercise: HashMap<Uuid, Vec<gym_set::Model>> = HashMap::new();
+    for set in sets {
+        sets_by_exercise
+            .entry(set.exercise_id)
+            .or_default()
+            .push(set);
+    }
+    let mut previous_by_exercise: HashMap<Uuid, Vec<(NaiveDate, gym_set::Model)>> = HashMap::new();
+    for (date, set) in previous {
+        previous_by_exercise
+            .entry(set.exercise_id)
+            .or_default()
+            .push((date, set));
+    }
+
+    let mut responses: Vec<_> = exercises
+        .into_iter()
+        .filter_map(|exercise| {
+            let mut sets = sets_by_exercise.remove(&exercise.id)?;
+            sets.sort_by_key(|set| (set.set_number, set.created_at));
+            let first_logged = sets.iter().map(|set| set.created_at).min()?;
+
+            let current = exercise_history(
+                sets.iter().map(|set| (date, set.clone())).collect(),
+                formula,
+            )
+            .pop();
+            let previous = previous_by_exercise
+                .remove(&exercise.id)
+                .and_then(|sets| exercise_history(sets, formula).pop());
+            let total_volume = current
+                .as_ref()
+                .map_or(Decimal::ZERO, |current| current.total_volume);
+            let estimated_one_rep_max = current
+                .as_ref()
+                .and_then(|current| current.estimated_one_rep_max);
+
+            let response = SessionExerciseResponse {
+                exercise_id: exercise.id,
+                exercise_name: exercise.name,
+                primary_muscles: exercise.primary_muscles,
+                secondary_muscles: exercise.secondary_muscles,
+                sets: sets
+                    .into_iter()
+                    .map(|set| GymSetResponse::with_formula(set, formula))
+                    .collect(),
+                total_sets: current.as_ref().map_or(0, |current| current.total_sets),
+                total_reps: current.as_ref().map_or(0, |current| current.total_reps),
+                total_volume,
+                estimated_one_rep_max,
+                volume_change: previous
+                    .as_ref()
+                    .map(|previous| total_volume - previous.total_volume),
+                one_rep_max_change: estimated_one_rep_max
+                    .zip(previous.as_ref().and_then(|p| p.estimated_one_rep_max))
+                    .map(|(current, previous)| current - previous),
+                previous,
+            };
+            Some((first_logged, response))
+        })
+        .collect();
+
+    responses.sort_by_key(|(first_logged, _)| *first_logged);
+    responses
+        .into_iter()
+        .map(|(_, response)| response)
+        .collect()
+}
+
+/// Sets and volume per muscle for each week (starting on Monday), oldest first.
+/// A working set counts fully for the primary muscles of its exercise and half for
+/// the secondary ones.
+pub fn weekly_muscle_volume(
+    sets: &[(NaiveDate, gym_set::Model)],
+    muscles: Vec<exercise_muscle::Model>,
+) -> Vec<WeeklyVolumeResponse> {
+    let mut muscles_by_exercise: HashMap<Uuid, Vec<exercise_muscle::Model>> = HashMap::new();
+    for muscle in muscles {
+        muscles_by_exercise
+            .entry(muscle.exercise_id)
+            .or_default()
+            .push(muscle);
+    }
+
+    let mut weeks: BTreeMap<NaiveDate, Vec<MuscleVolumeResponse>> = BTreeMap::new();
+    for (date, set) in sets.iter().filter(|(_, set)| set.is_working_set()) {
+        let Some(muscles) = muscles_by_exercise.get(&set.exercise_id) else {
+            continue;
+        };
+        let week = weeks
+            .entry(date.week(Weekday::Mon).first_day())
+            .or_default();
+
+        for muscle in muscles {
+            let share = match muscle.role {
+                MuscleRoleEnum::Primary => Decimal::ONE,
+                MuscleRoleEnum::Secondary => Decimal::new(5, 1),
+            };
+            match week
+                .iter_mut()
+                .find(|volume| volume.muscle == muscle.muscle)
+            {
+                Some(volume) => {
+                    volume.sets += share;
+                    volume.volume += set.volume() * share;
+                }
+                None => week.push(MuscleVolumeResponse {
+                    muscle: muscle.muscle.clone(),
+                    sets: share,
+                    volume: set.volume() * share,
+                }),
+            }
+        }
+    }
+
+    weeks
+        .into_iter()
+        .map(|(week_start, mut muscles)| {
+            muscles.sort_by_key(|volume| MuscleEnum::iter().position(|m| m == volume.muscle));
+            WeeklyVolumeResponse {
+                week_start,
+                muscles,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use entities::sea_orm_active_enums::{SetTypeEnum, VisibilityEnum};
+
+    fn set(
+        session_id: Uuid,
+        exercise_id: Uuid,
+        repetitions: i32,
+        weight_kg: i64,
+    ) -> gym_set::Model {
+        let now = Utc::now().fixed_offset();
+        gym_set::Model {
+            id: Uuid::new_v4(),
+            session_id,
+            exercise_id,
+            set_number: 1,
+            repetitions,
+            weight_kg: Decimal::from(weight_kg),
+            set_type: SetTypeEnum::Normal,
+            rpe: None,
+            rir: None,
+            rest_seconds: None,
+            duration_seconds: None,
+            distance_meters: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn muscle(
+        exercise_id: Uuid,
+        muscle: MuscleEnum,
+        role: MuscleRoleEnum,
+    ) -> exercise_muscle::Model {
+        let now = Utc::now().fixed_offset();
+        exercise_muscle::Model {
+            id: Uuid::new_v4(),
+            exercise_id,
+            muscle,
+            role,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    #[test]
+    fn test_exercise_history() {
+        let exercise = Uuid::new_v4();
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let skipped = Uuid::new_v4();
+        let sets = vec![
+            (
+                day(1),
+                gym_set::Model {
+                    set_type: SetTypeEnum::WarmUp,
+                    ..set(first, exercise, 10, 20)
+                },
+            ),
+            (day(1), set(first, exercise, 10, 60)),
+            (day(1), set(first, exercise, 5, 70)),
+            (day(1), set(first, exercise, 8, 70)),
+            (day(3), set(skipped, exercise, 0, 80)),
+            (day(8), set(second, exercise, 12, 50)),
+        ];
+
+        let history = exercise_history(sets, &OneRepMaxFormulaEnum::Epley);
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].date, day(1));
+        assert_eq!(history[0].top_set.weight_kg, Decimal::from(70));
+        assert_eq!(history[0].top_set.repetitions, 8);
+        assert_eq!(history[0].total_sets, 3);
+        assert_eq!(history[0].total_reps, 23);
+        assert_eq!(history[0].total_volume, Decimal::from(1510));
+        // 70 kg x 8
+        assert_eq!(
+            history[0].estimated_one_rep_max,
+            Some(Decimal::new(8867, 2))
+        );
+        assert_eq!(history[1].session_id, second);
+        assert_eq!(history[1].total_volume, Decimal::from(600));
+    }
+
+    #[test]
+    fn test_weekly_muscle_volume() {
+        let bench = Uuid::new_v4();
+        let row = Uuid::new_v4();
+        let session = Uuid::new_v4();
+        let muscles = vec![
+            muscle(bench, MuscleEnum::Chest, MuscleRoleEnum::Primary),
+            muscle(bench, MuscleEnum::Triceps, MuscleRoleEnum::Secondary),
+            muscle(row, MuscleEnum::Triceps, MuscleRoleEnum::Primary),
+        ];
+        let sets = vec![
+            // Monday and Sunday of the same week
+            (day(1), set(session, bench, 10, 60)),
+            (day(7), set(session, bench, 10, 60)),
+            (day(7), set(session, row, 10, 40)),
+            (day(8), set(session, bench, 10, 60)),
+        ];
+
+        let weeks = weekly_muscle_volume(&sets, muscles);
+
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].week_start, day(1));
+        let chest = weeks[0]
+            .muscles
+            .iter()
+            .find(|volume| volume.muscle == MuscleEnum::Chest)
+            .unwrap();
+        assert_eq!(chest.sets, Decimal::from(2));
+        assert_eq!(chest.volume, Decimal::from(1200));
+        let triceps = weeks[0]
+            .muscles
+            .iter()
+            .find(|volume| volume.muscle == MuscleEnum::Triceps)
+            .unwrap();
+        assert_eq!(triceps.sets, Decimal::new(20, 1));
+        assert_eq!(triceps.volume, Decimal::from(1000));
+        assert_eq!(weeks[1].week_start, day(8));
+        assert_eq!(weeks[1].muscles.len(), 2);
+    }
+
+    #[test]
+    fn test_session_exercises() {
+        let bench = Uuid::new_v4();
+        let row = Uuid::new_v4();
+        let session = Uuid::new_v4();
+        let previous_session = Uuid::new_v4();
+        let exercise = |id, name: &str| GymExerciseResponse {
+            id,
+            name: name.to_string(),
+            description: None,
+            equipment: None,
+            movement_pattern: None,
+            is_unilateral: false,
+            visibility: VisibilityEnum::Private,
+            added_by: Uuid::nil(),
+            primary_muscles: vec![MuscleEnum::Chest],
+            secondary_muscles: Vec::new(),
+        };
+
+        let mut first_row = set(session, row, 10, 40);
+        first_row.created_at -= chrono::Duration::minutes(5);
+        let mut second_bench = set(session, bench, 8, 65);
+        second_bench.set_number = 2;
+        let first_bench = set(session, bench, 10, 60);
+        let sets = vec![first_row, second_bench, first_bench];
+        let previous = vec![
+            (day(1), set(previous_session, bench, 10, 60)),
+            (day(1), set(previous_session, bench, 10, 60)),
+        ];
+
+        let exercises = session_exercises(
+            day(8),
+            sets,
+            vec![exercise(bench, "Bench"), exercise(row, "Row")],
+            previous,
+            &OneRepMaxFormulaEnum::Epley,
+        );
+
+        // The row was logged first
+        assert_eq!(exercises.len(), 2);
+        assert_eq!(exercises[0].exercise_name, "Row");
+        assert!(exercises[0].previous.is_none());
+        assert!(exercises[0].volume_change.is_none());
+
+        let bench = &exercises[1];
+        assert_eq!(bench.sets[0].set_number, 1);
+        assert_eq!(bench.sets[1].set_number, 2);
+        assert_eq!(bench.total_sets, 2);
+        assert_eq!(bench.total_reps, 18);
+        assert_eq!(bench.total_volume, Decimal::from(1120));
+        assert_eq!(bench.previous.as_ref().unwrap().date, day(1));
+        assert_eq!(bench.volume_change, Some(Decimal::from(-80)));
+        // 65 kg x 8 against 60 kg x 10
+        assert_eq!(bench.one_rep_max_change, Some(Decimal::new(233, 2)));
+    }
+}
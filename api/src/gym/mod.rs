@@ -0,0 +1,4 @@
+pub mod analytics;
+pub mod programs;
+pub mod sets;
+pub mod templates;
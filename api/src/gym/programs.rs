@@ -0,0 +1,262 @@
+use chrono::{Datelike, NaiveDate};
+use entities::{gym_set, training_program, training_program_day, workout_template_exercise};
+use sea_orm::prelude::Decimal;
+
+/// Targets of an exercise for the next session of a program
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseTarget {
+    pub sets: i32,
+    pub repetitions: i32,
+    pub weight_kg: Decimal,
+    /// Sessions in a row that missed the targets since the last change of weight
+    pub consecutive_failures: i32,
+    /// Whether the weight was just lowered after too many failures
+    pub is_deload: bool,
+}
+
+/// Week of the program (starting at 1) on `date`, `None` outside of the program
+pub fn program_week(program: &training_program::Model, date: NaiveDate) -> Option<i32> {
+    let days = (date - program.start_date).num_days();
+    if days < 0 {
+        return None;
+    }
+    let week = (days / 7 + 1) as i32;
+    (week <= program.weeks).then_some(week)
+}
+
+/// Days of the program scheduled on `date`
+pub fn scheduled_days<'a>(
+    program: &training_program::Model,
+    days: &'a [training_program_day::Model],
+    date: NaiveDate,
+) -> Vec<&'a training_program_day::Model> {
+    let Some(week) = program_week(program, date) else {
+        return Vec::new();
+    };
+    let weekday = date.weekday().number_from_monday() as i32;
+
+    days.iter()
+        .filter(|day| day.weekday == weekday && day.week.is_none_or(|w| w == week))
+        .collect()
+}
+
+/// Replays the progression rules of the program over the sessions of the exercise,
+/// `history` being sorted chronologically and grouped by session.
+///
+/// A session is completed when at least the target number of working sets reached the
+/// target repetitions at the target weight: the weight then goes up by the program
+/// increment. After too many sessions missing it in a row, the weight is lowered by the
+/// deload percentage. Without a target weight in the template, the heaviest set of the
+/// first session is the starting point.
+pub fn next_target(
+    program: &training_program::Model,
+    exercise: &workout_template_exercise::Model,
+    history: &[(NaiveDate, gym_set::Model)],
+) -> ExerciseTarget {
+    let mut weight_kg = exercise.target_weight_kg;
+    let mut consecutive_failures = 0;
+    let mut is_deload = false;
+
+    for session in history.chunk_by(|(_, a), (_, b)| a.session_id == b.session_id) {
+        let working: Vec<&gym_set::Model> = session
+            .iter()
+            .map(|(_, set)| set)
+            .filter(|set| set.is_working_set())
+            .collect();
+        let Some(heaviest) = working.iter().map(|set| set.weight_kg).max() else {
+            continue;
+        };
+        let current = *weight_kg.get_or_insert(heaviest);
+
+        let completed_sets = working
+            .iter()
+            .filter(|set| {
+                set.weight_kg >= current && set.repetitions >= exercise.target_repetitions
+            })
+            .count() as i32;
+
+        is_deload = false;
+        if completed_sets >= exercise.target_sets {
+            weight_kg = Some(current + program.increment_kg);
+            consecutive_failures = 0;
+        } else {
+            consecutive_failures += 1;
+            if consecutive_failures >= program.failures_before_deload {
+                weight_kg = Some(
+                    (current * Decimal::from(100 - program.deload_percent) / Decimal::from(100))
+                        .round_dp(2),
+                );
+                consecutive_failures = 0;
+                is_deload = true;
+            }
+        }
+    }
+
+    ExerciseTarget {
+        sets: exercise.target_sets,
+        repetitions: exercise.target_repetitions,
+        weight_kg: weight_kg.unwrap_or(Decimal::ZERO),
+        consecutive_failures,
+        is_deload,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use entities::sea_orm_active_enums::SetTypeEnum;
+    use uuid::Uuid;
+
+    fn program() -> training_program::Model {
+        let now = Utc::now().fixed_offset();
+        training_program::Model {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            name: "Linear".to_string(),
+            description: None,
+            // A Monday
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            weeks: 4,
+            increment_kg: Decimal::new(25, 1),
+            failures_before_deload: 2,
+            deload_percent: 10,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn day(week: Option<i32>, weekday: i32) -> training_program_day::Model {
+        let now = Utc::now().fixed_offset();
+        training_program_day::Model {
+            id: Uuid::new_v4(),
+            program_id: Uuid::nil(),
+            template_id: Uuid::new_v4(),
+            week,
+            weekday,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn exercise(target_weight_kg: Option<i64>) -> workout_template_exercise::Model {
+        let now = Utc::now().fixed_offset();
+        workout_template_exercise::Model {
+            id: Uuid::new_v4(),
+            template_id: Uuid::nil(),
+            exercise_id: Uuid::nil(),
+            position: 1,
+            target_sets: 3,
+            target_repetitions: 5,
+            target_weight_kg: target_weight_kg.map(Decimal::from),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// A session of sets at the same weight
+    fn session(
+        d: u32,
+        weight_kg: Decimal,
+        repetitions: &[i32],
+    ) -> Vec<(NaiveDate, gym_set::Model)> {
+        let now = Utc::now().fixed_offset();
+        let session_id = Uuid::new_v4();
+        repetitions
+            .iter()
+            .zip(1..)
+            .map(|(repetitions, set_number)| {
+                (
+                    NaiveDate::from_ymd_opt(2024, 1, d).unwrap(),
+                    gym_set::Model {
+                        id: Uuid::new_v4(),
+                        session_id,
+                        exercise_id: Uuid::nil(),
+                        set_number,
+                        repetitions: *repetitions,
+                        weight_kg,
+                        set_type: SetTypeEnum::Normal,
+                        rpe: None,
+                        rir: None,
+                        rest_seconds: None,
+                        duration_seconds: None,
+                        distance_meters: None,
+                        created_at: now,
+                        updated_at: now,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scheduled_days() {
+        let program = program();
+        let days = vec![day(None, 1), day(Some(2), 1), day(Some(2), 3)];
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+
+        assert_eq!(program_week(&program, date(1)), Some(1));
+        assert_eq!(program_week(&program, date(28)), Some(4));
+        assert_eq!(program_week(&program, date(29)), None);
+        assert_eq!(
+            program_week(&program, NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()),
+            None
+        );
+
+        assert_eq!(scheduled_days(&program, &days, date(1)).len(), 1);
+        assert_eq!(scheduled_days(&program, &days, date(8)).len(), 2);
+        assert_eq!(scheduled_days(&program, &days, date(10))[0].weekday, 3);
+        assert!(scheduled_days(&program, &days, date(2)).is_empty());
+        assert!(scheduled_days(&program, &days, date(29)).is_empty());
+    }
+
+    #[test]
+    fn test_next_target_progression() {
+        let program = program();
+        let exercise = exercise(Some(100));
+
+        let target = next_target(&program, &exercise, &[]);
+        assert_eq!(target.weight_kg, Decimal::from(100));
+
+        // All the repetitions were done twice
+        let history = [
+            session(1, Decimal::from(100), &[5, 5, 5]),
+            session(3, Decimal::new(1025, 1), &[5, 5, 5, 5]),
+        ]
+        .concat();
+        let target = next_target(&program, &exercise, &history);
+        assert_eq!(target.weight_kg, Decimal::from(105));
+        assert_eq!(target.consecutive_failures, 0);
+        assert!(!target.is_deload);
+
+        // Then missed once
+        let history = [history, session(5, Decimal::from(105), &[5, 4, 3])].concat();
+        let target = next_target(&program, &exercise, &history);
+        assert_eq!(target.weight_kg, Decimal::from(105));
+        assert_eq!(target.consecutive_failures, 1);
+
+        // And a second time, which deloads
+        let history = [history, session(8, Decimal::from(105), &[5, 5, 2])].concat();
+        let target = next_target(&program, &exercise, &history);
+        assert_eq!(target.weight_kg, Decimal::new(9450, 2));
+        assert_eq!(target.consecutive_failures, 0);
+        assert!(target.is_deload);
+    }
+
+    #[test]
+    fn test_next_target_without_target_weight() {
+        let program = program();
+        let exercise = exercise(None);
+
+        assert_eq!(
+            next_target(&program, &exercise, &[]).weight_kg,
+            Decimal::ZERO
+        );
+
+        let history = session(1, Decimal::from(60), &[5, 5, 5]);
+        assert_eq!(
+            next_target(&program, &exercise, &history).weight_kg,
+            Decimal::new(625, 1)
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::{HashMap, HashSet};
+
+use entities::gym_set;
+use uuid::Uuid;
+
+use crate::schemas::gym_schemas::BulkGymSetsRequest;
+
+/// A set of the session once the bulk changes are applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SetRef {
+    Existing(Uuid),
+    /// Index in the sets to create
+    Created(usize),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BulkSetsError {
+    /// Updated or deleted but not in the session
+    UnknownSet(Uuid),
+    /// Updated or deleted more than once
+    RepeatedSet(Uuid),
+    DuplicateNumber {
+        exercise_id: Uuid,
+        set_number: i32,
+    },
+}
+
+/// Number of every set left in the session after the changes, `existing` being its
+/// current sets. Without renumbering, two sets of an exercise can not share a number.
+/// Renumbering keeps the order of the numbers, existing sets before created ones on ties.
+pub fn number_sets(
+    existing: &[gym_set::Model],
+    request: &BulkGymSetsRequest,
+) -> Result<Vec<(SetRef, i32)>, BulkSetsError> {
+    let existing_ids: HashSet<Uuid> = existing.iter().map(|set| set.id).collect();
+    let mut touched: HashSet<Uuid> = HashSet::new();
+    for id in request
+        .update
+        .iter()
+        .map(|update| update.id)
+        .chain(request.delete.iter().copied())
+    {
+        if !existing_ids.contains(&id) {
+            return Err(BulkSetsError::UnknownSet(id));
+        }
+        if !touched.insert(id) {
+            return Err(BulkSetsError::RepeatedSet(id));
+        }
+    }
+
+    let new_numbers: HashMap<Uuid, i32> = request
+        .update
+        .iter()
+        .filter_map(|update| Some((update.id, update.changes.set_number?)))
+        .collect();
+
+    let mut kept: Vec<&gym_set::Model> = existing
+        .iter()
+        .filter(|set| !request.delete.contains(&set.id))
+        .collect();
+    kept.sort_by_key(|set| (set.set_number, set.created_at));
+
+    let mut sets: Vec<(SetRef, Uuid, i32)> = kept
+        .into_iter()
+        .map(|set| {
+            (
+                SetRef::Existing(set.id),
+                set.exercise_id,
+                new_numbers.get(&set.id).copied().unwrap_or(set.set_number),
+            )
+        })
+        .chain(
+            request
+                .create
+                .iter()
+                .enumerate()
+                .map(|(index, set)| (SetRef::Created(index), set.exercise_id, set.set_number)),
+        )
+        .collect();
+    // Stable, so ties keep the order above
+    sets.sort_by_key(|(_, _, set_number)| *set_number);
+
+    let mut numbers: HashMap<Uuid, i32> = HashMap::new();
+    let mut used: HashSet<(Uuid, i32)> = HashSet::new();
+    sets.into_iter()
+        .map(|(set, exercise_id, set_number)| {
+            if request.renumber {
+                let number = numbers.entry(exercise_id).or_insert(0);
+                *number += 1;
+                Ok((set, *number))
+            } else if used.insert((exercise_id, set_number)) {
+                Ok((set, set_number))
+            } else {
+                Err(BulkSetsError::DuplicateNumber {
+                    exercise_id,
+                    set_number,
+                })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::gym_schemas::{
+        BulkUpdateGymSetRequest, CreateGymSetRequest, UpdateGymSetRequest,
+    };
+    use chrono::Utc;
+    use entities::sea_orm_active_enums::SetTypeEnum;
+    use sea_orm::prelude::Decimal;
+
+    fn set(exercise_id: Uuid, set_number: i32) -> gym_set::Model {
+        let now = Utc::now().fixed_offset();
+        gym_set::Model {
+            id: Uuid::new_v4(),
+            session_id: Uuid::nil(),
+            exercise_id,
+            set_number,
+            repetitions: 10,
+            weight_kg: Decimal::from(60),
+            set_type: SetTypeEnum::Normal,
+            rpe: None,
+            rir: None,
+            rest_seconds: None,
+            duration_seconds: None,
+            distance_meters: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn create(exercise_id: Uuid, set_number: i32) -> CreateGymSetRequest {
+        CreateGymSetRequest {
+            exercise_id,
+            set_number,
+            repetitions: 10,
+            weight_kg: Decimal::from(60),
+            set_type: None,
+            rpe: None,
+            rir: None,
+            rest_seconds: None,
+            duration_seconds: None,
+            distance_meters: None,
+        }
+    }
+
+    fn renumber(id: Uuid, set_number: i32) -> BulkUpdateGymSetRequest {
+        BulkUpdateGymSetRequest {
+            id,
+            changes: UpdateGymSetRequest {
+                set_number: Some(set_number),
+                repetitions: None,
+                weight_kg: None,
+                set_type: None,
+                rpe: None,
+                rir: None,
+                rest_seconds: None,
+                duration_seconds: None,
+                distance_meters: None,
+            },
+        }
+    }
+
+    fn request(
+        create: Vec<CreateGymSetRequest>,
+        update: Vec<BulkUpdateGymSetRequest>,
+        delete: Vec<Uuid>,
+        renumber: bool,
+    ) -> BulkGymSetsRequest {
+        BulkGymSetsRequest {
+            create,
+            update,
+            delete,
+            renumber,
+        }
+    }
+
+    #[test]
+    fn test_number_sets_checks_sets_and_duplicates() {
+        let bench = Uuid::new_v4();
+        let row = Uuid::new_v4();
+        let existing = vec![set(bench, 1), set(bench, 2)];
+        let unknown = Uuid::new_v4();
+
+        assert_eq!(
+            number_sets(&existing, &request(vec![], vec![], vec![unknown], false)),
+            Err(BulkSetsError::UnknownSet(unknown))
+        );
+        assert_eq!(
+            number_sets(
+                &existing,
+                &request(
+                    vec![],
+                    vec![renumber(existing[0].id, 3)],
+                    vec![existing[0].id],
+                    false
+                )
+            ),
+            Err(BulkSetsError::RepeatedSet(existing[0].id))
+        );
+        assert_eq!(
+            number_sets(
+                &existing,
+                &request(vec![create(bench, 2)], vec![], vec![], false)
+            ),
+            Err(BulkSetsError::DuplicateNumber {
+                exercise_id: bench,
+                set_number: 2
+            })
+        );
+
+        // Numbers are per exercise, and freed by deleted or moved sets
+        let numbers = number_sets(
+            &existing,
+            &request(
+                vec![create(row, 1), create(bench, 2), create(bench, 3)],
+                vec![renumber(existing[1].id, 4)],
+                vec![existing[0].id],
+                false,
+            ),
+        )
+        .unwrap();
+        assert_eq!(numbers.len(), 4);
+        assert!(numbers.contains(&(SetRef::Existing(existing[1].id), 4)));
+        assert!(numbers.contains(&(SetRef::Created(0), 1)));
+    }
+
+    #[test]
+    fn test_number_sets_renumbers() {
+        let bench = Uuid::new_v4();
+        let row = Uuid::new_v4();
+        let existing = vec![set(bench, 1), set(bench, 3), set(row, 5)];
+
+        let numbers = number_sets(
+            &existing,
+            &request(
+                vec![create(bench, 3), create(bench, 2)],
+                vec![],
+                vec![existing[0].id],
+                true,
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(
+            numbers,
+            vec![
+                (SetRef::Created(1), 1),
+                (SetRef::Existing(existing[1].id), 2),
+                (SetRef::Created(0), 3),
+                (SetRef::Existing(existing[2].id), 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use entities::{gym_set, workout_template_exercise};
+use sea_orm::prelude::Decimal;
+use uuid::Uuid;
+
+/// A set created when starting a session from a template
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedSet {
+    pub exercise_id: Uuid,
+    pub set_number: i32,
+    pub repetitions: i32,
+    pub weight_kg: Decimal,
+}
+
+/// Sets of every template exercise, in the template order. `last_sets` are the sets of the
+/// last session of each exercise: when given, the n-th planned set repeats the n-th set done
+/// back then (or the last one when fewer were done) instead of the template targets.
+pub fn planned_sets(
+    exercises: &[workout_template_exercise::Model],
+    last_sets: &[gym_set::Model],
+) -> Vec<PlannedSet> {
+    let mut last_sets_by_exercise: HashMap<Uuid, Vec<&gym_set::Model>> = HashMap::new();
+    for set in last_sets {
+        last_sets_by_exercise
+            .entry(set.exercise_id)
+            .or_default()
+            .push(set);
+    }
+    for sets in last_sets_by_exercise.values_mut() {
+        sets.sort_by_key(|set| set.set_number);
+    }
+
+    // The same exercise can come back later in a template, its numbering goes on
+    let mut next_numbers: HashMap<Uuid, i32> = HashMap::new();
+    let mut planned = Vec::new();
+    for exercise in exercises {
+        let next_number = next_numbers.entry(exercise.exercise_id).or_insert(1);
+        let last_sets = last_sets_by_exercise
+            .get(&exercise.exercise_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        for index in 0..exercise.target_sets as usize {
+            let (repetitions, weight_kg) = match last_sets.get(index).or(last_sets.last()) {
+                Some(last) => (last.repetitions, last.weight_kg),
+                None => (
+                    exercise.target_repetitions,
+                    exercise.target_weight_kg.unwrap_or(Decimal::ZERO),
+                ),
+            };
+            planned.push(PlannedSet {
+                exercise_id: exercise.exercise_id,
+                set_number: *next_number,
+                repetitions,
+                weight_kg,
+            });
+            *next_number += 1;
+        }
+    }
+
+    planned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use entities::sea_orm_active_enums::SetTypeEnum;
+
+    fn template_exercise(
+        exercise_id: Uuid,
+        position: i32,
+        target_sets: i32,
+        target_weight_kg: Option<i64>,
+    ) -> workout_template_exercise::Model {
+        let now = Utc::now().fixed_offset();
+        workout_template_exercise::Model {
+            id: Uuid::new_v4(),
+            template_id: Uuid::nil(),
+            exercise_id,
+            position,
+            target_sets,
+            target_repetitions: 8,
+            target_weight_kg: target_weight_kg.map(Decimal::from),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn set(exercise_id: Uuid, set_number: i32, repetitions: i32, weight_kg: i64) -> gym_set::Model {
+        let now = Utc::now().fixed_offset();
+        gym_set::Model {
+            id: Uuid::new_v4(),
+            session_id: Uuid::nil(),
+            exercise_id,
+            set_number,
+            repetitions,
+            weight_kg: Decimal::from(weight_kg),
+            set_type: SetTypeEnum::Normal,
+            rpe: None,
+            rir: None,
+            rest_seconds: None,
+            duration_seconds: None,
+            distance_meters: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn summary(planned: &[PlannedSet]) -> Vec<(i32, i32, Decimal)> {
+        planned
+            .iter()
+            .map(|set| (set.set_number, set.repetitions, set.weight_kg))
+            .collect()
+    }
+
+    #[test]
+    fn test_planned_sets_from_targets() {
+        let squat = Uuid::new_v4();
+        let curl = Uuid::new_v4();
+        let exercises = vec![
+            template_exercise(squat, 1, 2, Some(100)),
+            template_exercise(curl, 2, 1, None),
+            template_exercise(squat, 3, 1, Some(80)),
+        ];
+
+        let planned = planned_sets(&exercises, &[]);
+
+        assert_eq!(planned.len(), 4);
+        assert_eq!(planned[2].exercise_id, curl);
+        assert_eq!(
+            summary(&planned),
+            vec![
+                (1, 8, Decimal::from(100)),
+                (2, 8, Decimal::from(100)),
+                (1, 8, Decimal::ZERO),
+                (3, 8, Decimal::from(80)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_planned_sets_from_last_performance() {
+        let squat = Uuid::new_v4();
+        let bench = Uuid::new_v4();
+        let exercises = vec![
+            template_exercise(squat, 1, 3, Some(100)),
+            template_exercise(bench, 2, 1, Some(60)),
+        ];
+        let last_sets = vec![set(squat, 2, 6, 110), set(squat, 1, 5, 105)];
+
+        let planned = planned_sets(&exercises, &last_sets);
+
+        // The last set done is repeated, exercises never done keep their targets
+        assert_eq!(
+            summary(&planned),
+            vec![
+                (1, 5, Decimal::from(105)),
+                (2, 6, Decimal::from(110)),
+                (3, 6, Decimal::from(110)),
+                (1, 8, Decimal::from(60)),
+            ]
+        );
+    }
+}
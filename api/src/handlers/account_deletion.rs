@@ -0,0 +1,118 @@
+use crate::{
+    auth::{middleware::RequireAuth, password::verify_password_async},
+    axummain::state::AppState,
+    schemas::account_deletion_schemas::*,
+    utils::guest_name_generator::GUEST_EMAIL_DOMAIN,
+};
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use tracing::{error, info};
+
+/// Is a deletion of my account scheduled
+pub async fn get_account_deletion(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+) -> Result<Json<AccountDeletionResponse>, StatusCode> {
+    info!("User {} fetching account deletion", user.id);
+
+    let request = state
+        .repositories
+        .account_deletion_repository
+        .find_by_user_id(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch account deletion request: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(AccountDeletionResponse {
+        delete_after: request.delete_after,
+    }))
+}
+
+/// Schedule the deletion of my account and all of its data. Every session is closed,
+/// logging in again before the end of the grace period cancels the deletion.
+pub async fn request_account_deletion(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+    Json(payload): Json<DeleteAccountRequest>,
+) -> Result<Json<AccountDeletionResponse>, Response> {
+    info!("User {} requesting account deletion", user.id);
+
+    let password_valid = verify_password_async(payload.password, user.password_hash.clone())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+    if !password_valid {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid password"})),
+        )
+            .into_response());
+    }
+
+    let existing = state
+        .repositories
+        .account_deletion_repository
+        .find_by_user_id(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch account deletion request: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    if let Some(existing) = existing {
+        return Ok(Json(AccountDeletionResponse {
+            delete_after: existing.delete_after,
+        }));
+    }
+
+    let delete_after = Utc::now() + Duration::days(state.account_deletion.grace_period_days.into());
+    let request = state
+        .repositories
+        .account_deletion_repository
+        .create_request(&user.id, &delete_after.fixed_offset())
+        .await
+        .map_err(|err| {
+            error!("Failed to create account deletion request: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    state
+        .repositories
+        .refresh_token_repository
+        .delete_all_user_tokens(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to revoke refresh tokens: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    // Guests have no real email address to confirm to
+    if !user.email.ends_with(GUEST_EMAIL_DOMAIN) {
+        state
+            .jobs
+            .email_job
+            .send_account_deletion_email(&user.email, &user.username, delete_after)
+            .await
+            .map_err(|err| {
+                error!("Failed to send account deletion email: {err}");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            })?;
+    }
+
+    info!(
+        "Account of user {} scheduled for deletion after {}",
+        user.id, request.delete_after
+    );
+
+    Ok(Json(AccountDeletionResponse {
+        delete_after: request.delete_after,
+    }))
+}
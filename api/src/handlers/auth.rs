@@ -1,10 +1,13 @@
 use crate::utils::guest_name_generator::GUEST_EMAIL_DOMAIN;
 use crate::{
     auth::{
+        client_info::ClientInfo,
         jwt::generate_token,
+        login_security,
         middleware::RequireAuth,
-        password::{hash_password_async, verify_password_async},
+        password::{LOW_COST_HASHING, hash_password_async, needs_rehash, verify_password_async},
         refresh_token::generate_refresh_token,
+        two_factor,
     },
     axummain::state::AppState,
     schemas::{
@@ -13,12 +16,19 @@ use crate::{
             ForgotPasswordRequest, ForgotPasswordResponse, ResetPasswordRequest,
         },
         token_schemas::{LogoutRequest, LogoutResponse, RefreshTokenRequest, RefreshTokenResponse},
+        two_factor_schemas::TwoFactorChallengeResponse,
     },
     utils::{get_now_time_paris::now_paris_fixed, token_generator::generate_verification_token},
 };
 
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
 use chrono::Duration;
+use entities::users;
 use log::error;
 use serde_json::json;
 use tracing::{debug, info};
@@ -40,6 +50,10 @@ pub async fn register(
         )
             .into_response());
     }
+    if let Err(err) = state.password_policy.check(&payload.user.password) {
+        info!("Password policy violation during registration: {}", err);
+        return Err(err.into_response());
+    }
 
     if state
         .repositories
@@ -55,9 +69,10 @@ pub async fn register(
         return Err(StatusCode::CONFLICT.into_response());
     }
 
-    let password_hash = hash_password_async(payload.user.password.clone(), None)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+    let password_hash =
+        hash_password_async(payload.user.password.clone(), state.password_policy.hashing)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
 
     common_register_logic(
         state,
@@ -87,7 +102,7 @@ pub async fn register_guest(
     };
 
     let email = format!("{username}{GUEST_EMAIL_DOMAIN}");
-    let password_hash = hash_password_async("password".to_string(), Some(4))
+    let password_hash = hash_password_async("password".to_string(), LOW_COST_HASHING)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
 
@@ -96,6 +111,92 @@ pub async fn register_guest(
         .map_err(|e| e.into_response())
 }
 
+/// Convert the guest account of the caller into a regular one, keeping all its data
+pub async fn upgrade_guest(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+    Json(payload): Json<RegisterUserRequest>,
+) -> Result<Json<LoginResponse>, Response> {
+    info!(
+        "Received guest upgrade request from {} to: {} [email: {}]",
+        user.id, payload.user.username, payload.user.email
+    );
+    if let Err(err) = payload.user.validate() {
+        info!("Validation error during guest upgrade: {}", err);
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": err.to_string()})),
+        )
+            .into_response());
+    }
+    if let Err(err) = state.password_policy.check(&payload.user.password) {
+        info!("Password policy violation during guest upgrade: {}", err);
+        return Err(err.into_response());
+    }
+
+    if !user.email.ends_with(GUEST_EMAIL_DOMAIN) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Only guest accounts can be upgraded"})),
+        )
+            .into_response());
+    }
+    if payload.user.email.ends_with(GUEST_EMAIL_DOMAIN) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "A real email address is required"})),
+        )
+            .into_response());
+    }
+
+    // The guest may keep its generated username
+    let email_taken = state
+        .repositories
+        .user_repository
+        .find_by_email(&payload.user.email)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .is_some();
+    let username_taken = state
+        .repositories
+        .user_repository
+        .find_by_username(&payload.user.username)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .is_some_and(|other| other.id != user.id);
+    if email_taken || username_taken {
+        info!(
+            "Guest upgrade with existing email or username: {} [email: {}]",
+            payload.user.username, payload.user.email
+        );
+        return Err(StatusCode::CONFLICT.into_response());
+    }
+
+    let password_hash =
+        hash_password_async(payload.user.password.clone(), state.password_policy.hashing)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+    let user = state
+        .repositories
+        .user_repository
+        .upgrade_guest(&user.id, &payload.user.username, &password_hash)
+        .await
+        .map_err(|err| {
+            error!("Failed to upgrade guest account because: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    send_upgrade_verification_email(&state, &user, &payload.user.email).await?;
+
+    // The refresh tokens of the guest were revoked, the client gets new ones
+    let response = create_login_response(&state, user)
+        .await
+        .map_err(|status| status.into_response())?;
+
+    Ok(Json(response))
+}
+
 async fn common_register_logic(
     state: AppState,
     username: String,
@@ -118,38 +219,8 @@ async fn common_register_logic(
         }
     };
 
-    if !is_guest {
-        let verification_token = generate_verification_token();
-        // If updated, need to be changed in the mail too
-        let expires_at = now_paris_fixed(Duration::hours(2));
-
-        debug!(
-            "Generated email verification token for user {}: {}",
-            user.id, verification_token
-        );
-        if let Err(err) = state
-            .repositories
-            .email_verification_repository
-            .create_token(&user.id, &verification_token, &expires_at)
-            .await
-        {
-            error!("Failed to register token because: {err}");
-            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
-        }
-
-        debug!(
-            "Sending verification email to {}: {}",
-            user.email, verification_token
-        );
-        if let Err(err) = state
-            .jobs
-            .email_job
-            .send_register_email(&user.email, &user.username, &verification_token)
-            .await
-        {
-            error!("Failed to send verification email: {err}");
-            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
-        }
+    if !is_guest && let Err(response) = send_verification_email(&state, &user).await {
+        return Err(response);
     }
 
     let access_token = generate_token(&user.id, &state.jwt_secret)
@@ -187,10 +258,89 @@ async fn common_register_logic(
     Ok(Json(response))
 }
 
+/// Create an email verification token for the user and send it by email
+async fn send_verification_email(state: &AppState, user: &users::Model) -> Result<(), Response> {
+    let verification_token = generate_verification_token();
+    // If updated, need to be changed in the mail too
+    let expires_at = now_paris_fixed(Duration::hours(2));
+
+    debug!(
+        "Generated email verification token for user {}: {}",
+        user.id, verification_token
+    );
+    if let Err(err) = state
+        .repositories
+        .email_verification_repository
+        .create_token(&user.id, &verification_token, &expires_at)
+        .await
+    {
+        error!("Failed to register token because: {err}");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    }
+
+    debug!(
+        "Sending verification email to {}: {}",
+        user.email, verification_token
+    );
+    if let Err(err) = state
+        .jobs
+        .email_job
+        .send_register_email(&user.email, &user.username, &verification_token)
+        .await
+    {
+        error!("Failed to send verification email: {err}");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    }
+
+    Ok(())
+}
+
+/// Ask an upgraded guest to confirm its real email. The account keeps its verified guest
+/// email until then, so it is never seen as an unverified registration to clean up.
+async fn send_upgrade_verification_email(
+    state: &AppState,
+    user: &users::Model,
+    email: &str,
+) -> Result<(), Response> {
+    let verification_token = generate_verification_token();
+    let expires_at = now_paris_fixed(Duration::hours(2));
+
+    debug!(
+        "Generated guest upgrade verification token for user {}: {}",
+        user.id, verification_token
+    );
+    if let Err(err) = state
+        .repositories
+        .email_verification_repository
+        .create_email_change_token(&user.id, &verification_token, &expires_at, email)
+        .await
+    {
+        error!("Failed to register token because: {err}");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    }
+
+    debug!(
+        "Sending guest upgrade verification email to {}: {}",
+        email, verification_token
+    );
+    if let Err(err) = state
+        .jobs
+        .email_job
+        .send_register_email(email, &user.username, &verification_token)
+        .await
+    {
+        error!("Failed to send verification email: {err}");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    }
+
+    Ok(())
+}
+
 pub async fn login(
     State(state): State<AppState>,
+    client: ClientInfo,
     Json(payload): Json<LoginUserRequest>,
-) -> Result<Json<LoginResponse>, impl IntoResponse> {
+) -> Result<Response, impl IntoResponse> {
     info!("Received login request for email: {}", payload.user.email);
     if let Err(err) = payload.user.validate() {
         info!("Validation error during login: {}", err);
@@ -201,19 +351,57 @@ pub async fn login(
             .into_response());
     }
 
-    let user = state
+    // Security checks rely on Redis, they are skipped rather than blocking logins when it fails
+    if let Some(ip) = client.ip.as_deref() {
+        match login_security::ip_blocked_for(&state, ip).await {
+            Ok(Some(retry_after)) => {
+                info!("Login attempt from blocked IP: {}", ip);
+                return Err(retry_later(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    retry_after,
+                    "Too many failed login attempts, please try again later",
+                ));
+            }
+            Ok(None) => {}
+            Err(err) => error!("Failed to check failed logins of IP: {}", err),
+        }
+    }
+
+    let user = match state
         .repositories
         .user_repository
         .find_by_email(&payload.user.email)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
-        .ok_or_else(|| {
+    {
+        // The system user owning the catalogs has no usable password
+        Some(user) if !user.is_system() => user,
+        _ => {
             info!(
                 "Login attempt with non-existing email: {}",
                 payload.user.email
             );
-            StatusCode::UNAUTHORIZED.into_response()
-        })?;
+            if let Err(err) =
+                login_security::record_failed_login(&state, None, client.ip.as_deref()).await
+            {
+                error!("Failed to record failed login: {}", err);
+            }
+            return Err(StatusCode::UNAUTHORIZED.into_response());
+        }
+    };
+
+    match login_security::account_locked_for(&state, &user.id).await {
+        Ok(Some(retry_after)) => {
+            info!("Login attempt on locked account: {}", user.id);
+            return Err(retry_later(
+                StatusCode::LOCKED,
+                retry_after,
+                "Account temporarily locked after too many failed login attempts",
+            ));
+        }
+        Ok(None) => {}
+        Err(err) => error!("Failed to check account lock: {}", err),
+    }
 
     let password_valid =
         verify_password_async(payload.user.password.clone(), user.password_hash.clone())
@@ -222,26 +410,143 @@ pub async fn login(
 
     if !password_valid {
         info!("Invalid password attempt for email: {}", payload.user.email);
+        match login_security::record_failed_login(&state, Some(&user.id), client.ip.as_deref())
+            .await
+        {
+            Ok(true) => login_security::send_lockout_alert(&state, &user, &client).await,
+            Ok(false) => {}
+            Err(err) => error!("Failed to record failed login: {}", err),
+        }
         return Err(StatusCode::UNAUTHORIZED.into_response());
     }
 
+    if let Err(err) = login_security::clear_failed_logins(&state, &user.id).await {
+        error!("Failed to clear failed logins: {}", err);
+    }
+
+    let user = rehash_password_if_needed(&state, user, payload.user.password).await;
+
+    complete_login(&state, user, &client).await
+}
+
+/// Replace a bcrypt hash, or one made with older Argon2id parameters, now that the
+/// password is known. A failure only delays the migration to the next login.
+async fn rehash_password_if_needed(
+    state: &AppState,
+    user: users::Model,
+    password: String,
+) -> users::Model {
+    let hashing = state.password_policy.hashing;
+    if !needs_rehash(&user.password_hash, &hashing) {
+        return user;
+    }
+
+    debug!("Rehashing password of user {}", user.id);
+    let password_hash = match hash_password_async(password, hashing).await {
+        Ok(password_hash) => password_hash,
+        Err(err) => {
+            error!("Failed to rehash password: {}", err);
+            return user;
+        }
+    };
+
+    match state
+        .repositories
+        .user_repository
+        .update_password(&user.id, &password_hash)
+        .await
+    {
+        Ok(user) => user,
+        Err(err) => {
+            error!("Failed to save rehashed password: {}", err);
+            user
+        }
+    }
+}
+
+/// Last step of a login once the user proved who they are: ask for the second factor
+/// when it is enabled, otherwise issue the tokens
+pub async fn complete_login(
+    state: &AppState,
+    user: users::Model,
+    client: &ClientInfo,
+) -> Result<Response, Response> {
+    let two_factor = state
+        .repositories
+        .two_factor_repository
+        .find_by_user(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch two-factor settings: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    // The tokens are only issued once the code is checked by `verify_two_factor`
+    if two_factor.is_some_and(|two_factor| two_factor.enabled) {
+        info!("Two-factor challenge required for user {}", user.id);
+        let challenge_token = two_factor::create_challenge(&state.redis, &user.id)
+            .await
+            .map_err(|err| {
+                error!("Failed to create two-factor challenge: {}", err);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            })?;
+
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(TwoFactorChallengeResponse {
+                two_factor_required: true,
+                challenge_token,
+                expires_in: two_factor::CHALLENGE_TTL_SECS,
+            }),
+        )
+            .into_response());
+    }
+
+    login_security::track_login_device(state, &user, client).await;
+
+    let response = create_login_response(state, user)
+        .await
+        .map_err(|status| status.into_response())?;
+
+    Ok(Json(response).into_response())
+}
+
+/// Issue a new pair of access and refresh tokens for a user who proved who they are
+pub async fn create_login_response(
+    state: &AppState,
+    user: users::Model,
+) -> Result<LoginResponse, StatusCode> {
     let access_token = generate_token(&user.id, &state.jwt_secret)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     let refresh_token = generate_refresh_token();
 
+    // Logging in again is how a user changes their mind about deleting the account
+    let deletion_cancelled = state
+        .repositories
+        .account_deletion_repository
+        .cancel_request(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to cancel account deletion: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if deletion_cancelled {
+        info!("Account deletion of user {} cancelled by login", user.id);
+    }
+
     debug!("Creating refresh token for user {}", user.id);
     state
         .repositories
         .refresh_token_repository
         .create_token(&user.id, &refresh_token)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    Ok(Json(LoginResponse {
+    Ok(LoginResponse {
         user: UserData::from_user(user),
         access_token,
         refresh_token,
-    }))
+    })
 }
 
 pub async fn current_user(
@@ -430,6 +735,10 @@ pub async fn reset_password(
         )
             .into_response());
     }
+    if let Err(err) = state.password_policy.check(&payload.new_password) {
+        info!("Password policy violation during reset password: {}", err);
+        return Err(err.into_response());
+    }
 
     let reset_token = state
         .repositories
@@ -459,9 +768,10 @@ pub async fn reset_password(
         return Err(StatusCode::GONE.into_response());
     }
 
-    let new_password_hash = hash_password_async(payload.new_password.clone(), None)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+    let new_password_hash =
+        hash_password_async(payload.new_password.clone(), state.password_policy.hashing)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
 
     debug!("Updating password for user {}", reset_token.user_id);
     let user = state
@@ -484,7 +794,7 @@ pub async fn reset_password(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
 
     let refresh_token = generate_refresh_token();
-    let refresh_token_hash = hash_password_async(refresh_token.clone(), Some(4))
+    let refresh_token_hash = hash_password_async(refresh_token.clone(), LOW_COST_HASHING)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
 
@@ -586,3 +896,12 @@ pub async fn logout(
         message: "Logged out successfully".to_string(),
     }))
 }
+
+/// An error response telling the client when to try again
+fn retry_later(status: StatusCode, retry_after: u64, message: &str) -> Response {
+    let mut response = (status, Json(json!({"error": message}))).into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
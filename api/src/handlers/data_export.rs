@@ -0,0 +1,95 @@
+use crate::{
+    auth::middleware::{RequireAuth, RequireVerifiedAuth},
+    axummain::state::AppState,
+    schemas::data_export_schemas::*,
+};
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use entities::{data_export_key, data_export_pending_key};
+use redis::AsyncCommands;
+use serde_json::json;
+use tracing::{error, info};
+
+/// Another export can be requested if the worker did not finish this one in time
+const PENDING_EXPORT_TTL_SECS: u64 = 3600;
+
+/// Ask for an archive of all my data, the download link is sent by email once ready
+pub async fn request_data_export(
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<DataExportResponse>), Response> {
+    info!("User {} requesting a data export", user.id);
+
+    let mut con = state.redis.clone();
+    let options = redis::SetOptions::default()
+        .conditional_set(redis::ExistenceCheck::NX)
+        .with_expiration(redis::SetExpiry::EX(PENDING_EXPORT_TTL_SECS));
+    let created: Option<String> = con
+        .set_options(data_export_pending_key(&user.id), 1, options)
+        .await
+        .map_err(|err| {
+            error!("Failed to mark the data export as pending: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    if created.is_none() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "An export is already being prepared"})),
+        )
+            .into_response());
+    }
+
+    state
+        .jobs
+        .data_export_job
+        .send_data_export_job(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to queue the data export: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(DataExportResponse {
+            message: "Your export is being prepared, you will receive a download link by email"
+                .to_string(),
+        }),
+    ))
+}
+
+/// Download an export archive from the link sent by email
+pub async fn download_data_export(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Response, StatusCode> {
+    info!("User {} downloading a data export", user.id);
+
+    let mut con = state.redis.clone();
+    let archive: Option<Vec<u8>> =
+        con.get(data_export_key(&user.id, &token))
+            .await
+            .map_err(|err| {
+                error!("Failed to fetch the data export: {}", err);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+    let archive = archive.ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"dimdim-health-export.zip\"",
+            ),
+        ],
+        archive,
+    )
+        .into_response())
+}
@@ -0,0 +1,177 @@
+use crate::{
+    auth::middleware::RequireVerifiedAuth, axummain::state::AppState,
+    schemas::data_import_schemas::*,
+};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use entities::sea_orm_active_enums::{ImportKindEnum, ImportStatusEnum};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// Bigger files have to be split by the user
+const MAX_IMPORT_LINES: usize = 20_000;
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    pub kind: ImportKindEnum,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Upload a CSV file, it is imported in the background. A dry run only reports what
+/// would be imported, it can be applied afterwards.
+pub async fn create_data_import(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Query(query): Query<ImportQuery>,
+    content: String,
+) -> Result<(StatusCode, Json<DataImportResponse>), Response> {
+    info!(
+        "User {} importing {:?} (dry run: {})",
+        user.id, query.kind, query.dry_run
+    );
+
+    let lines = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count();
+    if lines < 2 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "The file needs a header line and at least one row"})),
+        )
+            .into_response());
+    }
+    if lines > MAX_IMPORT_LINES {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(json!({
+                "error": format!("The file must have at most {} lines", MAX_IMPORT_LINES)
+            })),
+        )
+            .into_response());
+    }
+
+    let data_import = state
+        .repositories
+        .data_import_repository
+        .create(user.id, query.kind, query.dry_run, content)
+        .await
+        .map_err(|err| {
+            error!("Failed to create data import: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    queue_data_import(&state, &data_import.id).await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(DataImportResponse::from(data_import)),
+    ))
+}
+
+pub async fn get_data_imports(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+) -> Result<Json<Vec<DataImportResponse>>, StatusCode> {
+    info!("Fetching data imports for user: {}", user.id);
+
+    let imports = state
+        .repositories
+        .data_import_repository
+        .find_all_by_user(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch data imports: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(
+        imports.into_iter().map(DataImportResponse::from).collect(),
+    ))
+}
+
+pub async fn get_data_import(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DataImportResponse>, StatusCode> {
+    info!("Fetching data import {} for user: {}", id, user.id);
+
+    let data_import = state
+        .repositories
+        .data_import_repository
+        .find_by_id_and_user(&id, &user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch data import: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(DataImportResponse::from(data_import)))
+}
+
+/// Import for real the content of a finished dry run
+pub async fn apply_data_import(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<DataImportResponse>), Response> {
+    info!("User {} applying data import {}", user.id, id);
+
+    let data_import = state
+        .repositories
+        .data_import_repository
+        .find_by_id_and_user(&id, &user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch data import: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+    if !data_import.dry_run || data_import.status != ImportStatusEnum::Completed {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Only a finished dry run can be applied"})),
+        )
+            .into_response());
+    }
+
+    let data_import = state
+        .repositories
+        .data_import_repository
+        .apply_dry_run(data_import)
+        .await
+        .map_err(|err| {
+            error!("Failed to update data import: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    queue_data_import(&state, &data_import.id).await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(DataImportResponse::from(data_import)),
+    ))
+}
+
+async fn queue_data_import(state: &AppState, import_id: &Uuid) -> Result<(), Response> {
+    state
+        .jobs
+        .data_import_job
+        .send_data_import_job(import_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to queue data import: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
@@ -7,6 +7,7 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
+use entities::sea_orm_active_enums::VisibilityEnum;
 use serde::Deserialize;
 use serde_json::json;
 use tracing::{error, info};
@@ -48,7 +49,7 @@ pub async fn create_food_item(
 
 pub async fn get_food_items(
     State(state): State<AppState>,
-    RequireVerifiedAuth(_user): RequireVerifiedAuth,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
     Query(query): Query<SearchQuery>,
 ) -> Result<Json<Vec<FoodItemResponse>>, impl IntoResponse> {
     info!("Fetching food items");
@@ -57,17 +58,21 @@ pub async fn get_food_items(
         state
             .repositories
             .food_item_repository
-            .find_by_scan_code(&scan_code)
+            .find_by_scan_code(&scan_code, user.id)
             .await
             .map(|opt| opt.into_iter().collect())
     } else if let Some(name) = query.name {
         state
             .repositories
             .food_item_repository
-            .find_by_name(&name)
+            .find_by_name(&name, user.id)
             .await
     } else {
-        state.repositories.food_item_repository.find_all().await
+        state
+            .repositories
+            .food_item_repository
+            .find_all(user.id)
+            .await
     };
 
     food_items_result
@@ -104,12 +109,22 @@ pub async fn update_food_item(
             error!("Failed to fetch food item: {}", err);
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
         })?
+        .filter(|food_item| food_item.is_visible_to(&user.id))
         .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
 
     if food_item.added_by != user.id {
         return Err(StatusCode::FORBIDDEN.into_response());
     }
 
+    // Everyone eating a public food item relies on it staying the same
+    if food_item.visibility == VisibilityEnum::Public {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Public food items can not be edited"})),
+        )
+            .into_response());
+    }
+
     state
         .repositories
         .food_item_repository
@@ -139,12 +154,40 @@ pub async fn delete_food_item(
             error!("Failed to fetch food item: {}", err);
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
         })?
+        .filter(|food_item| food_item.is_visible_to(&user.id))
         .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
 
     if food_item.added_by != user.id {
         return Err(StatusCode::FORBIDDEN.into_response());
     }
 
+    // Other users may already have eaten a public food item
+    if food_item.visibility == VisibilityEnum::Public {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Public food items can not be deleted"})),
+        )
+            .into_response());
+    }
+
+    // Their meal items would be deleted with it
+    let used_by_others = state
+        .repositories
+        .food_item_repository
+        .is_used_by_others(&id, &user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to check food item usage: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    if used_by_others {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Food item is used by other users"})),
+        )
+            .into_response());
+    }
+
     state
         .repositories
         .food_item_repository
@@ -156,3 +199,66 @@ pub async fn delete_food_item(
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
         })
 }
+
+pub async fn share_food_item(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FoodItemResponse>, impl IntoResponse> {
+    info!("Sharing food item {} for user: {}", id, user.id);
+
+    let food_item = state
+        .repositories
+        .food_item_repository
+        .find_by_id(&id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch food item: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .filter(|food_item| food_item.is_visible_to(&user.id))
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+    if food_item.added_by != user.id {
+        return Err(StatusCode::FORBIDDEN.into_response());
+    }
+
+    if food_item.visibility != VisibilityEnum::Private {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Food item is already shared"})),
+        )
+            .into_response());
+    }
+
+    let duplicate = state
+        .repositories
+        .food_item_repository
+        .find_public_duplicate(id, &food_item.name)
+        .await
+        .map_err(|err| {
+            error!("Failed to check for duplicate food items: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    if let Some(duplicate) = duplicate {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "A public food item with this name already exists",
+                "duplicate_id": duplicate.id,
+            })),
+        )
+            .into_response());
+    }
+
+    state
+        .repositories
+        .food_item_repository
+        .set_visibility(id, VisibilityEnum::Pending)
+        .await
+        .map(|food_item| Json(FoodItemResponse::from(food_item)))
+        .map_err(|err| {
+            error!("Failed to share food item: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
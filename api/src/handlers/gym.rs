@@ -1,5 +1,14 @@
 use crate::{
-    auth::middleware::RequireVerifiedAuth, axummain::state::AppState, schemas::gym_schemas::*,
+    auth::{
+        middleware::RequireVerifiedAuth,
+        resource_authorization::{GymScope, ViewUserData},
+    },
+    axummain::state::AppState,
+    gym::{
+        analytics::{exercise_history, session_exercises, weekly_muscle_volume},
+        sets::{BulkSetsError, number_sets},
+    },
+    schemas::gym_schemas::*,
 };
 use axum::{
     Json,
@@ -7,8 +16,10 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
-use chrono::NaiveDate;
-use entities::sea_orm_active_enums::MuscleEnum;
+use chrono::{Duration, NaiveDate, Utc, Weekday};
+use entities::sea_orm_active_enums::{
+    EquipmentEnum, MovementPatternEnum, MuscleEnum, OneRepMaxFormulaEnum, VisibilityEnum,
+};
 use serde::Deserialize;
 use serde_json::json;
 use tracing::{error, info};
@@ -21,9 +32,109 @@ pub struct DateQuery {
 }
 
 #[derive(Debug, Deserialize)]
-pub struct MuscleQuery {
+pub struct GymExerciseQuery {
     pub muscle: Option<MuscleEnum>,
     pub name: Option<String>,
+    pub equipment: Option<EquipmentEnum>,
+    pub movement_pattern: Option<MovementPatternEnum>,
+    pub is_unilateral: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OneRepMaxQuery {
+    /// Epley when not given
+    pub formula: Option<OneRepMaxFormulaEnum>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExerciseHistoryQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Epley when not given
+    pub formula: Option<OneRepMaxFormulaEnum>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VolumeQuery {
+    /// The last 12 weeks when not given
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+fn date_range_error() -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": "from must not be after to"})),
+    )
+        .into_response()
+}
+
+fn session_times_error() -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": "ended_at must not be before started_at"})),
+    )
+        .into_response()
+}
+
+fn user_weight_error() -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": "The weight must be one of the user recorded on the session date"})),
+    )
+        .into_response()
+}
+
+fn bulk_sets_error(err: BulkSetsError) -> axum::response::Response {
+    let body = match err {
+        BulkSetsError::UnknownSet(set_id) => {
+            json!({"error": "Set not found in the session", "set_id": set_id})
+        }
+        BulkSetsError::RepeatedSet(set_id) => {
+            json!({"error": "A set can only be updated or deleted once", "set_id": set_id})
+        }
+        BulkSetsError::DuplicateNumber {
+            exercise_id,
+            set_number,
+        } => json!({
+            "error": "Set number already used for this exercise",
+            "exercise_id": exercise_id,
+            "set_number": set_number,
+        }),
+    };
+    (StatusCode::BAD_REQUEST, Json(body)).into_response()
+}
+
+/// Two sets of an exercise can not share a number in a session, the database refuses it
+/// when the sets changed since they were checked
+fn is_duplicate_set_number(err: &sea_orm::DbErr) -> bool {
+    matches!(
+        err.sql_err(),
+        Some(sea_orm::SqlErr::UniqueConstraintViolation(_))
+    )
+}
+
+fn duplicate_set_number() -> axum::response::Response {
+    (
+        StatusCode::CONFLICT,
+        Json(json!({"error": "Set number already used for this exercise"})),
+    )
+        .into_response()
+}
+
+/// Whether the weight was recorded by the user on the date
+async fn is_weight_of_day(
+    state: &AppState,
+    user_id: &Uuid,
+    user_weight_id: &Uuid,
+    date: NaiveDate,
+) -> Result<bool, sea_orm::DbErr> {
+    Ok(state
+        .repositories
+        .user_weight_repository
+        .find_by_id(user_weight_id)
+        .await?
+        .is_some_and(|weight| weight.user_id == *user_id && weight.recorded_at == date))
 }
 
 pub async fn create_gym_exercise(
@@ -44,13 +155,7 @@ pub async fn create_gym_exercise(
     match state
         .repositories
         .gym_exercise_repository
-        .create(
-            payload.name,
-            payload.description,
-            payload.primary_muscles,
-            payload.secondary_muscles,
-            user.id,
-        )
+        .create(payload, user.id)
         .await
     {
         Ok(exercise) => Ok(Json(exercise)),
@@ -63,53 +168,35 @@ pub async fn create_gym_exercise(
 
 pub async fn get_gym_exercises(
     State(state): State<AppState>,
-    RequireVerifiedAuth(_user): RequireVerifiedAuth,
-    Query(query): Query<MuscleQuery>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Query(query): Query<GymExerciseQuery>,
 ) -> Result<Json<Vec<GymExerciseResponse>>, impl IntoResponse> {
     info!("Fetching gym exercises");
 
-    let exercises = if let Some(muscle) = query.muscle {
-        match state
-            .repositories
-            .gym_exercise_repository
-            .find_by_muscle(muscle)
-            .await
-        {
-            Ok(exercises) => exercises,
-            Err(err) => {
-                error!("Failed to fetch exercises by muscle: {}", err);
-                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
-            }
-        }
-    } else if let Some(name) = query.name {
-        match state
-            .repositories
-            .gym_exercise_repository
-            .find_by_name(&name)
-            .await
-        {
-            Ok(exercises) => exercises,
-            Err(err) => {
-                error!("Failed to fetch exercises by name: {}", err);
-                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
-            }
-        }
-    } else {
-        match state.repositories.gym_exercise_repository.find_all().await {
-            Ok(exercises) => exercises,
-            Err(err) => {
-                error!("Failed to fetch exercises: {}", err);
-                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
-            }
+    match state
+        .repositories
+        .gym_exercise_repository
+        .search(
+            user.id,
+            query.muscle,
+            query.name.as_deref(),
+            query.equipment,
+            query.movement_pattern,
+            query.is_unilateral,
+        )
+        .await
+    {
+        Ok(exercises) => Ok(Json(exercises)),
+        Err(err) => {
+            error!("Failed to fetch exercises: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
         }
-    };
-
-    Ok(Json(exercises))
+    }
 }
 
 pub async fn get_gym_exercise(
     State(state): State<AppState>,
-    RequireVerifiedAuth(_user): RequireVerifiedAuth,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
     Path(id): Path<Uuid>,
 ) -> Result<Json<GymExerciseResponse>, impl IntoResponse> {
     info!("Fetching gym exercise: {}", id);
@@ -120,7 +207,13 @@ pub async fn get_gym_exercise(
         .find_by_id_with_muscles(&id)
         .await
     {
-        Ok(Some(exercise)) => Ok(Json(exercise)),
+        // Private and pending exercises of other users are hidden
+        Ok(Some(exercise))
+            if exercise.visibility == VisibilityEnum::Public || exercise.added_by == user.id =>
+        {
+            Ok(Json(exercise))
+        }
+        Ok(Some(_)) => Err(StatusCode::NOT_FOUND.into_response()),
         Ok(None) => Err(StatusCode::NOT_FOUND.into_response()),
         Err(err) => {
             error!("Failed to fetch gym exercise: {}", err);
@@ -152,12 +245,20 @@ pub async fn update_gym_exercise(
         .find_by_id(&id)
         .await
     {
-        Ok(Some(exercise)) => {
+        Ok(Some(exercise)) if exercise.is_visible_to(&user.id) => {
             if exercise.added_by != user.id {
                 return Err(StatusCode::FORBIDDEN.into_response());
             }
+            // Everyone logging sets of a public exercise relies on it staying the same
+            if exercise.visibility == VisibilityEnum::Public {
+                return Err((
+                    StatusCode::CONFLICT,
+                    Json(json!({"error": "Public exercises can not be edited"})),
+                )
+                    .into_response());
+            }
         }
-        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Ok(_) => return Err(StatusCode::NOT_FOUND.into_response()),
         Err(err) => {
             error!("Failed to fetch gym exercise: {}", err);
             return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
@@ -167,13 +268,7 @@ pub async fn update_gym_exercise(
     match state
         .repositories
         .gym_exercise_repository
-        .update(
-            id,
-            payload.name,
-            payload.description,
-            payload.primary_muscles,
-            payload.secondary_muscles,
-        )
+        .update(id, payload)
         .await
     {
         Ok(exercise) => Ok(Json(exercise)),
@@ -198,18 +293,47 @@ pub async fn delete_gym_exercise(
         .find_by_id(&id)
         .await
     {
-        Ok(Some(exercise)) => {
+        Ok(Some(exercise)) if exercise.is_visible_to(&user.id) => {
             if exercise.added_by != user.id {
                 return Err(StatusCode::FORBIDDEN.into_response());
             }
+            // Other users may already log sets of a public exercise
+            if exercise.visibility == VisibilityEnum::Public {
+                return Err((
+                    StatusCode::CONFLICT,
+                    Json(json!({"error": "Public exercises can not be deleted"})),
+                )
+                    .into_response());
+            }
         }
-        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Ok(_) => return Err(StatusCode::NOT_FOUND.into_response()),
         Err(err) => {
             error!("Failed to fetch gym exercise: {}", err);
             return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
         }
     }
 
+    // Their sets would be deleted with it
+    match state
+        .repositories
+        .gym_exercise_repository
+        .is_used_by_others(&id, &user.id)
+        .await
+    {
+        Ok(false) => {}
+        Ok(true) => {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(json!({"error": "Exercise is used by other users"})),
+            )
+                .into_response());
+        }
+        Err(err) => {
+            error!("Failed to check gym exercise usage: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
     match state.repositories.gym_exercise_repository.delete(&id).await {
         Ok(_) => Ok(StatusCode::NO_CONTENT),
         Err(err) => {
@@ -219,6 +343,276 @@ pub async fn delete_gym_exercise(
     }
 }
 
+pub async fn share_gym_exercise(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+) -> Result<Json<GymExerciseResponse>, impl IntoResponse> {
+    info!("Sharing gym exercise {} for user: {}", id, user.id);
+
+    let exercise = match state
+        .repositories
+        .gym_exercise_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(exercise)) if exercise.is_visible_to(&user.id) => exercise,
+        Ok(_) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch gym exercise: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+
+    if exercise.added_by != user.id {
+        return Err(StatusCode::FORBIDDEN.into_response());
+    }
+
+    if exercise.visibility != VisibilityEnum::Private {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Exercise is already shared"})),
+        )
+            .into_response());
+    }
+
+    match state
+        .repositories
+        .gym_exercise_repository
+        .find_public_duplicate(id, &exercise.name)
+        .await
+    {
+        Ok(Some(duplicate)) => {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": "A public exercise with this name already exists",
+                    "duplicate_id": duplicate.id,
+                })),
+            )
+                .into_response());
+        }
+        Ok(None) => {}
+        Err(err) => {
+            error!("Failed to check for duplicate gym exercises: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    if let Err(err) = state
+        .repositories
+        .gym_exercise_repository
+        .set_visibility(id, VisibilityEnum::Pending)
+        .await
+    {
+        error!("Failed to share gym exercise: {}", err);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    }
+
+    match state
+        .repositories
+        .gym_exercise_repository
+        .find_by_id_with_muscles(&id)
+        .await
+    {
+        Ok(Some(exercise)) => Ok(Json(exercise)),
+        Ok(None) => Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch gym exercise: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn get_gym_exercise_records(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+    Query(query): Query<OneRepMaxQuery>,
+) -> Result<Json<PersonalRecordsResponse>, impl IntoResponse> {
+    info!(
+        "Fetching records of gym exercise {} for user: {}",
+        id, user.id
+    );
+
+    match state
+        .repositories
+        .gym_exercise_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(exercise)) if exercise.is_visible_to(&user.id) => {}
+        Ok(_) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch gym exercise: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    let formula = query.formula.unwrap_or(OneRepMaxFormulaEnum::Epley);
+    match state
+        .repositories
+        .gym_personal_record_repository
+        .find_history(user.id, id, formula.clone())
+        .await
+    {
+        Ok(history) => Ok(Json(PersonalRecordsResponse::from_history(
+            id, formula, history,
+        ))),
+        Err(err) => {
+            error!("Failed to fetch personal records: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+/// Every record beaten on the exercise, newest first
+pub async fn get_gym_exercise_record_history(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+    Query(query): Query<OneRepMaxQuery>,
+) -> Result<Json<Vec<PersonalRecordResponse>>, impl IntoResponse> {
+    info!(
+        "Fetching record history of gym exercise {} for user: {}",
+        id, user.id
+    );
+
+    match state
+        .repositories
+        .gym_exercise_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(exercise)) if exercise.is_visible_to(&user.id) => {}
+        Ok(_) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch gym exercise: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    match state
+        .repositories
+        .gym_personal_record_repository
+        .find_history(
+            user.id,
+            id,
+            query.formula.unwrap_or(OneRepMaxFormulaEnum::Epley),
+        )
+        .await
+    {
+        Ok(history) => Ok(Json(
+            history
+                .into_iter()
+                .map(PersonalRecordResponse::from)
+                .collect(),
+        )),
+        Err(err) => {
+            error!("Failed to fetch personal records: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+/// Progression of an exercise, one entry per session, oldest first
+pub async fn get_gym_exercise_history(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ExerciseHistoryQuery>,
+) -> Result<Json<Vec<ExerciseHistoryResponse>>, impl IntoResponse> {
+    info!(
+        "Fetching history of gym exercise {} for user: {}",
+        id, user.id
+    );
+
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(date_range_error());
+    }
+
+    match state
+        .repositories
+        .gym_exercise_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(exercise)) if exercise.is_visible_to(&user.id) => {}
+        Ok(_) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch gym exercise: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    match state
+        .repositories
+        .gym_set_repository
+        .find_by_user_with_dates(&user.id, Some(id), query.from, query.to)
+        .await
+    {
+        Ok(sets) => Ok(Json(exercise_history(
+            sets,
+            &query.formula.unwrap_or(OneRepMaxFormulaEnum::Epley),
+        ))),
+        Err(err) => {
+            error!("Failed to fetch gym sets: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+/// Weekly sets and volume per muscle
+pub async fn get_gym_volume(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Query(query): Query<VolumeQuery>,
+) -> Result<Json<Vec<WeeklyVolumeResponse>>, impl IntoResponse> {
+    info!("Fetching gym volume for user: {}", user.id);
+
+    let from = query.from.unwrap_or_else(|| {
+        (Utc::now() - Duration::weeks(11))
+            .date_naive()
+            .week(Weekday::Mon)
+            .first_day()
+    });
+    if query.to.is_some_and(|to| from > to) {
+        return Err(date_range_error());
+    }
+
+    let sets = match state
+        .repositories
+        .gym_set_repository
+        .find_by_user_with_dates(&user.id, None, Some(from), query.to)
+        .await
+    {
+        Ok(sets) => sets,
+        Err(err) => {
+            error!("Failed to fetch gym sets: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+
+    let mut exercise_ids: Vec<Uuid> = sets.iter().map(|(_, set)| set.exercise_id).collect();
+    exercise_ids.sort();
+    exercise_ids.dedup();
+
+    match state
+        .repositories
+        .gym_exercise_repository
+        .find_muscles(exercise_ids)
+        .await
+    {
+        Ok(muscles) => Ok(Json(weekly_muscle_volume(&sets, muscles))),
+        Err(err) => {
+            error!("Failed to fetch exercise muscles: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
 // ============================================================================
 // Gym Session handlers
 // ============================================================================
@@ -238,10 +632,27 @@ pub async fn create_gym_session(
             .into_response());
     }
 
+    if let (Some(started_at), Some(ended_at)) = (payload.started_at, payload.ended_at)
+        && ended_at < started_at
+    {
+        return Err(session_times_error());
+    }
+
+    if let Some(user_weight_id) = &payload.user_weight_id {
+        match is_weight_of_day(&state, &user.id, user_weight_id, payload.date).await {
+            Ok(true) => {}
+            Ok(false) => return Err(user_weight_error()),
+            Err(err) => {
+                error!("Failed to fetch user weight: {}", err);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+            }
+        }
+    }
+
     match state
         .repositories
         .gym_session_repository
-        .create(user.id, payload.date)
+        .create(user.id, payload)
         .await
     {
         Ok(session) => Ok(Json(GymSessionResponse::from(session))),
@@ -292,6 +703,48 @@ pub async fn get_gym_sessions(
     Ok(Json(response))
 }
 
+/// Get gym sessions of another user if the current user has permission to view them
+pub async fn get_other_user_gym_sessions(
+    State(state): State<AppState>,
+    ViewUserData {
+        requesting_user,
+        target_user_id,
+        ..
+    }: ViewUserData<GymScope>,
+    Query(query): Query<DateQuery>,
+) -> Result<Json<Vec<GymSessionResponse>>, impl IntoResponse> {
+    info!(
+        "User {} fetching gym sessions for user: {}",
+        requesting_user.id, target_user_id
+    );
+
+    let sessions = if let Some(date) = query.date {
+        state
+            .repositories
+            .gym_session_repository
+            .find_by_user_and_date(&target_user_id, date)
+            .await
+    } else {
+        state
+            .repositories
+            .gym_session_repository
+            .find_by_user_id(&target_user_id)
+            .await
+    };
+
+    match sessions {
+        Ok(sessions) => {
+            let response: Vec<GymSessionResponse> =
+                sessions.into_iter().map(GymSessionResponse::from).collect();
+            Ok(Json(response))
+        }
+        Err(err) => {
+            error!("Failed to fetch gym sessions: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
 pub async fn get_gym_session(
     State(state): State<AppState>,
     RequireVerifiedAuth(user): RequireVerifiedAuth,
@@ -319,11 +772,90 @@ pub async fn get_gym_session(
     }
 }
 
+/// Session with its sets grouped by exercise, each compared to its previous session
+pub async fn get_gym_session_details(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+    Query(query): Query<OneRepMaxQuery>,
+) -> Result<Json<GymSessionDetailsResponse>, impl IntoResponse> {
+    info!("Fetching gym session details {} for user: {}", id, user.id);
+
+    let formula = query.formula.unwrap_or(OneRepMaxFormulaEnum::Epley);
+
+    // Check if the session exists and belongs to the user
+    let session = match state
+        .repositories
+        .gym_session_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(session)) => {
+            if session.user_id != user.id {
+                return Err(StatusCode::FORBIDDEN.into_response());
+            }
+            session
+        }
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch gym session: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+
+    let sets = match state
+        .repositories
+        .gym_set_repository
+        .find_by_session_id(&id)
+        .await
+    {
+        Ok(sets) => sets,
+        Err(err) => {
+            error!("Failed to fetch gym sets: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+    let mut exercise_ids: Vec<Uuid> = sets.iter().map(|set| set.exercise_id).collect();
+    exercise_ids.sort();
+    exercise_ids.dedup();
+
+    let exercises = match state
+        .repositories
+        .gym_exercise_repository
+        .find_by_ids_with_muscles(exercise_ids.clone())
+        .await
+    {
+        Ok(exercises) => exercises,
+        Err(err) => {
+            error!("Failed to fetch gym exercises: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+
+    let previous = match state
+        .repositories
+        .gym_set_repository
+        .find_previous_performances(&session, exercise_ids)
+        .await
+    {
+        Ok(previous) => previous,
+        Err(err) => {
+            error!("Failed to fetch previous performances: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+
+    Ok(Json(GymSessionDetailsResponse {
+        exercises: session_exercises(session.date, sets, exercises, previous, &formula),
+        session: GymSessionResponse::from(session),
+    }))
+}
+
 pub async fn update_gym_session(
     State(state): State<AppState>,
     RequireVerifiedAuth(user): RequireVerifiedAuth,
     Path(id): Path<Uuid>,
-    Json(payload): Json<UpdateGymSessionRequest>,
+    Json(mut payload): Json<UpdateGymSessionRequest>,
 ) -> Result<Json<GymSessionResponse>, impl IntoResponse> {
     info!("Updating gym session {} for user: {}", id, user.id);
 
@@ -336,7 +868,7 @@ pub async fn update_gym_session(
     }
 
     // Check if the session exists and belongs to the user
-    match state
+    let session = match state
         .repositories
         .gym_session_repository
         .find_by_id(&id)
@@ -346,18 +878,47 @@ pub async fn update_gym_session(
             if session.user_id != user.id {
                 return Err(StatusCode::FORBIDDEN.into_response());
             }
+            session
         }
         Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
         Err(err) => {
             error!("Failed to fetch gym session: {}", err);
             return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
         }
+    };
+
+    let started_at = payload.started_at.unwrap_or(session.started_at);
+    let ended_at = payload.ended_at.unwrap_or(session.ended_at);
+    if let (Some(started_at), Some(ended_at)) = (started_at, ended_at)
+        && ended_at < started_at
+    {
+        return Err(session_times_error());
+    }
+
+    let date = payload.date.unwrap_or(session.date);
+    match payload.user_weight_id {
+        Some(Some(user_weight_id)) => {
+            match is_weight_of_day(&state, &user.id, &user_weight_id, date).await {
+                Ok(true) => {}
+                Ok(false) => return Err(user_weight_error()),
+                Err(err) => {
+                    error!("Failed to fetch user weight: {}", err);
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+                }
+            }
+        }
+        Some(None) => {}
+        // The weight of another day is not linked anymore once the session moves
+        None if date != session.date && session.user_weight_id.is_some() => {
+            payload.user_weight_id = Some(None);
+        }
+        None => {}
     }
 
     match state
         .repositories
         .gym_session_repository
-        .update(id, payload.date)
+        .update(id, payload)
         .await
     {
         Ok(session) => Ok(Json(GymSessionResponse::from(session))),
@@ -445,15 +1006,15 @@ pub async fn create_gym_set(
         }
     }
 
-    // Check if the exercise exists
+    // Check if the exercise exists and is visible to the user
     match state
         .repositories
         .gym_exercise_repository
         .find_by_id(&payload.exercise_id)
         .await
     {
-        Ok(Some(_)) => {}
-        Ok(None) => {
+        Ok(Some(exercise)) if exercise.is_visible_to(&user.id) => {}
+        Ok(_) => {
             return Err((
                 StatusCode::BAD_REQUEST,
                 Json(json!({"error": "Exercise not found"})),
@@ -469,16 +1030,11 @@ pub async fn create_gym_set(
     match state
         .repositories
         .gym_set_repository
-        .create(
-            session_id,
-            payload.exercise_id,
-            payload.set_number,
-            payload.repetitions,
-            payload.weight_kg,
-        )
+        .create(user.id, session_id, payload)
         .await
     {
         Ok(gym_set) => Ok(Json(GymSetResponse::from(gym_set))),
+        Err(err) if is_duplicate_set_number(&err) => Err(duplicate_set_number()),
         Err(err) => {
             error!("Failed to create gym set: {}", err);
             Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
@@ -486,16 +1042,118 @@ pub async fn create_gym_set(
     }
 }
 
+/// Creates, updates, deletes and renumbers sets of a session in a single transaction
+pub async fn bulk_gym_sets(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(session_id): Path<Uuid>,
+    Json(payload): Json<BulkGymSetsRequest>,
+) -> Result<Json<Vec<GymSetResponse>>, impl IntoResponse> {
+    info!(
+        "Applying bulk gym set changes to session {} for user: {}",
+        session_id, user.id
+    );
+
+    if let Err(err) = payload.validate() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": err.to_string()})),
+        )
+            .into_response());
+    }
+
+    // Check if the session exists and belongs to the user
+    match state
+        .repositories
+        .gym_session_repository
+        .find_by_id(&session_id)
+        .await
+    {
+        Ok(Some(session)) => {
+            if session.user_id != user.id {
+                return Err(StatusCode::FORBIDDEN.into_response());
+            }
+        }
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch gym session: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    // Check if the exercises of the new sets exist and are visible to the user
+    let mut exercise_ids: Vec<Uuid> = payload.create.iter().map(|set| set.exercise_id).collect();
+    exercise_ids.sort();
+    exercise_ids.dedup();
+    match state
+        .repositories
+        .gym_exercise_repository
+        .find_by_ids(exercise_ids.clone())
+        .await
+    {
+        Ok(exercises)
+            if exercises.len() == exercise_ids.len()
+                && exercises
+                    .iter()
+                    .all(|exercise| exercise.is_visible_to(&user.id)) => {}
+        Ok(_) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "Exercise not found"})),
+            )
+                .into_response());
+        }
+        Err(err) => {
+            error!("Failed to fetch gym exercises: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    let existing = match state
+        .repositories
+        .gym_set_repository
+        .find_by_session_id(&session_id)
+        .await
+    {
+        Ok(sets) => sets,
+        Err(err) => {
+            error!("Failed to fetch gym sets: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+    let numbers = match number_sets(&existing, &payload) {
+        Ok(numbers) => numbers,
+        Err(err) => return Err(bulk_sets_error(err)),
+    };
+
+    match state
+        .repositories
+        .gym_set_repository
+        .apply_bulk(user.id, session_id, payload, numbers)
+        .await
+    {
+        Ok(sets) => Ok(Json(sets.into_iter().map(GymSetResponse::from).collect())),
+        Err(err) if is_duplicate_set_number(&err) => Err(duplicate_set_number()),
+        Err(err) => {
+            error!("Failed to apply bulk gym set changes: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
 pub async fn get_gym_sets(
     State(state): State<AppState>,
     RequireVerifiedAuth(user): RequireVerifiedAuth,
     Path(session_id): Path<Uuid>,
+    Query(query): Query<OneRepMaxQuery>,
 ) -> Result<Json<Vec<GymSetResponse>>, impl IntoResponse> {
     info!(
         "Fetching gym sets for session {} for user: {}",
         session_id, user.id
     );
 
+    let formula = query.formula.unwrap_or(OneRepMaxFormulaEnum::Epley);
+
     // Check if the session exists and belongs to the user
     match state
         .repositories
@@ -515,6 +1173,56 @@ pub async fn get_gym_sets(
         }
     }
 
+    match state
+        .repositories
+        .gym_set_repository
+        .find_by_session_id(&session_id)
+        .await
+    {
+        Ok(sets) => {
+            let response: Vec<GymSetResponse> = sets
+                .into_iter()
+                .map(|set| GymSetResponse::with_formula(set, &formula))
+                .collect();
+            Ok(Json(response))
+        }
+        Err(err) => {
+            error!("Failed to fetch gym sets: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+/// Get sets of another user's gym session if the current user has permission to view them
+pub async fn get_other_user_gym_sets(
+    State(state): State<AppState>,
+    ViewUserData {
+        requesting_user,
+        target_user_id,
+        ..
+    }: ViewUserData<GymScope>,
+    Path((_, session_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<GymSetResponse>>, impl IntoResponse> {
+    info!(
+        "User {} fetching gym sets for session {} of user: {}",
+        requesting_user.id, session_id, target_user_id
+    );
+
+    // Check if the session exists and belongs to the watched user
+    match state
+        .repositories
+        .gym_session_repository
+        .find_by_id(&session_id)
+        .await
+    {
+        Ok(Some(session)) if session.user_id == target_user_id => {}
+        Ok(_) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch gym session: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
     match state
         .repositories
         .gym_set_repository
@@ -593,15 +1301,11 @@ pub async fn update_gym_set(
     match state
         .repositories
         .gym_set_repository
-        .update(
-            set_id,
-            payload.set_number,
-            payload.repetitions,
-            payload.weight_kg,
-        )
+        .update(user.id, set_id, payload)
         .await
     {
         Ok(gym_set) => Ok(Json(GymSetResponse::from(gym_set))),
+        Err(err) if is_duplicate_set_number(&err) => Err(duplicate_set_number()),
         Err(err) => {
             error!("Failed to update gym set: {}", err);
             Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
@@ -657,7 +1361,12 @@ pub async fn delete_gym_set(
         }
     }
 
-    match state.repositories.gym_set_repository.delete(&set_id).await {
+    match state
+        .repositories
+        .gym_set_repository
+        .delete(user.id, &set_id)
+        .await
+    {
         Ok(_) => Ok(StatusCode::NO_CONTENT),
         Err(err) => {
             error!("Failed to delete gym set: {}", err);
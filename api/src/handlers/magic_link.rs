@@ -0,0 +1,152 @@
+use crate::{
+    auth::{client_info::ClientInfo, login_security},
+    axummain::state::AppState,
+    handlers::auth::complete_login,
+    schemas::magic_link_schemas::{MagicLinkRequest, MagicLinkResponse, VerifyMagicLinkRequest},
+    utils::{
+        get_now_time_paris::now_paris_fixed, guest_name_generator::GUEST_EMAIL_DOMAIN,
+        token_generator::generate_verification_token,
+    },
+};
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::Duration;
+use serde_json::json;
+use tracing::{debug, error, info};
+use validator::Validate;
+
+/// Send a single-use login link by email
+pub async fn request_magic_link(
+    State(state): State<AppState>,
+    Json(payload): Json<MagicLinkRequest>,
+) -> Result<Json<MagicLinkResponse>, Response> {
+    info!("Received magic link request for email: {}", payload.email);
+
+    if let Err(err) = payload.validate() {
+        info!("Validation error during magic link request: {}", err);
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": err.to_string()})),
+        )
+            .into_response());
+    }
+
+    let ok_response = Ok(Json(MagicLinkResponse {
+        message: "If that email exists, a login link has been sent.".to_string(),
+    }));
+
+    let user = state
+        .repositories
+        .user_repository
+        .find_by_email(&payload.email)
+        .await
+        .map_err(|err| {
+            error!("Failed to query user by email because: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    // Same answer whatever the email, so that registered emails cannot be discovered.
+    // Guests have no real address to send the link to.
+    let user = match user {
+        Some(user) if !user.email.ends_with(GUEST_EMAIL_DOMAIN) => user,
+        _ => {
+            info!(
+                "Magic link request for non-existing email: {}",
+                payload.email
+            );
+            return ok_response;
+        }
+    };
+
+    let token = generate_verification_token();
+    // If updated, need to be changed in the mail too
+    let expires_at = now_paris_fixed(Duration::minutes(15));
+
+    state
+        .repositories
+        .magic_link_repository
+        .create_token(&user.id, &token, &expires_at)
+        .await
+        .map_err(|err| {
+            error!("Failed to create magic link token because: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    debug!("Sending magic link email to {}", user.email);
+    state
+        .jobs
+        .email_job
+        .send_magic_link_email(&user.email, &user.username, &token)
+        .await
+        .map_err(|err| {
+            error!("Failed to send magic link email: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    ok_response
+}
+
+/// Exchange a login link for the tokens, like a login with the right password
+pub async fn verify_magic_link(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(payload): Json<VerifyMagicLinkRequest>,
+) -> Result<Response, Response> {
+    info!("Received magic link verification");
+
+    let invalid_link = || {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid or expired login link"})),
+        )
+            .into_response()
+    };
+
+    let magic_link = state
+        .repositories
+        .magic_link_repository
+        .consume_token(&payload.token)
+        .await
+        .map_err(|err| {
+            error!("Failed to consume magic link token: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(invalid_link)?;
+
+    match login_security::account_locked_for(&state, &magic_link.user_id).await {
+        Ok(Some(_)) => {
+            info!("Magic link used on locked account: {}", magic_link.user_id);
+            return Err(StatusCode::LOCKED.into_response());
+        }
+        Ok(None) => {}
+        Err(err) => error!("Failed to check account lock: {}", err),
+    }
+
+    let user = state
+        .repositories
+        .user_repository
+        .find_by_id(&magic_link.user_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch user: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(invalid_link)?;
+
+    // The other links sent before are not needed anymore
+    if let Err(err) = state
+        .repositories
+        .magic_link_repository
+        .delete_all_user_tokens(&user.id)
+        .await
+    {
+        error!("Failed to delete magic link tokens: {}", err);
+    }
+
+    complete_login(&state, user, &client).await
+}
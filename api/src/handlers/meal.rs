@@ -1,5 +1,10 @@
 use crate::{
-    auth::middleware::RequireVerifiedAuth, axummain::state::AppState, schemas::meal_schemas::*,
+    auth::{
+        middleware::RequireVerifiedAuth,
+        resource_authorization::{MealsScope, ViewUserData},
+    },
+    axummain::state::AppState,
+    schemas::meal_schemas::*,
 };
 use axum::{
     Json,
@@ -75,6 +80,43 @@ pub async fn get_meals(
         })
 }
 
+/// Get meals of another user if the current user has permission to view them
+pub async fn get_other_user_meals(
+    State(state): State<AppState>,
+    ViewUserData {
+        requesting_user,
+        target_user_id,
+        ..
+    }: ViewUserData<MealsScope>,
+    Query(query): Query<DateQuery>,
+) -> Result<Json<Vec<MealResponse>>, impl IntoResponse> {
+    info!(
+        "User {} fetching meals for user: {}",
+        requesting_user.id, target_user_id
+    );
+
+    let meals_result = if let Some(date) = query.date {
+        state
+            .repositories
+            .meal_repository
+            .find_by_user_and_date(&target_user_id, date)
+            .await
+    } else {
+        state
+            .repositories
+            .meal_repository
+            .find_by_user_id(&target_user_id)
+            .await
+    };
+
+    meals_result
+        .map(|meals| Json(meals.into_iter().map(MealResponse::from).collect()))
+        .map_err(|err| {
+            error!("Failed to fetch meals: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
+
 pub async fn update_meal(
     State(state): State<AppState>,
     RequireVerifiedAuth(user): RequireVerifiedAuth,
@@ -187,8 +229,8 @@ pub async fn add_meal_item(
         return Err(StatusCode::FORBIDDEN.into_response());
     }
 
-    // Check if the food item exists
-    if state
+    // Check if the food item exists and is visible to the user
+    if !state
         .repositories
         .food_item_repository
         .find_by_id(&payload.food_item_id)
@@ -197,7 +239,7 @@ pub async fn add_meal_item(
             error!("Failed to fetch food item: {}", err);
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
         })?
-        .is_none()
+        .is_some_and(|food_item| food_item.is_visible_to(&user.id))
     {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -253,6 +295,49 @@ pub async fn get_meal_items(
         })
 }
 
+/// Get items of another user's meal if the current user has permission to view them
+pub async fn get_other_user_meal_items(
+    State(state): State<AppState>,
+    ViewUserData {
+        requesting_user,
+        target_user_id,
+        ..
+    }: ViewUserData<MealsScope>,
+    Path((_, meal_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<MealItemResponse>>, impl IntoResponse> {
+    info!(
+        "User {} fetching items for meal {} of user: {}",
+        requesting_user.id, meal_id, target_user_id
+    );
+
+    // Check if the meal exists and belongs to the watched user
+    let meal = state
+        .repositories
+        .meal_repository
+        .find_by_id(&meal_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch meal: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+    if meal.user_id != target_user_id {
+        return Err(StatusCode::NOT_FOUND.into_response());
+    }
+
+    state
+        .repositories
+        .meal_item_repository
+        .find_by_meal_id(&meal_id)
+        .await
+        .map(|meal_items| Json(meal_items.into_iter().map(MealItemResponse::from).collect()))
+        .map_err(|err| {
+            error!("Failed to fetch meal items: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
+
 pub async fn update_meal_item(
     State(state): State<AppState>,
     RequireVerifiedAuth(user): RequireVerifiedAuth,
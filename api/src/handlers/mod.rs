@@ -1,9 +1,20 @@
+pub mod account_deletion;
 pub mod auth;
+pub mod data_export;
+pub mod data_import;
 pub mod food_item;
 pub mod gym;
+pub mod magic_link;
 pub mod meal;
+pub mod moderation;
 pub mod server_health;
 pub mod settings;
+pub mod training_program;
+pub mod two_factor;
 pub mod user_group;
+pub mod user_info;
 pub mod user_watch_permissions;
 pub mod user_weight;
+pub mod watch_request;
+pub mod watching;
+pub mod workout_template;
@@ -0,0 +1,259 @@
+use crate::{
+    auth::middleware::RequireAdmin,
+    axummain::state::AppState,
+    schemas::{food_item_schemas::FoodItemResponse, gym_schemas::GymExerciseResponse},
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use entities::sea_orm_active_enums::VisibilityEnum;
+use serde_json::json;
+use tracing::{error, info};
+use uuid::Uuid;
+
+fn not_pending() -> axum::response::Response {
+    (
+        StatusCode::CONFLICT,
+        Json(json!({"error": "Only items pending review can be moderated"})),
+    )
+        .into_response()
+}
+
+// ============================================================================
+// Gym exercise moderation
+// ============================================================================
+
+pub async fn get_pending_gym_exercises(
+    State(state): State<AppState>,
+    RequireAdmin(user): RequireAdmin,
+) -> Result<Json<Vec<GymExerciseResponse>>, impl IntoResponse> {
+    info!("Fetching pending gym exercises for moderator: {}", user.id);
+
+    match state
+        .repositories
+        .gym_exercise_repository
+        .find_pending()
+        .await
+    {
+        Ok(exercises) => Ok(Json(exercises)),
+        Err(err) => {
+            error!("Failed to fetch pending gym exercises: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn approve_gym_exercise(
+    State(state): State<AppState>,
+    RequireAdmin(user): RequireAdmin,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, impl IntoResponse> {
+    info!("Approving gym exercise {} by moderator: {}", id, user.id);
+
+    let exercise = match state
+        .repositories
+        .gym_exercise_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(exercise)) => exercise,
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch gym exercise: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+
+    if exercise.visibility != VisibilityEnum::Pending {
+        return Err(not_pending());
+    }
+
+    // The name may have been taken while the exercise was waiting for review
+    match state
+        .repositories
+        .gym_exercise_repository
+        .find_public_duplicate(id, &exercise.name)
+        .await
+    {
+        Ok(Some(duplicate)) => {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": "A public exercise with this name already exists",
+                    "duplicate_id": duplicate.id,
+                })),
+            )
+                .into_response());
+        }
+        Ok(None) => {}
+        Err(err) => {
+            error!("Failed to check for duplicate gym exercises: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    match state
+        .repositories
+        .gym_exercise_repository
+        .set_visibility(id, VisibilityEnum::Public)
+        .await
+    {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(err) => {
+            error!("Failed to approve gym exercise: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn reject_gym_exercise(
+    State(state): State<AppState>,
+    RequireAdmin(user): RequireAdmin,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, impl IntoResponse> {
+    info!("Rejecting gym exercise {} by moderator: {}", id, user.id);
+
+    match state
+        .repositories
+        .gym_exercise_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(exercise)) if exercise.visibility == VisibilityEnum::Pending => {}
+        Ok(Some(_)) => return Err(not_pending()),
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch gym exercise: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    // A rejected exercise stays available to its creator
+    match state
+        .repositories
+        .gym_exercise_repository
+        .set_visibility(id, VisibilityEnum::Private)
+        .await
+    {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(err) => {
+            error!("Failed to reject gym exercise: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+// ============================================================================
+// Food item moderation
+// ============================================================================
+
+pub async fn get_pending_food_items(
+    State(state): State<AppState>,
+    RequireAdmin(user): RequireAdmin,
+) -> Result<Json<Vec<FoodItemResponse>>, impl IntoResponse> {
+    info!("Fetching pending food items for moderator: {}", user.id);
+
+    state
+        .repositories
+        .food_item_repository
+        .find_pending()
+        .await
+        .map(|items| Json(items.into_iter().map(FoodItemResponse::from).collect()))
+        .map_err(|err| {
+            error!("Failed to fetch pending food items: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
+
+pub async fn approve_food_item(
+    State(state): State<AppState>,
+    RequireAdmin(user): RequireAdmin,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, impl IntoResponse> {
+    info!("Approving food item {} by moderator: {}", id, user.id);
+
+    let food_item = state
+        .repositories
+        .food_item_repository
+        .find_by_id(&id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch food item: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+    if food_item.visibility != VisibilityEnum::Pending {
+        return Err(not_pending());
+    }
+
+    // The name may have been taken while the food item was waiting for review
+    let duplicate = state
+        .repositories
+        .food_item_repository
+        .find_public_duplicate(id, &food_item.name)
+        .await
+        .map_err(|err| {
+            error!("Failed to check for duplicate food items: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    if let Some(duplicate) = duplicate {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "A public food item with this name already exists",
+                "duplicate_id": duplicate.id,
+            })),
+        )
+            .into_response());
+    }
+
+    state
+        .repositories
+        .food_item_repository
+        .set_visibility(id, VisibilityEnum::Public)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(|err| {
+            error!("Failed to approve food item: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
+
+pub async fn reject_food_item(
+    State(state): State<AppState>,
+    RequireAdmin(user): RequireAdmin,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, impl IntoResponse> {
+    info!("Rejecting food item {} by moderator: {}", id, user.id);
+
+    let food_item = state
+        .repositories
+        .food_item_repository
+        .find_by_id(&id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch food item: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+    if food_item.visibility != VisibilityEnum::Pending {
+        return Err(not_pending());
+    }
+
+    // A rejected food item stays available to its creator
+    state
+        .repositories
+        .food_item_repository
+        .set_visibility(id, VisibilityEnum::Private)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(|err| {
+            error!("Failed to reject food item: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
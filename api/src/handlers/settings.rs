@@ -30,6 +30,13 @@ pub async fn update_settings(
         )
             .into_response());
     }
+    // Checked before anything is updated
+    if let Some(ref passwords) = payload.passwords
+        && let Err(err) = state.password_policy.check(&passwords.new_password)
+    {
+        info!("Password policy violation during settings update: {}", err);
+        return Err(err.into_response());
+    }
 
     let mut message = Vec::new();
 
@@ -96,9 +103,12 @@ pub async fn update_settings(
         }
 
         // Hash and update new password
-        let new_password_hash = hash_password_async(passwords.new_password.clone(), None)
-            .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+        let new_password_hash = hash_password_async(
+            passwords.new_password.clone(),
+            state.password_policy.hashing,
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
 
         debug!("Updating password for user {}", user.id);
         state
@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+
+use crate::{
+    auth::middleware::RequireVerifiedAuth,
+    axummain::state::AppState,
+    gym::programs::{next_target, program_week, scheduled_days},
+    schemas::training_program_schemas::*,
+    utils::get_now_time_paris::now_paris_fixed,
+};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{Duration, NaiveDate};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Deserialize)]
+pub struct TodayWorkoutQuery {
+    /// Today in Paris when not given
+    pub date: Option<NaiveDate>,
+}
+
+/// Whether every template of the days exists and belongs to the user
+async fn are_templates_owned(
+    state: &AppState,
+    user_id: &Uuid,
+    days: &[ProgramDayRequest],
+) -> Result<bool, sea_orm::DbErr> {
+    let mut template_ids: Vec<Uuid> = days.iter().map(|d| d.template_id).collect();
+    template_ids.sort();
+    template_ids.dedup();
+
+    let found = state
+        .repositories
+        .workout_template_repository
+        .find_by_ids(template_ids.clone())
+        .await?;
+    Ok(found.len() == template_ids.len()
+        && found.iter().all(|template| template.user_id == *user_id))
+}
+
+fn template_not_found() -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": "Workout template not found"})),
+    )
+        .into_response()
+}
+
+fn day_outside_program() -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": "Days must be within the weeks of the program"})),
+    )
+        .into_response()
+}
+
+pub async fn create_training_program(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Json(payload): Json<CreateTrainingProgramRequest>,
+) -> Result<Json<TrainingProgramResponse>, impl IntoResponse> {
+    info!("Creating training program for user: {}", user.id);
+
+    if let Err(err) = payload.validate() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": err.to_string()})),
+        )
+            .into_response());
+    }
+
+    if payload
+        .days
+        .iter()
+        .any(|day| day.week.is_some_and(|week| week > payload.weeks))
+    {
+        return Err(day_outside_program());
+    }
+
+    match are_templates_owned(&state, &user.id, &payload.days).await {
+        Ok(true) => {}
+        Ok(false) => return Err(template_not_found()),
+        Err(err) => {
+            error!("Failed to fetch workout templates: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    match state
+        .repositories
+        .training_program_repository
+        .create(user.id, payload)
+        .await
+    {
+        Ok(program) => Ok(Json(program)),
+        Err(err) => {
+            error!("Failed to create training program: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn get_training_programs(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+) -> Result<Json<Vec<TrainingProgramResponse>>, impl IntoResponse> {
+    info!("Fetching training programs for user: {}", user.id);
+
+    match state
+        .repositories
+        .training_program_repository
+        .find_by_user_id(&user.id)
+        .await
+    {
+        Ok(programs) => Ok(Json(programs)),
+        Err(err) => {
+            error!("Failed to fetch training programs: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn get_training_program(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TrainingProgramResponse>, impl IntoResponse> {
+    info!("Fetching training program {} for user: {}", id, user.id);
+
+    match state
+        .repositories
+        .training_program_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(program)) => {
+            if program.user_id != user.id {
+                return Err(StatusCode::FORBIDDEN.into_response());
+            }
+        }
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch training program: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    match state
+        .repositories
+        .training_program_repository
+        .find_by_id_with_days(&id)
+        .await
+    {
+        Ok(Some(program)) => Ok(Json(program)),
+        Ok(None) => Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch training program: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn update_training_program(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTrainingProgramRequest>,
+) -> Result<Json<TrainingProgramResponse>, impl IntoResponse> {
+    info!("Updating training program {} for user: {}", id, user.id);
+
+    if let Err(err) = payload.validate() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": err.to_string()})),
+        )
+            .into_response());
+    }
+
+    // Check if the program exists and belongs to the user
+    let program = match state
+        .repositories
+        .training_program_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(program)) => {
+            if program.user_id != user.id {
+                return Err(StatusCode::FORBIDDEN.into_response());
+            }
+            program
+        }
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch training program: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+
+    // Shortening the program must not leave days after its end
+    let weeks = payload.weeks.unwrap_or(program.weeks);
+    let day_weeks: Vec<Option<i32>> = match &payload.days {
+        Some(days) => days.iter().map(|day| day.week).collect(),
+        None => match state
+            .repositories
+            .training_program_repository
+            .find_by_id_with_days(&id)
+            .await
+        {
+            Ok(program) => program
+                .map(|program| program.days.iter().map(|day| day.week).collect())
+                .unwrap_or_default(),
+            Err(err) => {
+                error!("Failed to fetch training program days: {}", err);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+            }
+        },
+    };
+    if day_weeks
+        .iter()
+        .any(|week| week.is_some_and(|week| week > weeks))
+    {
+        return Err(day_outside_program());
+    }
+
+    if let Some(days) = &payload.days {
+        match are_templates_owned(&state, &user.id, days).await {
+            Ok(true) => {}
+            Ok(false) => return Err(template_not_found()),
+            Err(err) => {
+                error!("Failed to fetch workout templates: {}", err);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+            }
+        }
+    }
+
+    match state
+        .repositories
+        .training_program_repository
+        .update(id, payload)
+        .await
+    {
+        Ok(program) => Ok(Json(program)),
+        Err(err) => {
+            error!("Failed to update training program: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn delete_training_program(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, impl IntoResponse> {
+    info!("Deleting training program {} for user: {}", id, user.id);
+
+    // Check if the program exists and belongs to the user
+    match state
+        .repositories
+        .training_program_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(program)) => {
+            if program.user_id != user.id {
+                return Err(StatusCode::FORBIDDEN.into_response());
+            }
+        }
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch training program: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    match state
+        .repositories
+        .training_program_repository
+        .delete(&id)
+        .await
+    {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(err) => {
+            error!("Failed to delete training program: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+/// Workouts scheduled on the date by the running programs of the user, empty on rest
+/// days. The targets replay the progression rules over the sessions done since the
+/// start of each program, the ones of the date itself aside.
+pub async fn get_today_workout(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Query(query): Query<TodayWorkoutQuery>,
+) -> Result<Json<Vec<TodayWorkoutResponse>>, impl IntoResponse> {
+    info!("Fetching today's workout for user: {}", user.id);
+
+    let date = query
+        .date
+        .unwrap_or_else(|| now_paris_fixed(Duration::zero()).date_naive());
+
+    let programs = match state
+        .repositories
+        .training_program_repository
+        .find_started_with_days(&user.id, date)
+        .await
+    {
+        Ok(programs) => programs,
+        Err(err) => {
+            error!("Failed to fetch training programs: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+
+    let mut workouts = Vec::new();
+    for (program, days) in programs {
+        let Some(week) = program_week(&program, date) else {
+            continue;
+        };
+        let days = scheduled_days(&program, &days, date);
+        if days.is_empty() {
+            continue;
+        }
+
+        let templates: HashMap<Uuid, String> = match state
+            .repositories
+            .workout_template_repository
+            .find_by_ids(days.iter().map(|d| d.template_id).collect())
+            .await
+        {
+            Ok(templates) => templates.into_iter().map(|t| (t.id, t.name)).collect(),
+            Err(err) => {
+                error!("Failed to fetch workout templates: {}", err);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+            }
+        };
+
+        let history = match state
+            .repositories
+            .gym_set_repository
+            .find_by_user_with_dates(&user.id, None, Some(program.start_date), date.pred_opt())
+            .await
+        {
+            Ok(history) => history,
+            Err(err) => {
+                error!("Failed to fetch gym sets: {}", err);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+            }
+        };
+
+        for day in days {
+            let exercises = match state
+                .repositories
+                .workout_template_repository
+                .find_exercises(&day.template_id)
+                .await
+            {
+                Ok(exercises) => exercises,
+                Err(err) => {
+                    error!("Failed to fetch workout template exercises: {}", err);
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+                }
+            };
+
+            let names: HashMap<Uuid, String> = match state
+                .repositories
+                .gym_exercise_repository
+                .find_by_ids(exercises.iter().map(|e| e.exercise_id).collect())
+                .await
+            {
+                Ok(found) => found.into_iter().map(|e| (e.id, e.name)).collect(),
+                Err(err) => {
+                    error!("Failed to fetch gym exercises: {}", err);
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+                }
+            };
+
+            let exercises = exercises
+                .iter()
+                .map(|exercise| {
+                    let exercise_history: Vec<_> = history
+                        .iter()
+                        .filter(|(_, set)| set.exercise_id == exercise.exercise_id)
+                        .cloned()
+                        .collect();
+                    let target = next_target(&program, exercise, &exercise_history);
+                    ExerciseTargetResponse {
+                        exercise_id: exercise.exercise_id,
+                        exercise_name: names
+                            .get(&exercise.exercise_id)
+                            .cloned()
+                            .unwrap_or_default(),
+                        position: exercise.position,
+                        target_sets: target.sets,
+                        target_repetitions: target.repetitions,
+                        target_weight_kg: target.weight_kg,
+                        consecutive_failures: target.consecutive_failures,
+                        is_deload: target.is_deload,
+                    }
+                })
+                .collect();
+
+            workouts.push(TodayWorkoutResponse {
+                program_id: program.id,
+                program_name: program.name.clone(),
+                week,
+                template_id: day.template_id,
+                template_name: templates.get(&day.template_id).cloned().unwrap_or_default(),
+                exercises,
+            });
+        }
+    }
+
+    Ok(Json(workouts))
+}
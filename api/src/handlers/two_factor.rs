@@ -0,0 +1,399 @@
+use crate::{
+    auth::{
+        client_info::ClientInfo,
+        login_security,
+        middleware::{RequireAuth, RequireVerifiedAuth},
+        password::verify_password_async,
+        totp, two_factor,
+    },
+    axummain::state::AppState,
+    handlers::auth::create_login_response,
+    schemas::{auth_schemas::LoginResponse, two_factor_schemas::*},
+};
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use entities::user_two_factor;
+use serde_json::json;
+use tracing::{error, info};
+
+/// Is two-factor authentication enabled for me
+pub async fn get_two_factor_status(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+) -> Result<Json<TwoFactorStatusResponse>, StatusCode> {
+    info!("User {} fetching two-factor status", user.id);
+
+    let two_factor = state
+        .repositories
+        .two_factor_repository
+        .find_by_user(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch two-factor settings: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let enabled = two_factor.is_some_and(|two_factor| two_factor.enabled);
+    let recovery_codes_left = if enabled {
+        state
+            .repositories
+            .two_factor_repository
+            .count_unused_recovery_codes(&user.id)
+            .await
+            .map_err(|err| {
+                error!("Failed to count recovery codes: {}", err);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+    } else {
+        0
+    };
+
+    Ok(Json(TwoFactorStatusResponse {
+        enabled,
+        recovery_codes_left,
+    }))
+}
+
+/// Start the enrollment: generate a secret to add to an authenticator app.
+/// It is only active once confirmed with a first code.
+pub async fn setup_two_factor(
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    State(state): State<AppState>,
+) -> Result<Json<TwoFactorSetupResponse>, Response> {
+    info!("User {} starting two-factor enrollment", user.id);
+
+    let existing = state
+        .repositories
+        .two_factor_repository
+        .find_by_user(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch two-factor settings: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    if existing
+        .as_ref()
+        .is_some_and(|two_factor| two_factor.enabled)
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Two-factor authentication is already enabled"})),
+        )
+            .into_response());
+    }
+
+    let secret = totp::generate_secret();
+    state
+        .repositories
+        .two_factor_repository
+        .save_pending_secret(existing, &user.id, &secret)
+        .await
+        .map_err(|err| {
+            error!("Failed to save two-factor secret: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok(Json(TwoFactorSetupResponse {
+        otpauth_uri: totp::otpauth_uri(&secret, &user.email),
+        secret,
+    }))
+}
+
+/// Finish the enrollment with a code of the authenticator app, returns the recovery codes
+pub async fn confirm_two_factor(
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    State(state): State<AppState>,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<Json<RecoveryCodesResponse>, Response> {
+    info!("User {} confirming two-factor enrollment", user.id);
+
+    let two_factor = state
+        .repositories
+        .two_factor_repository
+        .find_by_user(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch two-factor settings: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "No two-factor enrollment in progress"})),
+            )
+                .into_response()
+        })?;
+
+    if two_factor.enabled {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Two-factor authentication is already enabled"})),
+        )
+            .into_response());
+    }
+
+    let Some(step) = totp::verify_code(&two_factor.secret, &payload.code, unix_now()) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Invalid code"})),
+        )
+            .into_response());
+    };
+
+    let recovery_codes = two_factor::generate_recovery_codes();
+    let hashes: Vec<String> = recovery_codes
+        .iter()
+        .map(|code| two_factor::hash_recovery_code(code))
+        .collect();
+
+    state
+        .repositories
+        .two_factor_repository
+        .enable(two_factor, step as i64, &hashes)
+        .await
+        .map_err(|err| {
+            error!("Failed to enable two-factor authentication: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+/// Second step of the login, exchange the challenge of the login and a code for the tokens
+pub async fn verify_two_factor(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(payload): Json<VerifyTwoFactorRequest>,
+) -> Result<Json<LoginResponse>, Response> {
+    let invalid_challenge = || {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid or expired challenge, please log in again"})),
+        )
+            .into_response()
+    };
+
+    let user_id = two_factor::find_challenge(&state.redis, &payload.challenge_token)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch two-factor challenge: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(invalid_challenge)?;
+
+    info!("Verifying two-factor code for user {}", user_id);
+
+    match login_security::account_locked_for(&state, &user_id).await {
+        Ok(Some(_)) => return Err(StatusCode::LOCKED.into_response()),
+        Ok(None) => {}
+        Err(err) => error!("Failed to check account lock: {}", err),
+    }
+
+    let user = state
+        .repositories
+        .user_repository
+        .find_by_id(&user_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch user: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(invalid_challenge)?;
+
+    let two_factor = find_enabled_two_factor(&state, &user.id)
+        .await?
+        .ok_or_else(invalid_challenge)?;
+
+    if !check_second_factor(&state, two_factor, &payload.second_factor).await? {
+        info!("Invalid two-factor code for user {}", user.id);
+        if let Err(err) =
+            two_factor::record_challenge_failure(&state.redis, &payload.challenge_token).await
+        {
+            error!("Failed to record two-factor failure: {}", err);
+        }
+        match login_security::record_failed_login(&state, Some(&user.id), client.ip.as_deref())
+            .await
+        {
+            Ok(true) => login_security::send_lockout_alert(&state, &user, &client).await,
+            Ok(false) => {}
+            Err(err) => error!("Failed to record failed login: {}", err),
+        }
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid code"})),
+        )
+            .into_response());
+    }
+
+    if let Err(err) = two_factor::consume_challenge(&state.redis, &payload.challenge_token).await {
+        error!("Failed to consume two-factor challenge: {}", err);
+    }
+    if let Err(err) = login_security::clear_failed_logins(&state, &user.id).await {
+        error!("Failed to clear failed logins: {}", err);
+    }
+    login_security::track_login_device(&state, &user, &client).await;
+
+    let response = create_login_response(&state, user)
+        .await
+        .map_err(|status| status.into_response())?;
+
+    Ok(Json(response))
+}
+
+/// Turn two-factor authentication off, needs both the password and a second factor
+pub async fn disable_two_factor(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+    Json(payload): Json<DisableTwoFactorRequest>,
+) -> Result<Json<TwoFactorStatusResponse>, Response> {
+    info!("User {} disabling two-factor authentication", user.id);
+
+    let password_valid = verify_password_async(payload.password, user.password_hash.clone())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+    if !password_valid {
+        return Err(StatusCode::UNAUTHORIZED.into_response());
+    }
+
+    let two_factor = find_enabled_two_factor(&state, &user.id)
+        .await?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+    if !check_second_factor(&state, two_factor, &payload.second_factor).await? {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid code"})),
+        )
+            .into_response());
+    }
+
+    state
+        .repositories
+        .two_factor_repository
+        .disable(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to disable two-factor authentication: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok(Json(TwoFactorStatusResponse {
+        enabled: false,
+        recovery_codes_left: 0,
+    }))
+}
+
+/// Replace all my recovery codes, needs a code of the authenticator app
+pub async fn regenerate_recovery_codes(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<Json<RecoveryCodesResponse>, Response> {
+    info!("User {} regenerating recovery codes", user.id);
+
+    let two_factor = find_enabled_two_factor(&state, &user.id)
+        .await?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+    let second_factor = SecondFactor {
+        code: Some(payload.code),
+        recovery_code: None,
+    };
+    if !check_second_factor(&state, two_factor, &second_factor).await? {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid code"})),
+        )
+            .into_response());
+    }
+
+    let recovery_codes = two_factor::generate_recovery_codes();
+    let hashes: Vec<String> = recovery_codes
+        .iter()
+        .map(|code| two_factor::hash_recovery_code(code))
+        .collect();
+
+    state
+        .repositories
+        .two_factor_repository
+        .replace_recovery_codes(&user.id, &hashes)
+        .await
+        .map_err(|err| {
+            error!("Failed to replace recovery codes: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+async fn find_enabled_two_factor(
+    state: &AppState,
+    user_id: &uuid::Uuid,
+) -> Result<Option<user_two_factor::Model>, Response> {
+    let two_factor = state
+        .repositories
+        .two_factor_repository
+        .find_by_user(user_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch two-factor settings: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok(two_factor.filter(|two_factor| two_factor.enabled))
+}
+
+/// Check a code of the authenticator app or use up a recovery code.
+/// A code is refused if it is not newer than the last accepted one, so it cannot be replayed.
+async fn check_second_factor(
+    state: &AppState,
+    two_factor: user_two_factor::Model,
+    second_factor: &SecondFactor,
+) -> Result<bool, Response> {
+    let repository = &state.repositories.two_factor_repository;
+
+    if let Some(code) = second_factor.code.as_deref() {
+        let Some(step) = totp::verify_code(&two_factor.secret, code, unix_now()) else {
+            return Ok(false);
+        };
+        let step = step as i64;
+        if two_factor.last_used_step.is_some_and(|last| last >= step) {
+            return Ok(false);
+        }
+
+        repository
+            .set_last_used_step(two_factor, step)
+            .await
+            .map_err(|err| {
+                error!("Failed to save two-factor step: {}", err);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            })?;
+        return Ok(true);
+    }
+
+    if let Some(recovery_code) = second_factor.recovery_code.as_deref() {
+        return repository
+            .use_recovery_code(
+                &two_factor.user_id,
+                &two_factor::hash_recovery_code(recovery_code),
+            )
+            .await
+            .map_err(|err| {
+                error!("Failed to use recovery code: {}", err);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            });
+    }
+
+    Ok(false)
+}
+
+fn unix_now() -> u64 {
+    Utc::now().timestamp() as u64
+}
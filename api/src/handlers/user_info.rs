@@ -0,0 +1,35 @@
+use crate::{
+    auth::resource_authorization::{BodyInfosScope, ViewUserData},
+    axummain::state::AppState,
+    schemas::user_info_schemas::*,
+};
+use axum::{Json, extract::State, http::StatusCode};
+use tracing::{error, info};
+
+/// Get body infos (height, birth date, ...) of another user if the current user has permission
+/// to view them
+pub async fn get_other_user_infos(
+    State(state): State<AppState>,
+    ViewUserData {
+        requesting_user,
+        target_user_id,
+        ..
+    }: ViewUserData<BodyInfosScope>,
+) -> Result<Json<UserInfosResponse>, StatusCode> {
+    info!(
+        "User {} fetching body infos for user: {}",
+        requesting_user.id, target_user_id
+    );
+
+    state
+        .repositories
+        .user_info_repository
+        .find_by_user_id(&target_user_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch user infos: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map(|infos| Json(UserInfosResponse::from(infos)))
+        .ok_or(StatusCode::NOT_FOUND)
+}
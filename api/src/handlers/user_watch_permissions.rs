@@ -3,8 +3,12 @@ use crate::{
     schemas::user_watch_permission_schemas::*,
 };
 
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
-use entities::users;
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
 use log::error;
 use serde_json::json;
 use tracing::info;
@@ -58,7 +62,7 @@ pub async fn get_watchers(
 ) -> Result<Json<WatchersResponse>, StatusCode> {
     info!("User {} fetching list of watchers", user.id);
 
-    let users: Vec<users::Model> = state
+    let permissions = state
         .repositories
         .user_watch_permission_repository
         .find_all_watched(&user.id)
@@ -68,12 +72,9 @@ pub async fn get_watchers(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    let watchers: Vec<WatchPermissionWithUser> = users
+    let watchers: Vec<WatchPermissionWithUser> = permissions
         .into_iter()
-        .map(|u| WatchPermissionWithUser {
-            user_id: u.id,
-            username: u.username.clone(),
-        })
+        .map(|(permission, u)| WatchPermissionWithUser::new(&permission, u))
         .collect();
 
     Ok(Json(WatchersResponse { watchers }))
@@ -86,7 +87,7 @@ pub async fn get_watching(
 ) -> Result<Json<WatchingResponse>, StatusCode> {
     info!("User {} fetching list of users they are watching", user.id);
 
-    let users: Vec<users::Model> = state
+    let permissions = state
         .repositories
         .user_watch_permission_repository
         .find_all_watching(&user.id)
@@ -96,12 +97,9 @@ pub async fn get_watching(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    let watching: Vec<WatchPermissionWithUser> = users
+    let watching: Vec<WatchPermissionWithUser> = permissions
         .into_iter()
-        .map(|u| WatchPermissionWithUser {
-            user_id: u.id,
-            username: u.username.clone(),
-        })
+        .map(|(permission, u)| WatchPermissionWithUser::new(&permission, u))
         .collect();
 
     Ok(Json(WatchingResponse { watching }))
@@ -118,6 +116,14 @@ pub async fn grant_watch_permission(
         user.id, payload.user_id
     );
 
+    if !is_valid_expiry(payload.expires_at) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Expiry date must be in the future"})),
+        )
+            .into_response());
+    }
+
     if state
         .repositories
         .user_repository
@@ -127,7 +133,7 @@ pub async fn grant_watch_permission(
             error!("Failed to check if user exists: {}", err);
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
         })?
-        .is_none()
+        .is_none_or(|target| target.is_system())
     {
         return Err((
             StatusCode::NOT_FOUND,
@@ -153,7 +159,12 @@ pub async fn grant_watch_permission(
     state
         .repositories
         .user_watch_permission_repository
-        .create(&user.id, &payload.user_id)
+        .create(
+            &user.id,
+            &payload.user_id,
+            &payload.scopes,
+            payload.expires_at,
+        )
         .await
         .map_err(|err| {
             error!("Failed to create watch permission: {}", err);
@@ -163,6 +174,66 @@ pub async fn grant_watch_permission(
     Ok(StatusCode::CREATED)
 }
 
+/// Change the scopes or the expiry date of a permission I granted
+pub async fn update_watch_permission(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+    Json(payload): Json<UpdateWatchPermissionRequest>,
+) -> Result<Json<WatchPermissionWithUser>, Response> {
+    info!(
+        "User {} updating watch permission of user {}",
+        user.id, payload.user_id
+    );
+
+    if !is_valid_expiry(payload.expires_at.flatten()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Expiry date must be in the future"})),
+        )
+            .into_response());
+    }
+
+    let permission = state
+        .repositories
+        .user_watch_permission_repository
+        .find_by_user_ids(&user.id, &payload.user_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to check permission exists: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Watch permission not found"})),
+            )
+                .into_response()
+        })?;
+
+    let watcher = state
+        .repositories
+        .user_repository
+        .find_by_id(&payload.user_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch watcher: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+    let permission = state
+        .repositories
+        .user_watch_permission_repository
+        .update(permission, &payload.scopes, payload.expires_at)
+        .await
+        .map_err(|err| {
+            error!("Failed to update watch permission: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok(Json(WatchPermissionWithUser::new(&permission, watcher)))
+}
+
 pub async fn revoke_watch_permission(
     RequireAuth(user): RequireAuth,
     State(state): State<AppState>,
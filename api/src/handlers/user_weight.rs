@@ -1,13 +1,17 @@
 use crate::{
-    auth::middleware::RequireVerifiedAuth, axummain::state::AppState,
-    schemas::user_weight_schemas::*, weight::weight_infos::user_weight_infos,
+    auth::{
+        middleware::RequireVerifiedAuth,
+        resource_authorization::{ViewUserData, WeightScope},
+    },
+    axummain::state::AppState,
+    schemas::user_weight_schemas::*,
+    weight::weight_infos::user_weight_infos,
 };
 use axum::{
     Json,
-    body::Body,
     extract::{Path, State},
     http::StatusCode,
-    response::{IntoResponse, Response},
+    response::IntoResponse,
 };
 use sea_orm::DbErr;
 use serde_json::json;
@@ -21,29 +25,6 @@ fn is_unique_constraint_violation(err: &DbErr) -> bool {
     error_str.contains("unique constraint") || error_str.contains("duplicate key")
 }
 
-/// Helper function to check if a user has permission to view another user's data
-async fn check_view_permission(
-    state: &AppState,
-    current_user_id: &Uuid,
-    target_user_id: &Uuid,
-) -> Result<(), Response<Body>> {
-    let can_view = state
-        .services
-        .authorization
-        .can_view_user_data(current_user_id, target_user_id)
-        .await
-        .map_err(|err| {
-            error!("Failed to check view permission: {}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        })?;
-
-    if !can_view {
-        return Err(StatusCode::FORBIDDEN.into_response());
-    }
-
-    Ok(())
-}
-
 pub async fn create_user_weight(
     State(state): State<AppState>,
     RequireVerifiedAuth(user): RequireVerifiedAuth,
@@ -236,20 +217,21 @@ pub async fn delete_user_weight(
 /// Get weights for another user if the current user has permission to view them
 pub async fn get_other_user_weights(
     State(state): State<AppState>,
-    RequireVerifiedAuth(current_user): RequireVerifiedAuth,
-    Path(user_id): Path<Uuid>,
+    ViewUserData {
+        requesting_user,
+        target_user_id,
+        ..
+    }: ViewUserData<WeightScope>,
 ) -> Result<Json<Vec<UserWeightResponse>>, impl IntoResponse> {
     info!(
         "User {} fetching weight entries for user: {}",
-        current_user.id, user_id
+        requesting_user.id, target_user_id
     );
 
-    check_view_permission(&state, &current_user.id, &user_id).await?;
-
     match state
         .repositories
         .user_weight_repository
-        .find_by_user_id(&user_id)
+        .find_by_user_id(&target_user_id)
         .await
     {
         Ok(weights) => {
@@ -267,20 +249,21 @@ pub async fn get_other_user_weights(
 /// Get weight infos for another user if the current user has permission to view them
 pub async fn get_other_user_weight_infos(
     State(state): State<AppState>,
-    RequireVerifiedAuth(current_user): RequireVerifiedAuth,
-    Path(user_id): Path<Uuid>,
+    ViewUserData {
+        requesting_user,
+        target_user_id,
+        ..
+    }: ViewUserData<WeightScope>,
 ) -> Result<Json<Option<UserWeightInfosResponse>>, impl IntoResponse> {
     info!(
         "User {} fetching weight infos for user: {}",
-        current_user.id, user_id
+        requesting_user.id, target_user_id
     );
 
-    check_view_permission(&state, &current_user.id, &user_id).await?;
-
     match state
         .repositories
         .user_weight_repository
-        .find_by_user_id(&user_id)
+        .find_by_user_id(&target_user_id)
         .await
     {
         Ok(weights) => Ok(Json(user_weight_infos(weights))),
@@ -294,20 +277,21 @@ pub async fn get_other_user_weight_infos(
 /// Get last weight for another user if the current user has permission to view them
 pub async fn get_other_user_last_weight(
     State(state): State<AppState>,
-    RequireVerifiedAuth(current_user): RequireVerifiedAuth,
-    Path(user_id): Path<Uuid>,
+    ViewUserData {
+        requesting_user,
+        target_user_id,
+        ..
+    }: ViewUserData<WeightScope>,
 ) -> Result<Json<UserWeightResponse>, impl IntoResponse> {
     info!(
         "User {} fetching last weight entry for user: {}",
-        current_user.id, user_id
+        requesting_user.id, target_user_id
     );
 
-    check_view_permission(&state, &current_user.id, &user_id).await?;
-
     match state
         .repositories
         .user_weight_repository
-        .find_last_by_user_id(&user_id)
+        .find_last_by_user_id(&target_user_id)
         .await
     {
         Ok(Some(weight)) => Ok(Json(UserWeightResponse::from(weight))),
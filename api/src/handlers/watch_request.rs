@@ -0,0 +1,279 @@
+use crate::{
+    auth::middleware::RequireAuth,
+    axummain::state::AppState,
+    schemas::{user_watch_permission_schemas::is_valid_expiry, watch_request_schemas::*},
+};
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use entities::{sea_orm_active_enums::WatchRequestStatusEnum, users, watch_request};
+use serde_json::json;
+use tracing::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Ask another user for the permission to watch their data
+pub async fn send_watch_request(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+    Json(payload): Json<SendWatchRequestRequest>,
+) -> Result<(StatusCode, Json<WatchRequestResponse>), impl IntoResponse> {
+    info!(
+        "User {} sending watch request to user {}",
+        user.id, payload.user_id
+    );
+
+    if let Err(err) = payload.validate() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": err.to_string()})),
+        )
+            .into_response());
+    }
+
+    if payload.user_id == user.id {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "You cannot send a watch request to yourself"})),
+        )
+            .into_response());
+    }
+
+    let target = state
+        .repositories
+        .user_repository
+        .find_by_id(&payload.user_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to check if user exists: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        // Nobody reads the requests of the system user
+        .filter(|target| !target.is_system())
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+                .into_response()
+        })?;
+
+    let permission = state
+        .repositories
+        .user_watch_permission_repository
+        .find_by_user_ids(&target.id, &user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to check existing permission: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    if permission.is_some_and(|p| !p.is_expired()) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "You are already watching this user"})),
+        )
+            .into_response());
+    }
+
+    if state
+        .repositories
+        .watch_request_repository
+        .find_pending_by_user_ids(&user.id, &target.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to check pending watch request: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .is_some()
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "A watch request is already pending for this user"})),
+        )
+            .into_response());
+    }
+
+    let request = state
+        .repositories
+        .watch_request_repository
+        .create(&user.id, &target.id, payload.message)
+        .await
+        .map_err(|err| {
+            error!("Failed to create watch request: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    // The request is stored either way, a failed notification must not fail it
+    if target.email_verified
+        && let Err(err) = state
+            .jobs
+            .email_job
+            .send_watch_request_email(
+                &target.email,
+                &target.username,
+                &user.username,
+                request.message.as_deref(),
+            )
+            .await
+    {
+        error!("Failed to send watch request email: {err}");
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(WatchRequestResponse::new(request, target)),
+    ))
+}
+
+/// Get pending watch requests other users sent me
+pub async fn get_incoming_watch_requests(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+) -> Result<Json<WatchRequestsResponse>, StatusCode> {
+    info!("User {} fetching incoming watch requests", user.id);
+
+    let requests = state
+        .repositories
+        .watch_request_repository
+        .find_pending_by_target(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch incoming watch requests: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(WatchRequestsResponse {
+        requests: requests
+            .into_iter()
+            .map(|(request, requester)| WatchRequestResponse::new(request, requester))
+            .collect(),
+    }))
+}
+
+/// Get all watch requests I sent, whatever their status
+pub async fn get_outgoing_watch_requests(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+) -> Result<Json<WatchRequestsResponse>, StatusCode> {
+    info!("User {} fetching outgoing watch requests", user.id);
+
+    let requests = state
+        .repositories
+        .watch_request_repository
+        .find_all_by_requester(&user.id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch outgoing watch requests: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(WatchRequestsResponse {
+        requests: requests
+            .into_iter()
+            .map(|(request, target)| WatchRequestResponse::new(request, target))
+            .collect(),
+    }))
+}
+
+/// Accept a watch request sent to me, granting the requester a watch permission
+pub async fn accept_watch_request(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<AcceptWatchRequestRequest>,
+) -> Result<Json<WatchRequestResponse>, Response> {
+    info!("User {} accepting watch request {}", user.id, id);
+
+    if !is_valid_expiry(payload.expires_at) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Expiry date must be in the future"})),
+        )
+            .into_response());
+    }
+
+    let (request, requester) = find_pending_request_for_target(&state, &user, &id).await?;
+
+    let request = state
+        .repositories
+        .watch_request_repository
+        .accept(request, &payload.scopes, payload.expires_at)
+        .await
+        .map_err(|err| {
+            error!("Failed to accept watch request: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok(Json(WatchRequestResponse::new(request, requester)))
+}
+
+/// Decline a watch request sent to me
+pub async fn decline_watch_request(
+    RequireAuth(user): RequireAuth,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WatchRequestResponse>, Response> {
+    info!("User {} declining watch request {}", user.id, id);
+
+    let (request, requester) = find_pending_request_for_target(&state, &user, &id).await?;
+
+    let request = state
+        .repositories
+        .watch_request_repository
+        .decline(request)
+        .await
+        .map_err(|err| {
+            error!("Failed to decline watch request: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok(Json(WatchRequestResponse::new(request, requester)))
+}
+
+/// Fetch a request that the user can still answer, along with its requester
+async fn find_pending_request_for_target(
+    state: &AppState,
+    user: &users::Model,
+    id: &Uuid,
+) -> Result<(watch_request::Model, users::Model), Response> {
+    let request = state
+        .repositories
+        .watch_request_repository
+        .find_by_id(id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch watch request: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+    if request.target_id != user.id {
+        return Err(StatusCode::FORBIDDEN.into_response());
+    }
+
+    if request.status != WatchRequestStatusEnum::Pending {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Watch request was already answered"})),
+        )
+            .into_response());
+    }
+
+    let requester = state
+        .repositories
+        .user_repository
+        .find_by_id(&request.requester_id)
+        .await
+        .map_err(|err| {
+            error!("Failed to fetch requester: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+    Ok((request, requester))
+}
@@ -0,0 +1,91 @@
+use crate::{
+    auth::{middleware::RequireVerifiedAuth, user_view_authorization::WatchScope},
+    axummain::state::AppState,
+    schemas::watching_schemas::*,
+    utils::get_now_time_paris::now_paris_fixed,
+    watching::overview::{WatchingOverviewData, watching_overview},
+};
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::Duration;
+use entities::{user_watch_permissions, users};
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// Ids of the watched users whose permission grants the scope
+fn user_ids_with_scope(
+    watched_users: &[(user_watch_permissions::Model, users::Model)],
+    scope: WatchScope,
+) -> Vec<Uuid> {
+    watched_users
+        .iter()
+        .filter(|(permission, _)| scope.is_granted_by(permission))
+        .map(|(_, user)| user.id)
+        .collect()
+}
+
+/// Overview of every user I am watching, built with one query per data domain
+pub async fn get_watching_overview(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+) -> Result<Json<WatchingOverviewResponse>, StatusCode> {
+    info!("User {} fetching watching overview", user.id);
+
+    let db_error = |err: sea_orm::DbErr| {
+        error!("Failed to build watching overview: {}", err);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    let watched_users = state
+        .services
+        .authorization
+        .get_watched_users(&user.id)
+        .await
+        .map_err(db_error)?;
+
+    if watched_users.is_empty() {
+        return Ok(Json(WatchingOverviewResponse { users: vec![] }));
+    }
+
+    let weight_user_ids = user_ids_with_scope(&watched_users, WatchScope::Weight);
+    let gym_user_ids = user_ids_with_scope(&watched_users, WatchScope::Gym);
+    let meals_user_ids = user_ids_with_scope(&watched_users, WatchScope::Meals);
+
+    let today = now_paris_fixed(Duration::zero()).date_naive();
+
+    let last_weights = state
+        .repositories
+        .user_weight_repository
+        .find_last_by_user_ids(&weight_user_ids)
+        .await
+        .map_err(db_error)?;
+    let recent_weights = state
+        .repositories
+        .user_weight_repository
+        .find_by_user_ids_since(&weight_user_ids, today - Duration::days(7))
+        .await
+        .map_err(db_error)?;
+    let last_gym_session_dates = state
+        .repositories
+        .gym_session_repository
+        .find_last_dates_by_user_ids(&gym_user_ids)
+        .await
+        .map_err(db_error)?;
+    let yesterday_calories = state
+        .repositories
+        .meal_repository
+        .sum_calories_by_user_ids_and_date(&meals_user_ids, today - Duration::days(1))
+        .await
+        .map_err(db_error)?;
+
+    let users = watching_overview(
+        watched_users,
+        WatchingOverviewData {
+            last_weights,
+            recent_weights,
+            last_gym_session_dates,
+            yesterday_calories,
+        },
+    );
+
+    Ok(Json(WatchingOverviewResponse { users }))
+}
@@ -0,0 +1,323 @@
+use crate::{
+    auth::middleware::RequireVerifiedAuth,
+    axummain::state::AppState,
+    gym::templates::planned_sets,
+    schemas::{
+        gym_schemas::{GymSessionResponse, GymSetResponse},
+        workout_template_schemas::*,
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+use tracing::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Whether every exercise of the template exists and is visible to the user
+async fn are_exercises_visible(
+    state: &AppState,
+    user_id: &Uuid,
+    exercises: &[WorkoutTemplateExerciseRequest],
+) -> Result<bool, sea_orm::DbErr> {
+    let mut exercise_ids: Vec<Uuid> = exercises.iter().map(|e| e.exercise_id).collect();
+    exercise_ids.sort();
+    exercise_ids.dedup();
+
+    let found = state
+        .repositories
+        .gym_exercise_repository
+        .find_by_ids(exercise_ids.clone())
+        .await?;
+    Ok(found.len() == exercise_ids.len()
+        && found.iter().all(|exercise| exercise.is_visible_to(user_id)))
+}
+
+fn exercise_not_found() -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": "Exercise not found"})),
+    )
+        .into_response()
+}
+
+pub async fn create_workout_template(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Json(payload): Json<CreateWorkoutTemplateRequest>,
+) -> Result<Json<WorkoutTemplateResponse>, impl IntoResponse> {
+    info!("Creating workout template for user: {}", user.id);
+
+    if let Err(err) = payload.validate() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": err.to_string()})),
+        )
+            .into_response());
+    }
+
+    match are_exercises_visible(&state, &user.id, &payload.exercises).await {
+        Ok(true) => {}
+        Ok(false) => return Err(exercise_not_found()),
+        Err(err) => {
+            error!("Failed to fetch gym exercises: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    match state
+        .repositories
+        .workout_template_repository
+        .create(user.id, payload)
+        .await
+    {
+        Ok(template) => Ok(Json(template)),
+        Err(err) => {
+            error!("Failed to create workout template: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn get_workout_templates(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+) -> Result<Json<Vec<WorkoutTemplateResponse>>, impl IntoResponse> {
+    info!("Fetching workout templates for user: {}", user.id);
+
+    match state
+        .repositories
+        .workout_template_repository
+        .find_by_user_id(&user.id)
+        .await
+    {
+        Ok(templates) => Ok(Json(templates)),
+        Err(err) => {
+            error!("Failed to fetch workout templates: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn get_workout_template(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WorkoutTemplateResponse>, impl IntoResponse> {
+    info!("Fetching workout template {} for user: {}", id, user.id);
+
+    match state
+        .repositories
+        .workout_template_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(template)) => {
+            if template.user_id != user.id {
+                return Err(StatusCode::FORBIDDEN.into_response());
+            }
+        }
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch workout template: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    match state
+        .repositories
+        .workout_template_repository
+        .find_by_id_with_exercises(&id)
+        .await
+    {
+        Ok(Some(template)) => Ok(Json(template)),
+        Ok(None) => Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch workout template: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn update_workout_template(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWorkoutTemplateRequest>,
+) -> Result<Json<WorkoutTemplateResponse>, impl IntoResponse> {
+    info!("Updating workout template {} for user: {}", id, user.id);
+
+    if let Err(err) = payload.validate() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": err.to_string()})),
+        )
+            .into_response());
+    }
+
+    // Check if the template exists and belongs to the user
+    match state
+        .repositories
+        .workout_template_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(template)) => {
+            if template.user_id != user.id {
+                return Err(StatusCode::FORBIDDEN.into_response());
+            }
+        }
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch workout template: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    if let Some(exercises) = &payload.exercises {
+        match are_exercises_visible(&state, &user.id, exercises).await {
+            Ok(true) => {}
+            Ok(false) => return Err(exercise_not_found()),
+            Err(err) => {
+                error!("Failed to fetch gym exercises: {}", err);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+            }
+        }
+    }
+
+    match state
+        .repositories
+        .workout_template_repository
+        .update(id, payload)
+        .await
+    {
+        Ok(template) => Ok(Json(template)),
+        Err(err) => {
+            error!("Failed to update workout template: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+pub async fn delete_workout_template(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, impl IntoResponse> {
+    info!("Deleting workout template {} for user: {}", id, user.id);
+
+    // Check if the template exists and belongs to the user
+    match state
+        .repositories
+        .workout_template_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(template)) => {
+            if template.user_id != user.id {
+                return Err(StatusCode::FORBIDDEN.into_response());
+            }
+        }
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch workout template: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    match state
+        .repositories
+        .workout_template_repository
+        .delete(&id)
+        .await
+    {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(err) => {
+            error!("Failed to delete workout template: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+/// Creates a gym session with the planned sets of the template
+pub async fn start_workout_template(
+    State(state): State<AppState>,
+    RequireVerifiedAuth(user): RequireVerifiedAuth,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<StartWorkoutTemplateRequest>,
+) -> Result<Json<StartedSessionResponse>, impl IntoResponse> {
+    info!("Starting workout template {} for user: {}", id, user.id);
+
+    // Check if the template exists and belongs to the user
+    match state
+        .repositories
+        .workout_template_repository
+        .find_by_id(&id)
+        .await
+    {
+        Ok(Some(template)) => {
+            if template.user_id != user.id {
+                return Err(StatusCode::FORBIDDEN.into_response());
+            }
+        }
+        Ok(None) => return Err(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => {
+            error!("Failed to fetch workout template: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
+    let exercises = match state
+        .repositories
+        .workout_template_repository
+        .find_exercises(&id)
+        .await
+    {
+        Ok(exercises) => exercises,
+        Err(err) => {
+            error!("Failed to fetch workout template exercises: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+
+    let last_sets = if payload.prefill_from_last {
+        match state
+            .repositories
+            .gym_set_repository
+            .find_last_performances(
+                &user.id,
+                exercises.iter().map(|e| e.exercise_id).collect(),
+                payload.date,
+            )
+            .await
+        {
+            Ok(sets) => sets,
+            Err(err) => {
+                error!("Failed to fetch last performances: {}", err);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    match state
+        .repositories
+        .gym_session_repository
+        .create_with_sets(user.id, payload.date, planned_sets(&exercises, &last_sets))
+        .await
+    {
+        Ok((session, sets)) => Ok(Json(StartedSessionResponse {
+            session: GymSessionResponse::from(session),
+            sets: sets.into_iter().map(GymSetResponse::from).collect(),
+        })),
+        Err(err) => {
+            error!("Failed to create gym session from template: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
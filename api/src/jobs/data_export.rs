@@ -0,0 +1,27 @@
+use entities::{Job, JobDataExport, TaskType};
+use redis::{AsyncCommands, aio::ConnectionManager};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct DataExportJob {
+    pub redis: ConnectionManager,
+}
+
+impl DataExportJob {
+    pub fn new(redis: ConnectionManager) -> Self {
+        Self { redis }
+    }
+
+    pub async fn send_data_export_job(&self, user_id: &Uuid) -> Result<(), redis::RedisError> {
+        let job_data_export = JobDataExport { user_id: *user_id };
+
+        let job = Job {
+            task_type: TaskType::DataExport,
+            data: serde_json::to_value(job_data_export).unwrap(),
+        };
+
+        let mut con = self.redis.clone();
+        con.rpush::<_, _, ()>("jobs", serde_json::to_string(&job).unwrap())
+            .await
+    }
+}
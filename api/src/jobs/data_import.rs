@@ -0,0 +1,29 @@
+use entities::{Job, JobDataImport, TaskType};
+use redis::{AsyncCommands, aio::ConnectionManager};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct DataImportJob {
+    pub redis: ConnectionManager,
+}
+
+impl DataImportJob {
+    pub fn new(redis: ConnectionManager) -> Self {
+        Self { redis }
+    }
+
+    pub async fn send_data_import_job(&self, import_id: &Uuid) -> Result<(), redis::RedisError> {
+        let job_data_import = JobDataImport {
+            import_id: *import_id,
+        };
+
+        let job = Job {
+            task_type: TaskType::DataImport,
+            data: serde_json::to_value(job_data_import).unwrap(),
+        };
+
+        let mut con = self.redis.clone();
+        con.rpush::<_, _, ()>("jobs", serde_json::to_string(&job).unwrap())
+            .await
+    }
+}
@@ -1,4 +1,7 @@
-use entities::{EmailType, Job, JobEmail, JobEmailRegister, TaskType};
+use entities::{
+    EmailType, Job, JobEmail, JobEmailAccountDeletion, JobEmailMagicLink, JobEmailRegister,
+    JobEmailSecurityAlert, JobEmailWatchRequest, SecurityAlert, TaskType,
+};
 use redis::{AsyncCommands, aio::ConnectionManager};
 
 #[derive(Clone)]
@@ -91,4 +94,114 @@ impl EmailJob {
         con.rpush::<_, _, ()>("jobs", serde_json::to_string(&job).unwrap())
             .await
     }
+
+    pub async fn send_watch_request_email(
+        &self,
+        email: &str,
+        username: &str,
+        requester_username: &str,
+        message: Option<&str>,
+    ) -> Result<(), redis::RedisError> {
+        let job_email_watch_request = JobEmailWatchRequest {
+            email: email.to_string(),
+            username: username.to_string(),
+            requester_username: requester_username.to_string(),
+            message: message.map(str::to_string),
+        };
+
+        let job_email = JobEmail {
+            email_type: EmailType::WatchRequest,
+            data: serde_json::to_value(job_email_watch_request).unwrap(),
+        };
+
+        let job = Job {
+            task_type: TaskType::Email,
+            data: serde_json::to_value(job_email).unwrap(),
+        };
+
+        let mut con = self.redis.clone();
+        con.rpush::<_, _, ()>("jobs", serde_json::to_string(&job).unwrap())
+            .await
+    }
+
+    pub async fn send_security_alert_email(
+        &self,
+        email: &str,
+        username: &str,
+        alert: SecurityAlert,
+    ) -> Result<(), redis::RedisError> {
+        let job_email_security_alert = JobEmailSecurityAlert {
+            email: email.to_string(),
+            username: username.to_string(),
+            alert,
+        };
+
+        let job_email = JobEmail {
+            email_type: EmailType::SecurityAlert,
+            data: serde_json::to_value(job_email_security_alert).unwrap(),
+        };
+
+        let job = Job {
+            task_type: TaskType::Email,
+            data: serde_json::to_value(job_email).unwrap(),
+        };
+
+        let mut con = self.redis.clone();
+        con.rpush::<_, _, ()>("jobs", serde_json::to_string(&job).unwrap())
+            .await
+    }
+
+    pub async fn send_magic_link_email(
+        &self,
+        email: &str,
+        username: &str,
+        token: &str,
+    ) -> Result<(), redis::RedisError> {
+        let job_email_magic_link = JobEmailMagicLink {
+            email: email.to_string(),
+            username: username.to_string(),
+            token: token.to_string(),
+        };
+
+        let job_email = JobEmail {
+            email_type: EmailType::MagicLink,
+            data: serde_json::to_value(job_email_magic_link).unwrap(),
+        };
+
+        let job = Job {
+            task_type: TaskType::Email,
+            data: serde_json::to_value(job_email).unwrap(),
+        };
+
+        let mut con = self.redis.clone();
+        con.rpush::<_, _, ()>("jobs", serde_json::to_string(&job).unwrap())
+            .await
+    }
+
+    pub async fn send_account_deletion_email(
+        &self,
+        email: &str,
+        username: &str,
+        delete_after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), redis::RedisError> {
+        let job_email_account_deletion = JobEmailAccountDeletion {
+            email: email.to_string(),
+            username: username.to_string(),
+            delete_after,
+        };
+
+        let job_email = JobEmail {
+            email_type: EmailType::AccountDeletion,
+            data: serde_json::to_value(job_email_account_deletion).unwrap(),
+        };
+
+        let job = Job {
+            task_type: TaskType::Email,
+            data: serde_json::to_value(job_email).unwrap(),
+        };
+
+        let mut con = self.redis.clone();
+        con.rpush::<_, _, ()>("jobs", serde_json::to_string(&job).unwrap())
+            .await
+    }
 }
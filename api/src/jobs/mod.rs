@@ -1,17 +1,27 @@
 use redis::aio::ConnectionManager;
 
-use crate::jobs::email::EmailJob;
+use crate::jobs::{data_export::DataExportJob, data_import::DataImportJob, email::EmailJob};
 
+pub mod data_export;
+pub mod data_import;
 pub mod email;
 
 #[derive(Clone)]
 pub struct Jobs {
     pub email_job: EmailJob,
+    pub data_export_job: DataExportJob,
+    pub data_import_job: DataImportJob,
 }
 
 impl Jobs {
     pub fn new(redis: ConnectionManager) -> Self {
-        let email_job = EmailJob::new(redis);
-        Jobs { email_job }
+        let email_job = EmailJob::new(redis.clone());
+        let data_export_job = DataExportJob::new(redis.clone());
+        let data_import_job = DataImportJob::new(redis);
+        Jobs {
+            email_job,
+            data_export_job,
+            data_import_job,
+        }
     }
 }
@@ -1,9 +1,11 @@
 pub mod auth;
 pub mod axummain;
+pub mod gym;
 pub mod handlers;
 pub mod jobs;
 pub mod repositories;
 pub mod schemas;
 pub mod services;
 pub mod utils;
+pub mod watching;
 pub mod weight;
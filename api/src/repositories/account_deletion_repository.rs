@@ -0,0 +1,53 @@
+use entities::account_deletion_request;
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+};
+
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct AccountDeletionRepository {
+    db: DatabaseConnection,
+}
+
+impl AccountDeletionRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn find_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Option<account_deletion_request::Model>, sea_orm::DbErr> {
+        account_deletion_request::Entity::find()
+            .filter(account_deletion_request::Column::UserId.eq(*user_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn create_request(
+        &self,
+        user_id: &Uuid,
+        delete_after: &chrono::DateTime<chrono::FixedOffset>,
+    ) -> Result<account_deletion_request::Model, sea_orm::DbErr> {
+        let request = account_deletion_request::ActiveModel {
+            id: NotSet,
+            user_id: Set(*user_id),
+            delete_after: Set(*delete_after),
+            created_at: NotSet,
+        };
+        request.insert(&self.db).await
+    }
+
+    /// Cancel the pending deletion of the user, returns whether there was one
+    pub async fn cancel_request(&self, user_id: &Uuid) -> Result<bool, sea_orm::DbErr> {
+        let result = account_deletion_request::Entity::delete_many()
+            .filter(account_deletion_request::Column::UserId.eq(*user_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
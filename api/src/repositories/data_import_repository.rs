@@ -0,0 +1,79 @@
+use entities::{
+    data_import,
+    sea_orm_active_enums::{ImportKindEnum, ImportStatusEnum},
+};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct DataImportRepository {
+    db: DatabaseConnection,
+}
+
+impl DataImportRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        kind: ImportKindEnum,
+        dry_run: bool,
+        content: String,
+    ) -> Result<data_import::Model, sea_orm::DbErr> {
+        let data_import = data_import::ActiveModel {
+            id: NotSet,
+            user_id: Set(user_id),
+            kind: Set(kind),
+            status: Set(ImportStatusEnum::Pending),
+            dry_run: Set(dry_run),
+            content: Set(content),
+            report: Set(None),
+            finished_at: Set(None),
+            created_at: NotSet,
+            updated_at: NotSet,
+        };
+        data_import.insert(&self.db).await
+    }
+
+    pub async fn find_by_id_and_user(
+        &self,
+        id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<Option<data_import::Model>, sea_orm::DbErr> {
+        data_import::Entity::find_by_id(*id)
+            .filter(data_import::Column::UserId.eq(*user_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn find_all_by_user(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<data_import::Model>, sea_orm::DbErr> {
+        data_import::Entity::find()
+            .filter(data_import::Column::UserId.eq(*user_id))
+            .order_by_desc(data_import::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Turn a finished dry run into a real import of the same content
+    pub async fn apply_dry_run(
+        &self,
+        data_import: data_import::Model,
+    ) -> Result<data_import::Model, sea_orm::DbErr> {
+        let mut data_import: data_import::ActiveModel = data_import.into();
+        data_import.dry_run = Set(false);
+        data_import.status = Set(ImportStatusEnum::Pending);
+        data_import.report = Set(None);
+        data_import.finished_at = Set(None);
+        data_import.update(&self.db).await
+    }
+}
@@ -1,8 +1,10 @@
-use entities::food_item;
+use entities::{food_item, sea_orm_active_enums::VisibilityEnum};
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder,
+    sea_query::{Expr, ExprTrait, Func},
 };
 use uuid::Uuid;
 
@@ -18,6 +20,13 @@ impl FoodItemRepository {
         Self { db }
     }
 
+    /// Public food items and the ones added by the user
+    fn visible_to(user_id: Uuid) -> Condition {
+        Condition::any()
+            .add(food_item::Column::Visibility.eq(VisibilityEnum::Public))
+            .add(food_item::Column::AddedBy.eq(user_id))
+    }
+
     pub async fn create(
         &self,
         request: CreateFoodItemRequest,
@@ -35,6 +44,7 @@ impl FoodItemRepository {
             added_by: Set(added_by),
             added_at: NotSet,
             updated_at: NotSet,
+            visibility: Set(VisibilityEnum::Private),
         };
         let food_item = food_item.insert(&self.db).await?;
 
@@ -55,13 +65,21 @@ impl FoodItemRepository {
             .await
     }
 
-    pub async fn find_all(&self) -> Result<Vec<food_item::Model>, sea_orm::DbErr> {
-        food_item::Entity::find().all(&self.db).await
+    pub async fn find_all(&self, user_id: Uuid) -> Result<Vec<food_item::Model>, sea_orm::DbErr> {
+        food_item::Entity::find()
+            .filter(Self::visible_to(user_id))
+            .all(&self.db)
+            .await
     }
 
-    pub async fn find_by_name(&self, name: &str) -> Result<Vec<food_item::Model>, sea_orm::DbErr> {
+    pub async fn find_by_name(
+        &self,
+        name: &str,
+        user_id: Uuid,
+    ) -> Result<Vec<food_item::Model>, sea_orm::DbErr> {
         food_item::Entity::find()
             .filter(food_item::Column::Name.contains(name))
+            .filter(Self::visible_to(user_id))
             .all(&self.db)
             .await
     }
@@ -69,25 +87,66 @@ impl FoodItemRepository {
     pub async fn find_by_scan_code(
         &self,
         scan_code: &str,
+        user_id: Uuid,
     ) -> Result<Option<food_item::Model>, sea_orm::DbErr> {
         food_item::Entity::find()
             .filter(food_item::Column::ScanCode.eq(scan_code))
+            .filter(Self::visible_to(user_id))
+            .one(&self.db)
+            .await
+    }
+
+    /// Food items waiting for a moderator, oldest first
+    pub async fn find_pending(&self) -> Result<Vec<food_item::Model>, sea_orm::DbErr> {
+        food_item::Entity::find()
+            .filter(food_item::Column::Visibility.eq(VisibilityEnum::Pending))
+            .order_by_asc(food_item::Column::UpdatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// A public food item other than `id` with the same name, ignoring case
+    pub async fn find_public_duplicate(
+        &self,
+        id: Uuid,
+        name: &str,
+    ) -> Result<Option<food_item::Model>, sea_orm::DbErr> {
+        food_item::Entity::find()
+            .filter(food_item::Column::Visibility.eq(VisibilityEnum::Public))
+            .filter(food_item::Column::Id.ne(id))
+            .filter(
+                Expr::expr(Func::lower(Expr::col(food_item::Column::Name)))
+                    .eq(Expr::val(name.trim().to_lowercase())),
+            )
             .one(&self.db)
             .await
     }
 
+    pub async fn set_visibility(
+        &self,
+        id: Uuid,
+        visibility: VisibilityEnum,
+    ) -> Result<food_item::Model, sea_orm::DbErr> {
+        let food_item = food_item::ActiveModel {
+            id: Set(id),
+            visibility: Set(visibility),
+            ..Default::default()
+        };
+        food_item.update(&self.db).await
+    }
+
     pub async fn update(
         &self,
         id: Uuid,
         request: UpdateFoodItemRequest,
     ) -> Result<food_item::Model, sea_orm::DbErr> {
-        let mut food_item: food_item::ActiveModel = food_item::Entity::find_by_id(id)
+        let existing = food_item::Entity::find_by_id(id)
             .one(&self.db)
             .await?
             .ok_or(sea_orm::DbErr::RecordNotFound(
                 "Food item not found".to_owned(),
-            ))?
-            .into();
+            ))?;
+        let mut food_item: food_item::ActiveModel = existing.into();
 
         if let Some(name) = request.name {
             food_item.name = Set(name);
@@ -114,6 +173,21 @@ impl FoodItemRepository {
         food_item.update(&self.db).await
     }
 
+    /// Whether other users than the creator have the food item in their meals
+    pub async fn is_used_by_others(
+        &self,
+        id: &Uuid,
+        added_by: &Uuid,
+    ) -> Result<bool, sea_orm::DbErr> {
+        let count = food_item::Entity::find()
+            .filter(food_item::Column::Id.eq(*id))
+            .filter(food_item::Entity::used_by_others(vec![*added_by]))
+            .count(&self.db)
+            .await?;
+
+        Ok(count > 0)
+    }
+
     pub async fn delete(&self, id: &Uuid) -> Result<(), sea_orm::DbErr> {
         food_item::Entity::delete_by_id(*id).exec(&self.db).await?;
         Ok(())
@@ -2,16 +2,22 @@ use std::collections::HashMap;
 
 use entities::{
     exercise_muscle, gym_exercise,
-    sea_orm_active_enums::{MuscleEnum, MuscleRoleEnum},
+    sea_orm_active_enums::{
+        EquipmentEnum, MovementPatternEnum, MuscleEnum, MuscleRoleEnum, VisibilityEnum,
+    },
 };
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, QueryTrait,
+    sea_query::{Expr, ExprTrait, Func},
 };
 use uuid::Uuid;
 
-use crate::schemas::gym_schemas::GymExerciseResponse;
+use crate::schemas::gym_schemas::{
+    CreateGymExerciseRequest, GymExerciseResponse, UpdateGymExerciseRequest,
+};
 
 #[derive(Clone)]
 pub struct GymExerciseRepository {
@@ -59,6 +65,11 @@ impl GymExerciseRepository {
                     id: exercise.id,
                     name: exercise.name,
                     description: exercise.description,
+                    equipment: exercise.equipment,
+                    movement_pattern: exercise.movement_pattern,
+                    is_unilateral: exercise.is_unilateral,
+                    visibility: exercise.visibility,
+                    added_by: exercise.added_by,
                     primary_muscles,
                     secondary_muscles,
                 }
@@ -68,23 +79,24 @@ impl GymExerciseRepository {
 
     pub async fn create(
         &self,
-        name: String,
-        description: Option<String>,
-        primary_muscles: Vec<MuscleEnum>,
-        secondary_muscles: Vec<MuscleEnum>,
+        request: CreateGymExerciseRequest,
         added_by: Uuid,
     ) -> Result<GymExerciseResponse, sea_orm::DbErr> {
         let exercise = gym_exercise::ActiveModel {
             id: NotSet,
-            name: Set(name),
-            description: Set(description),
+            name: Set(request.name),
+            description: Set(request.description),
             added_by: Set(added_by),
             created_at: NotSet,
             updated_at: NotSet,
+            equipment: Set(request.equipment),
+            movement_pattern: Set(request.movement_pattern),
+            is_unilateral: Set(request.is_unilateral),
+            visibility: Set(VisibilityEnum::Private),
         };
         let exercise = exercise.insert(&self.db).await?;
 
-        for muscle in &primary_muscles {
+        for muscle in &request.primary_muscles {
             let primary = exercise_muscle::ActiveModel {
                 id: NotSet,
                 exercise_id: Set(exercise.id),
@@ -95,7 +107,7 @@ impl GymExerciseRepository {
             };
             primary.insert(&self.db).await?;
         }
-        for muscle in &secondary_muscles {
+        for muscle in &request.secondary_muscles {
             let secondary = exercise_muscle::ActiveModel {
                 id: NotSet,
                 exercise_id: Set(exercise.id),
@@ -111,8 +123,13 @@ impl GymExerciseRepository {
             id: exercise.id,
             name: exercise.name,
             description: exercise.description,
-            primary_muscles,
-            secondary_muscles,
+            equipment: exercise.equipment,
+            movement_pattern: exercise.movement_pattern,
+            is_unilateral: exercise.is_unilateral,
+            visibility: exercise.visibility,
+            added_by: exercise.added_by,
+            primary_muscles: request.primary_muscles,
+            secondary_muscles: request.secondary_muscles,
         };
 
         Ok(gym_exercise_response)
@@ -127,6 +144,16 @@ impl GymExerciseRepository {
             .await
     }
 
+    pub async fn find_by_ids(
+        &self,
+        ids: Vec<Uuid>,
+    ) -> Result<Vec<gym_exercise::Model>, sea_orm::DbErr> {
+        gym_exercise::Entity::find()
+            .filter(gym_exercise::Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+    }
+
     pub async fn find_by_id_with_muscles(
         &self,
         id: &Uuid,
@@ -149,50 +176,66 @@ impl GymExerciseRepository {
         }
     }
 
-    pub async fn find_all(&self) -> Result<Vec<GymExerciseResponse>, sea_orm::DbErr> {
-        let exercises = gym_exercise::Entity::find().all(&self.db).await?;
-
-        if exercises.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        // Fetch all muscles for all exercises in a single query
-        let exercise_ids: Vec<Uuid> = exercises.iter().map(|e| e.id).collect();
-        let all_muscles = exercise_muscle::Entity::find()
-            .filter(exercise_muscle::Column::ExerciseId.is_in(exercise_ids))
-            .all(&self.db)
-            .await?;
+    pub async fn find_by_ids_with_muscles(
+        &self,
+        ids: Vec<Uuid>,
+    ) -> Result<Vec<GymExerciseResponse>, sea_orm::DbErr> {
+        let exercises = self.find_by_ids(ids.clone()).await?;
+        let muscles = self.find_muscles(ids).await?;
 
-        Ok(Self::build_responses(exercises, all_muscles))
+        Ok(Self::build_responses(exercises, muscles))
     }
 
-    pub async fn find_by_muscle(
+    /// Exercises visible to the user matching every given filter, sorted by name
+    pub async fn search(
         &self,
-        muscle: MuscleEnum,
+        user_id: Uuid,
+        muscle: Option<MuscleEnum>,
+        name: Option<&str>,
+        equipment: Option<EquipmentEnum>,
+        movement_pattern: Option<MovementPatternEnum>,
+        is_unilateral: Option<bool>,
     ) -> Result<Vec<GymExerciseResponse>, sea_orm::DbErr> {
-        // Find all exercise_muscle entries with this muscle
-        let exercise_muscles = exercise_muscle::Entity::find()
-            .filter(exercise_muscle::Column::Muscle.eq(muscle))
-            .all(&self.db)
-            .await?;
+        let mut query = gym_exercise::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(gym_exercise::Column::Visibility.eq(VisibilityEnum::Public))
+                    .add(gym_exercise::Column::AddedBy.eq(user_id)),
+            )
+            .order_by_asc(gym_exercise::Column::Name);
+
+        if let Some(muscle) = muscle {
+            query = query.filter(
+                gym_exercise::Column::Id.in_subquery(
+                    exercise_muscle::Entity::find()
+                        .select_only()
+                        .column(exercise_muscle::Column::ExerciseId)
+                        .filter(exercise_muscle::Column::Muscle.eq(muscle))
+                        .into_query(),
+                ),
+            );
+        }
+        if let Some(name) = name {
+            query = query.filter(gym_exercise::Column::Name.contains(name));
+        }
+        if let Some(equipment) = equipment {
+            query = query.filter(gym_exercise::Column::Equipment.eq(equipment));
+        }
+        if let Some(movement_pattern) = movement_pattern {
+            query = query.filter(gym_exercise::Column::MovementPattern.eq(movement_pattern));
+        }
+        if let Some(is_unilateral) = is_unilateral {
+            query = query.filter(gym_exercise::Column::IsUnilateral.eq(is_unilateral));
+        }
 
-        // Get unique exercise ids
-        let mut exercise_ids: Vec<Uuid> =
-            exercise_muscles.iter().map(|em| em.exercise_id).collect();
-        exercise_ids.sort();
-        exercise_ids.dedup();
+        let exercises = query.all(&self.db).await?;
 
-        if exercise_ids.is_empty() {
+        if exercises.is_empty() {
             return Ok(Vec::new());
         }
 
-        // Fetch all exercises in a single query
-        let exercises = gym_exercise::Entity::find()
-            .filter(gym_exercise::Column::Id.is_in(exercise_ids.clone()))
-            .all(&self.db)
-            .await?;
-
-        // Fetch all muscles for these exercises
+        // Fetch all muscles for all matched exercises in a single query
+        let exercise_ids: Vec<Uuid> = exercises.iter().map(|e| e.id).collect();
         let all_muscles = exercise_muscle::Entity::find()
             .filter(exercise_muscle::Column::ExerciseId.is_in(exercise_ids))
             .all(&self.db)
@@ -201,56 +244,50 @@ impl GymExerciseRepository {
         Ok(Self::build_responses(exercises, all_muscles))
     }
 
-    pub async fn find_by_name(
+    /// Muscles worked by any of the exercises
+    pub async fn find_muscles(
         &self,
-        name: &str,
-    ) -> Result<Vec<GymExerciseResponse>, sea_orm::DbErr> {
-        let exercises = gym_exercise::Entity::find()
-            .filter(gym_exercise::Column::Name.contains(name))
-            .all(&self.db)
-            .await?;
-
-        if exercises.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        // Fetch all muscles for all matched exercises in a single query
-        let exercise_ids: Vec<Uuid> = exercises.iter().map(|e| e.id).collect();
-        let all_muscles = exercise_muscle::Entity::find()
+        exercise_ids: Vec<Uuid>,
+    ) -> Result<Vec<exercise_muscle::Model>, sea_orm::DbErr> {
+        exercise_muscle::Entity::find()
             .filter(exercise_muscle::Column::ExerciseId.is_in(exercise_ids))
             .all(&self.db)
-            .await?;
-
-        Ok(Self::build_responses(exercises, all_muscles))
+            .await
     }
 
     pub async fn update(
         &self,
         id: Uuid,
-        name: Option<String>,
-        description: Option<Option<String>>,
-        primary_muscles: Option<Vec<MuscleEnum>>,
-        secondary_muscles: Option<Vec<MuscleEnum>>,
+        request: UpdateGymExerciseRequest,
     ) -> Result<GymExerciseResponse, sea_orm::DbErr> {
-        let mut exercise: gym_exercise::ActiveModel = gym_exercise::Entity::find_by_id(id)
+        let existing = gym_exercise::Entity::find_by_id(id)
             .one(&self.db)
             .await?
             .ok_or(sea_orm::DbErr::RecordNotFound(
                 "Exercise not found".to_owned(),
-            ))?
-            .into();
+            ))?;
+        let mut exercise: gym_exercise::ActiveModel = existing.into();
 
-        if let Some(name) = name {
+        if let Some(name) = request.name {
             exercise.name = Set(name);
         }
-        if let Some(description) = description {
+        if let Some(description) = request.description {
             exercise.description = Set(description);
         }
+        if let Some(equipment) = request.equipment {
+            exercise.equipment = Set(equipment);
+        }
+        if let Some(movement_pattern) = request.movement_pattern {
+            exercise.movement_pattern = Set(movement_pattern);
+        }
+        if let Some(is_unilateral) = request.is_unilateral {
+            exercise.is_unilateral = Set(is_unilateral);
+        }
 
         let exercise = exercise.update(&self.db).await?;
 
         // Update muscles if provided
-        if primary_muscles.is_some() || secondary_muscles.is_some() {
+        if request.primary_muscles.is_some() || request.secondary_muscles.is_some() {
             // Delete existing muscles
             exercise_muscle::Entity::delete_many()
                 .filter(exercise_muscle::Column::ExerciseId.eq(id))
@@ -258,7 +295,7 @@ impl GymExerciseRepository {
                 .await?;
 
             // Insert new primary muscles
-            if let Some(ref muscles) = primary_muscles {
+            if let Some(ref muscles) = request.primary_muscles {
                 for muscle in muscles {
                     let new_muscle = exercise_muscle::ActiveModel {
                         id: NotSet,
@@ -273,7 +310,7 @@ impl GymExerciseRepository {
             }
 
             // Insert new secondary muscles
-            if let Some(ref muscles) = secondary_muscles {
+            if let Some(ref muscles) = request.secondary_muscles {
                 for muscle in muscles {
                     let new_muscle = exercise_muscle::ActiveModel {
                         id: NotSet,
@@ -298,6 +335,72 @@ impl GymExerciseRepository {
         Ok(responses.into_iter().next().unwrap())
     }
 
+    /// Whether other users than the creator log sets of the exercise or plan it
+    pub async fn is_used_by_others(
+        &self,
+        id: &Uuid,
+        added_by: &Uuid,
+    ) -> Result<bool, sea_orm::DbErr> {
+        let count = gym_exercise::Entity::find()
+            .filter(gym_exercise::Column::Id.eq(*id))
+            .filter(gym_exercise::Entity::used_by_others(vec![*added_by]))
+            .count(&self.db)
+            .await?;
+
+        Ok(count > 0)
+    }
+
+    /// Exercises waiting for a moderator, oldest first
+    pub async fn find_pending(&self) -> Result<Vec<GymExerciseResponse>, sea_orm::DbErr> {
+        let exercises = gym_exercise::Entity::find()
+            .filter(gym_exercise::Column::Visibility.eq(VisibilityEnum::Pending))
+            .order_by_asc(gym_exercise::Column::UpdatedAt)
+            .all(&self.db)
+            .await?;
+
+        if exercises.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let exercise_ids: Vec<Uuid> = exercises.iter().map(|e| e.id).collect();
+        let all_muscles = exercise_muscle::Entity::find()
+            .filter(exercise_muscle::Column::ExerciseId.is_in(exercise_ids))
+            .all(&self.db)
+            .await?;
+
+        Ok(Self::build_responses(exercises, all_muscles))
+    }
+
+    /// A public exercise other than `id` with the same name, ignoring case
+    pub async fn find_public_duplicate(
+        &self,
+        id: Uuid,
+        name: &str,
+    ) -> Result<Option<gym_exercise::Model>, sea_orm::DbErr> {
+        gym_exercise::Entity::find()
+            .filter(gym_exercise::Column::Visibility.eq(VisibilityEnum::Public))
+            .filter(gym_exercise::Column::Id.ne(id))
+            .filter(
+                Expr::expr(Func::lower(Expr::col(gym_exercise::Column::Name)))
+                    .eq(Expr::val(name.trim().to_lowercase())),
+            )
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn set_visibility(
+        &self,
+        id: Uuid,
+        visibility: VisibilityEnum,
+    ) -> Result<gym_exercise::Model, sea_orm::DbErr> {
+        let exercise = gym_exercise::ActiveModel {
+            id: Set(id),
+            visibility: Set(visibility),
+            ..Default::default()
+        };
+        exercise.update(&self.db).await
+    }
+
     pub async fn delete(&self, id: &Uuid) -> Result<(), sea_orm::DbErr> {
         // Muscles will be deleted via cascade
         gym_exercise::Entity::delete_by_id(id.to_owned())
@@ -0,0 +1,38 @@
+use entities::{gym_personal_record, sea_orm_active_enums::OneRepMaxFormulaEnum};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+/// Records are written by `gym_personal_record::Entity::recompute` along with the sets,
+/// this repository only reads them
+#[derive(Clone)]
+pub struct GymPersonalRecordRepository {
+    db: DatabaseConnection,
+}
+
+impl GymPersonalRecordRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Every record of the user for the exercise, newest first. One rep max records
+    /// are only those estimated with `formula`.
+    pub async fn find_history(
+        &self,
+        user_id: Uuid,
+        exercise_id: Uuid,
+        formula: OneRepMaxFormulaEnum,
+    ) -> Result<Vec<gym_personal_record::Model>, sea_orm::DbErr> {
+        gym_personal_record::Entity::find()
+            .filter(gym_personal_record::Column::UserId.eq(user_id))
+            .filter(gym_personal_record::Column::ExerciseId.eq(exercise_id))
+            .filter(
+                Condition::any()
+                    .add(gym_personal_record::Column::Formula.is_null())
+                    .add(gym_personal_record::Column::Formula.eq(formula)),
+            )
+            .order_by_desc(gym_personal_record::Column::AchievedOn)
+            .order_by_desc(gym_personal_record::Column::Value)
+            .all(&self.db)
+            .await
+    }
+}
@@ -1,12 +1,18 @@
 use chrono::NaiveDate;
-use entities::gym_session;
+use entities::{gym_personal_record, gym_session, gym_set};
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
 };
 use uuid::Uuid;
 
+use crate::{
+    gym::templates::PlannedSet,
+    schemas::gym_schemas::{CreateGymSessionRequest, UpdateGymSessionRequest},
+};
+
 #[derive(Clone)]
 pub struct GymSessionRepository {
     db: DatabaseConnection,
@@ -17,15 +23,35 @@ impl GymSessionRepository {
         Self { db }
     }
 
+    /// Exercises with at least one set in the session
+    async fn find_exercise_ids<C: ConnectionTrait>(
+        db: &C,
+        session_id: Uuid,
+    ) -> Result<Vec<Uuid>, sea_orm::DbErr> {
+        gym_set::Entity::find()
+            .select_only()
+            .column(gym_set::Column::ExerciseId)
+            .distinct()
+            .filter(gym_set::Column::SessionId.eq(session_id))
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
     pub async fn create(
         &self,
         user_id: Uuid,
-        date: NaiveDate,
+        request: CreateGymSessionRequest,
     ) -> Result<gym_session::Model, sea_orm::DbErr> {
         let session = gym_session::ActiveModel {
             id: NotSet,
             user_id: Set(user_id),
-            date: Set(date),
+            date: Set(request.date),
+            started_at: Set(request.started_at),
+            ended_at: Set(request.ended_at),
+            notes: Set(request.notes),
+            perceived_difficulty: Set(request.perceived_difficulty),
+            user_weight_id: Set(request.user_weight_id),
             created_at: NotSet,
             updated_at: NotSet,
         };
@@ -34,6 +60,60 @@ impl GymSessionRepository {
         Ok(session)
     }
 
+    /// Session pre-filled with sets, personal records of `user_id` being updated along
+    pub async fn create_with_sets(
+        &self,
+        user_id: Uuid,
+        date: NaiveDate,
+        planned_sets: Vec<PlannedSet>,
+    ) -> Result<(gym_session::Model, Vec<gym_set::Model>), sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+        let session = gym_session::ActiveModel {
+            id: NotSet,
+            user_id: Set(user_id),
+            date: Set(date),
+            started_at: NotSet,
+            ended_at: NotSet,
+            notes: NotSet,
+            perceived_difficulty: NotSet,
+            user_weight_id: NotSet,
+            created_at: NotSet,
+            updated_at: NotSet,
+        }
+        .insert(&txn)
+        .await?;
+
+        let mut sets = Vec::with_capacity(planned_sets.len());
+        for planned in planned_sets {
+            let set = gym_set::ActiveModel {
+                id: NotSet,
+                session_id: Set(session.id),
+                exercise_id: Set(planned.exercise_id),
+                set_number: Set(planned.set_number),
+                repetitions: Set(planned.repetitions),
+                weight_kg: Set(planned.weight_kg),
+                set_type: NotSet,
+                rpe: NotSet,
+                rir: NotSet,
+                rest_seconds: NotSet,
+                duration_seconds: NotSet,
+                distance_meters: NotSet,
+                created_at: NotSet,
+                updated_at: NotSet,
+            }
+            .insert(&txn)
+            .await?;
+            sets.push(set);
+        }
+
+        for exercise_id in Self::find_exercise_ids(&txn, session.id).await? {
+            gym_personal_record::Entity::recompute(&txn, user_id, exercise_id).await?;
+        }
+        txn.commit().await?;
+
+        Ok((session, sets))
+    }
+
     pub async fn find_by_id(
         &self,
         id: &Uuid,
@@ -50,6 +130,7 @@ impl GymSessionRepository {
         gym_session::Entity::find()
             .filter(gym_session::Column::UserId.eq(user_id.to_owned()))
             .order_by_desc(gym_session::Column::Date)
+            .order_by_desc(gym_session::Column::CreatedAt)
             .all(&self.db)
             .await
     }
@@ -62,6 +143,23 @@ impl GymSessionRepository {
         gym_session::Entity::find()
             .filter(gym_session::Column::UserId.eq(user_id.to_owned()))
             .filter(gym_session::Column::Date.eq(date))
+            .order_by_asc(gym_session::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Date of the last gym session of each user, in a single query
+    pub async fn find_last_dates_by_user_ids(
+        &self,
+        user_ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, NaiveDate)>, sea_orm::DbErr> {
+        gym_session::Entity::find()
+            .select_only()
+            .column(gym_session::Column::UserId)
+            .column_as(gym_session::Column::Date.max(), "last_date")
+            .filter(gym_session::Column::UserId.is_in(user_ids.to_vec()))
+            .group_by(gym_session::Column::UserId)
+            .into_tuple()
             .all(&self.db)
             .await
     }
@@ -69,10 +167,12 @@ impl GymSessionRepository {
     pub async fn update(
         &self,
         id: Uuid,
-        date: Option<NaiveDate>,
+        request: UpdateGymSessionRequest,
     ) -> Result<gym_session::Model, sea_orm::DbErr> {
+        let date = request.date;
+        let txn = self.db.begin().await?;
         let mut session: gym_session::ActiveModel = gym_session::Entity::find_by_id(id)
-            .one(&self.db)
+            .one(&txn)
             .await?
             .ok_or(sea_orm::DbErr::RecordNotFound(
                 "Session not found".to_owned(),
@@ -82,16 +182,53 @@ impl GymSessionRepository {
         if let Some(date) = date {
             session.date = Set(date);
         }
+        if let Some(started_at) = request.started_at {
+            session.started_at = Set(started_at);
+        }
+        if let Some(ended_at) = request.ended_at {
+            session.ended_at = Set(ended_at);
+        }
+        if let Some(notes) = request.notes {
+            session.notes = Set(notes);
+        }
+        if let Some(perceived_difficulty) = request.perceived_difficulty {
+            session.perceived_difficulty = Set(perceived_difficulty);
+        }
+        if let Some(user_weight_id) = request.user_weight_id {
+            session.user_weight_id = Set(user_weight_id);
+        }
+
+        let session = session.update(&txn).await?;
 
-        let session = session.update(&self.db).await?;
+        // Moving the session may change which sets were the first to reach a record
+        if date.is_some() {
+            for exercise_id in Self::find_exercise_ids(&txn, id).await? {
+                gym_personal_record::Entity::recompute(&txn, session.user_id, exercise_id).await?;
+            }
+        }
+        txn.commit().await?;
 
         Ok(session)
     }
 
     pub async fn delete(&self, id: &Uuid) -> Result<(), sea_orm::DbErr> {
-        gym_session::Entity::delete_by_id(id.to_owned())
-            .exec(&self.db)
+        let txn = self.db.begin().await?;
+        let Some(session) = gym_session::Entity::find_by_id(id.to_owned())
+            .one(&txn)
+            .await?
+        else {
+            return Ok(());
+        };
+        let exercise_ids = Self::find_exercise_ids(&txn, session.id).await?;
+
+        // Sets and the records they held are deleted via cascade
+        gym_session::Entity::delete_by_id(session.id)
+            .exec(&txn)
             .await?;
+        for exercise_id in exercise_ids {
+            gym_personal_record::Entity::recompute(&txn, session.user_id, exercise_id).await?;
+        }
+        txn.commit().await?;
         Ok(())
     }
 }
@@ -1,12 +1,21 @@
-use entities::gym_set;
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::NaiveDate;
+use entities::{gym_personal_record, gym_session, gym_set, sea_orm_active_enums::SetTypeEnum};
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
-    prelude::Decimal,
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    QueryTrait, TransactionTrait,
+    sea_query::{Expr, ExprTrait},
 };
 use uuid::Uuid;
 
+use crate::{
+    gym::sets::SetRef,
+    schemas::gym_schemas::{BulkGymSetsRequest, CreateGymSetRequest, UpdateGymSetRequest},
+};
+
 #[derive(Clone)]
 pub struct GymSetRepository {
     db: DatabaseConnection,
@@ -17,25 +26,67 @@ impl GymSetRepository {
         Self { db }
     }
 
-    pub async fn create(
-        &self,
-        session_id: Uuid,
-        exercise_id: Uuid,
-        set_number: i32,
-        repetitions: i32,
-        weight_kg: Decimal,
-    ) -> Result<gym_set::Model, sea_orm::DbErr> {
-        let gym_set = gym_set::ActiveModel {
+    fn new_set(session_id: Uuid, request: CreateGymSetRequest) -> gym_set::ActiveModel {
+        gym_set::ActiveModel {
             id: NotSet,
             session_id: Set(session_id),
-            exercise_id: Set(exercise_id),
-            set_number: Set(set_number),
-            repetitions: Set(repetitions),
-            weight_kg: Set(weight_kg),
+            exercise_id: Set(request.exercise_id),
+            set_number: Set(request.set_number),
+            repetitions: Set(request.repetitions),
+            weight_kg: Set(request.weight_kg),
+            set_type: Set(request.set_type.unwrap_or(SetTypeEnum::Normal)),
+            rpe: Set(request.rpe),
+            rir: Set(request.rir),
+            rest_seconds: Set(request.rest_seconds),
+            duration_seconds: Set(request.duration_seconds),
+            distance_meters: Set(request.distance_meters),
             created_at: NotSet,
             updated_at: NotSet,
-        };
-        let gym_set = gym_set.insert(&self.db).await?;
+        }
+    }
+
+    fn apply_changes(gym_set: &mut gym_set::ActiveModel, request: UpdateGymSetRequest) {
+        if let Some(set_number) = request.set_number {
+            gym_set.set_number = Set(set_number);
+        }
+        if let Some(repetitions) = request.repetitions {
+            gym_set.repetitions = Set(repetitions);
+        }
+        if let Some(weight_kg) = request.weight_kg {
+            gym_set.weight_kg = Set(weight_kg);
+        }
+        if let Some(set_type) = request.set_type {
+            gym_set.set_type = Set(set_type);
+        }
+        if let Some(rpe) = request.rpe {
+            gym_set.rpe = Set(rpe);
+        }
+        if let Some(rir) = request.rir {
+            gym_set.rir = Set(rir);
+        }
+        if let Some(rest_seconds) = request.rest_seconds {
+            gym_set.rest_seconds = Set(rest_seconds);
+        }
+        if let Some(duration_seconds) = request.duration_seconds {
+            gym_set.duration_seconds = Set(duration_seconds);
+        }
+        if let Some(distance_meters) = request.distance_meters {
+            gym_set.distance_meters = Set(distance_meters);
+        }
+    }
+
+    /// Personal records of `user_id` are updated along with their sets
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        request: CreateGymSetRequest,
+    ) -> Result<gym_set::Model, sea_orm::DbErr> {
+        let gym_set = Self::new_set(session_id, request);
+        let txn = self.db.begin().await?;
+        let gym_set = gym_set.insert(&txn).await?;
+        gym_personal_record::Entity::recompute(&txn, user_id, gym_set.exercise_id).await?;
+        txn.commit().await?;
 
         Ok(gym_set)
     }
@@ -57,38 +108,241 @@ impl GymSetRepository {
             .await
     }
 
+    /// Sets of a user with the date of their session, oldest first and grouped by session,
+    /// optionally for a single exercise and between two dates included
+    pub async fn find_by_user_with_dates(
+        &self,
+        user_id: &Uuid,
+        exercise_id: Option<Uuid>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<(NaiveDate, gym_set::Model)>, sea_orm::DbErr> {
+        let mut query = gym_set::Entity::find()
+            .find_also_related(gym_session::Entity)
+            .filter(gym_session::Column::UserId.eq(user_id.to_owned()))
+            .order_by_asc(gym_session::Column::Date)
+            .order_by_asc(gym_session::Column::CreatedAt)
+            .order_by_asc(gym_set::Column::SessionId)
+            .order_by_asc(gym_set::Column::SetNumber);
+
+        if let Some(exercise_id) = exercise_id {
+            query = query.filter(gym_set::Column::ExerciseId.eq(exercise_id));
+        }
+        if let Some(from) = from {
+            query = query.filter(gym_session::Column::Date.gte(from));
+        }
+        if let Some(to) = to {
+            query = query.filter(gym_session::Column::Date.lte(to));
+        }
+
+        Ok(query
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .filter_map(|(set, session)| session.map(|session| (session.date, set)))
+            .collect())
+    }
+
+    /// Working sets of the last session of the user, on or before `date`, of each exercise
+    pub async fn find_last_performances(
+        &self,
+        user_id: &Uuid,
+        exercise_ids: Vec<Uuid>,
+        date: NaiveDate,
+    ) -> Result<Vec<gym_set::Model>, sea_orm::DbErr> {
+        Ok(self
+            .find_latest_sets(
+                Condition::all()
+                    .add(gym_session::Column::UserId.eq(user_id.to_owned()))
+                    .add(gym_session::Column::Date.lte(date))
+                    .add(gym_set::Column::ExerciseId.is_in(exercise_ids))
+                    .add(gym_set::Column::SetType.ne(SetTypeEnum::WarmUp)),
+            )
+            .await?
+            .into_iter()
+            .map(|(_, set)| set)
+            .collect())
+    }
+
+    /// Working sets, with their date, of the session before `session` in which each
+    /// exercise was done
+    pub async fn find_previous_performances(
+        &self,
+        session: &gym_session::Model,
+        exercise_ids: Vec<Uuid>,
+    ) -> Result<Vec<(NaiveDate, gym_set::Model)>, sea_orm::DbErr> {
+        self.find_latest_sets(
+            Condition::all()
+                .add(gym_session::Column::UserId.eq(session.user_id))
+                .add(
+                    Condition::any()
+                        .add(gym_session::Column::Date.lt(session.date))
+                        .add(
+                            Condition::all()
+                                .add(gym_session::Column::Date.eq(session.date))
+                                .add(gym_session::Column::CreatedAt.lt(session.created_at)),
+                        ),
+                )
+                .add(gym_set::Column::ExerciseId.is_in(exercise_ids))
+                .add(gym_set::Column::SetType.ne(SetTypeEnum::WarmUp))
+                .add(gym_set::Column::Repetitions.gt(0)),
+        )
+        .await
+    }
+
+    /// Sets matching `condition` of the latest session holding one of them, for each
+    /// exercise. Only that session is loaded rather than the whole history
+    async fn find_latest_sets(
+        &self,
+        condition: Condition,
+    ) -> Result<Vec<(NaiveDate, gym_set::Model)>, sea_orm::DbErr> {
+        let latest_sessions = gym_set::Entity::find()
+            .select_only()
+            .column(gym_set::Column::ExerciseId)
+            .column(gym_set::Column::SessionId)
+            .inner_join(gym_session::Entity)
+            .filter(condition.clone())
+            .distinct_on([gym_set::Column::ExerciseId])
+            .order_by_asc(gym_set::Column::ExerciseId)
+            .order_by_desc(gym_session::Column::Date)
+            .order_by_desc(gym_session::Column::CreatedAt)
+            .into_query();
+
+        Ok(gym_set::Entity::find()
+            .find_also_related(gym_session::Entity)
+            .filter(condition)
+            .filter(
+                Expr::tuple([
+                    Expr::col((gym_set::Entity, gym_set::Column::ExerciseId)),
+                    Expr::col((gym_set::Entity, gym_set::Column::SessionId)),
+                ])
+                .in_subquery(latest_sessions),
+            )
+            .order_by_desc(gym_session::Column::Date)
+            .order_by_desc(gym_session::Column::CreatedAt)
+            .order_by_asc(gym_set::Column::SetNumber)
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .filter_map(|(set, session)| session.map(|session| (session.date, set)))
+            .collect())
+    }
+
     pub async fn update(
         &self,
+        user_id: Uuid,
         id: Uuid,
-        set_number: Option<i32>,
-        repetitions: Option<i32>,
-        weight_kg: Option<Decimal>,
+        request: UpdateGymSetRequest,
     ) -> Result<gym_set::Model, sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
         let mut gym_set: gym_set::ActiveModel = gym_set::Entity::find_by_id(id)
-            .one(&self.db)
+            .one(&txn)
             .await?
             .ok_or(sea_orm::DbErr::RecordNotFound("Set not found".to_owned()))?
             .into();
+        Self::apply_changes(&mut gym_set, request);
 
-        if let Some(set_number) = set_number {
-            gym_set.set_number = Set(set_number);
+        let gym_set = gym_set.update(&txn).await?;
+        gym_personal_record::Entity::recompute(&txn, user_id, gym_set.exercise_id).await?;
+        txn.commit().await?;
+
+        Ok(gym_set)
+    }
+
+    /// Applies the changes of a bulk request in a single transaction, each set left in the
+    /// session getting its number from `numbers`. Returns every set of the session.
+    pub async fn apply_bulk(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        request: BulkGymSetsRequest,
+        numbers: Vec<(SetRef, i32)>,
+    ) -> Result<Vec<gym_set::Model>, sea_orm::DbErr> {
+        let numbers: HashMap<SetRef, i32> = numbers.into_iter().collect();
+        let txn = self.db.begin().await?;
+
+        let mut existing: HashMap<Uuid, gym_set::Model> = gym_set::Entity::find()
+            .filter(gym_set::Column::SessionId.eq(session_id))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|set| (set.id, set))
+            .collect();
+        let mut exercise_ids: BTreeSet<Uuid> =
+            existing.values().map(|set| set.exercise_id).collect();
+
+        if !request.delete.is_empty() {
+            gym_set::Entity::delete_many()
+                .filter(gym_set::Column::SessionId.eq(session_id))
+                .filter(gym_set::Column::Id.is_in(request.delete.clone()))
+                .exec(&txn)
+                .await?;
+            for id in &request.delete {
+                existing.remove(id);
+            }
         }
-        if let Some(repetitions) = repetitions {
-            gym_set.repetitions = Set(repetitions);
+
+        let mut changes: HashMap<Uuid, UpdateGymSetRequest> = request
+            .update
+            .into_iter()
+            .map(|update| (update.id, update.changes))
+            .collect();
+        for (id, set) in existing {
+            let number = numbers.get(&SetRef::Existing(id)).copied();
+            let change = changes.remove(&id);
+            if change.is_none() && number.is_none_or(|number| number == set.set_number) {
+                continue;
+            }
+
+            let mut set: gym_set::ActiveModel = set.into();
+            if let Some(change) = change {
+                Self::apply_changes(&mut set, change);
+            }
+            if let Some(number) = number {
+                set.set_number = Set(number);
+            }
+            set.update(&txn).await?;
         }
-        if let Some(weight_kg) = weight_kg {
-            gym_set.weight_kg = Set(weight_kg);
+
+        let created: Vec<gym_set::ActiveModel> = request
+            .create
+            .into_iter()
+            .enumerate()
+            .map(|(index, create)| {
+                exercise_ids.insert(create.exercise_id);
+                let mut set = Self::new_set(session_id, create);
+                if let Some(number) = numbers.get(&SetRef::Created(index)) {
+                    set.set_number = Set(*number);
+                }
+                set
+            })
+            .collect();
+        if !created.is_empty() {
+            gym_set::Entity::insert_many(created).exec(&txn).await?;
         }
 
-        let gym_set = gym_set.update(&self.db).await?;
+        for exercise_id in exercise_ids {
+            gym_personal_record::Entity::recompute(&txn, user_id, exercise_id).await?;
+        }
 
-        Ok(gym_set)
+        let sets = gym_set::Entity::find()
+            .filter(gym_set::Column::SessionId.eq(session_id))
+            .order_by_asc(gym_set::Column::SetNumber)
+            .all(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(sets)
     }
 
-    pub async fn delete(&self, id: &Uuid) -> Result<(), sea_orm::DbErr> {
-        gym_set::Entity::delete_by_id(id.to_owned())
-            .exec(&self.db)
-            .await?;
+    pub async fn delete(&self, user_id: Uuid, id: &Uuid) -> Result<(), sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+        let Some(gym_set) = gym_set::Entity::find_by_id(id.to_owned()).one(&txn).await? else {
+            return Ok(());
+        };
+        gym_set::Entity::delete_by_id(gym_set.id).exec(&txn).await?;
+        gym_personal_record::Entity::recompute(&txn, user_id, gym_set.exercise_id).await?;
+        txn.commit().await?;
         Ok(())
     }
 }
@@ -0,0 +1,67 @@
+use chrono::Utc;
+use entities::magic_link_token;
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    sea_query::Expr,
+};
+
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct MagicLinkRepository {
+    db: DatabaseConnection,
+}
+
+impl MagicLinkRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create_token(
+        &self,
+        user_id: &Uuid,
+        token: &str,
+        expires_at: &chrono::DateTime<chrono::FixedOffset>,
+    ) -> Result<magic_link_token::Model, sea_orm::DbErr> {
+        let magic_link_token = magic_link_token::ActiveModel {
+            id: NotSet,
+            user_id: Set(*user_id),
+            token: Set(token.to_owned()),
+            expires_at: Set(*expires_at),
+            used_at: Set(None),
+            created_at: NotSet,
+        };
+        magic_link_token.insert(&self.db).await
+    }
+
+    /// Mark the token as used and return it, in a single query so that two concurrent
+    /// requests cannot both log in with it. Expired and used tokens return `None`.
+    pub async fn consume_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<magic_link_token::Model>, sea_orm::DbErr> {
+        let now = Utc::now();
+        let tokens = magic_link_token::Entity::update_many()
+            .col_expr(magic_link_token::Column::UsedAt, Expr::current_timestamp())
+            .filter(magic_link_token::Column::Token.eq(token))
+            .filter(magic_link_token::Column::UsedAt.is_null())
+            .filter(magic_link_token::Column::ExpiresAt.gte(now))
+            .exec_with_returning(&self.db)
+            .await?;
+
+        Ok(tokens.into_iter().next())
+    }
+
+    /// Invalidate the other links of the user once one of them was used
+    pub async fn delete_all_user_tokens(&self, user_id: &Uuid) -> Result<(), sea_orm::DbErr> {
+        magic_link_token::Entity::delete_many()
+            .filter(magic_link_token::Column::UserId.eq(*user_id))
+            .filter(magic_link_token::Column::UsedAt.is_null())
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
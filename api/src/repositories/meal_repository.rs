@@ -1,8 +1,10 @@
-use entities::meal;
+use entities::{meal, meal_item};
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    ColumnTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect,
+    RelationTrait,
+    sea_query::Expr,
 };
 use uuid::Uuid;
 
@@ -64,6 +66,32 @@ impl MealRepository {
             .await
     }
 
+    /// Total calories eaten on `date` by each user, in a single query.
+    /// Users without any meal item that day are not returned.
+    pub async fn sum_calories_by_user_ids_and_date(
+        &self,
+        user_ids: &[Uuid],
+        date: chrono::NaiveDate,
+    ) -> Result<Vec<(Uuid, i64)>, sea_orm::DbErr> {
+        meal::Entity::find()
+            .select_only()
+            .column(meal::Column::UserId)
+            .column_as(
+                Expr::cust(
+                    "(SUM(meal_item.quantity_in_grams * food_item.calories_per100g) / 100)::bigint",
+                ),
+                "calories",
+            )
+            .join(JoinType::InnerJoin, meal::Relation::MealItem.def())
+            .join(JoinType::InnerJoin, meal_item::Relation::FoodItem.def())
+            .filter(meal::Column::UserId.is_in(user_ids.to_vec()))
+            .filter(meal::Column::Date.eq(date))
+            .group_by(meal::Column::UserId)
+            .into_tuple()
+            .all(&self.db)
+            .await
+    }
+
     pub async fn update(
         &self,
         id: Uuid,
@@ -1,48 +1,73 @@
 use sea_orm::DatabaseConnection;
 
 use crate::repositories::{
+    account_deletion_repository::AccountDeletionRepository,
+    data_import_repository::DataImportRepository,
     email_verification_repository::EmailVerificationRepository,
     food_item_repository::FoodItemRepository, gym_exercise_repository::GymExerciseRepository,
+    gym_personal_record_repository::GymPersonalRecordRepository,
     gym_session_repository::GymSessionRepository, gym_set_repository::GymSetRepository,
-    meal_item_repository::MealItemRepository, meal_repository::MealRepository,
-    password_reset_repository::PasswordResetRepository,
-    refresh_token_repository::RefreshTokenRepository, user_group_repository::UserGroupsRepository,
-    user_info_repository::UserInfoRepository, user_repository::UserRepository,
+    magic_link_repository::MagicLinkRepository, meal_item_repository::MealItemRepository,
+    meal_repository::MealRepository, password_reset_repository::PasswordResetRepository,
+    refresh_token_repository::RefreshTokenRepository,
+    training_program_repository::TrainingProgramRepository,
+    two_factor_repository::TwoFactorRepository, user_group_repository::UserGroupsRepository,
+    user_info_repository::UserInfoRepository,
+    user_login_device_repository::UserLoginDeviceRepository, user_repository::UserRepository,
     user_watch_permission_repository::UserWatchPermissionRepository,
-    user_weight_repository::UserWeightRepository,
+    user_weight_repository::UserWeightRepository, watch_request_repository::WatchRequestRepository,
+    workout_template_repository::WorkoutTemplateRepository,
 };
 
+pub mod account_deletion_repository;
+pub mod data_import_repository;
 pub mod email_verification_repository;
 pub mod food_item_repository;
 pub mod gym_exercise_repository;
+pub mod gym_personal_record_repository;
 pub mod gym_session_repository;
 pub mod gym_set_repository;
+pub mod magic_link_repository;
 pub mod meal_item_repository;
 pub mod meal_repository;
 pub mod password_reset_repository;
 pub mod refresh_token_repository;
+pub mod training_program_repository;
+pub mod two_factor_repository;
 pub mod user_group_repository;
 pub mod user_info_repository;
+pub mod user_login_device_repository;
 pub mod user_repository;
 pub mod user_watch_permission_repository;
 pub mod user_weight_repository;
+pub mod watch_request_repository;
+pub mod workout_template_repository;
 
 #[derive(Clone)]
 pub struct Repositories {
     pub user_repository: UserRepository,
     pub email_verification_repository: EmailVerificationRepository,
     pub password_reset_repository: PasswordResetRepository,
+    pub magic_link_repository: MagicLinkRepository,
+    pub account_deletion_repository: AccountDeletionRepository,
     pub refresh_token_repository: RefreshTokenRepository,
+    pub two_factor_repository: TwoFactorRepository,
     pub user_info_repository: UserInfoRepository,
+    pub user_login_device_repository: UserLoginDeviceRepository,
     pub user_group_repository: UserGroupsRepository,
     pub user_watch_permission_repository: UserWatchPermissionRepository,
     pub user_weight_repository: UserWeightRepository,
+    pub watch_request_repository: WatchRequestRepository,
     pub food_item_repository: FoodItemRepository,
     pub meal_repository: MealRepository,
     pub meal_item_repository: MealItemRepository,
     pub gym_exercise_repository: GymExerciseRepository,
     pub gym_session_repository: GymSessionRepository,
     pub gym_set_repository: GymSetRepository,
+    pub gym_personal_record_repository: GymPersonalRecordRepository,
+    pub workout_template_repository: WorkoutTemplateRepository,
+    pub training_program_repository: TrainingProgramRepository,
+    pub data_import_repository: DataImportRepository,
 }
 
 impl Repositories {
@@ -50,33 +75,51 @@ impl Repositories {
         let user_repository = UserRepository::new(db.clone());
         let email_verification_repository = EmailVerificationRepository::new(db.clone());
         let password_reset_repository = PasswordResetRepository::new(db.clone());
+        let magic_link_repository = MagicLinkRepository::new(db.clone());
+        let account_deletion_repository = AccountDeletionRepository::new(db.clone());
         let refresh_token_repository = RefreshTokenRepository::new(db.clone());
+        let two_factor_repository = TwoFactorRepository::new(db.clone());
         let user_info_repository = UserInfoRepository::new(db.clone());
+        let user_login_device_repository = UserLoginDeviceRepository::new(db.clone());
         let user_group_repository = UserGroupsRepository::new(db.clone());
         let user_watch_permission_repository = UserWatchPermissionRepository::new(db.clone());
         let user_weight_repository = UserWeightRepository::new(db.clone());
+        let watch_request_repository = WatchRequestRepository::new(db.clone());
         let food_item_repository = FoodItemRepository::new(db.clone());
         let meal_repository = MealRepository::new(db.clone());
         let meal_item_repository = MealItemRepository::new(db.clone());
         let gym_exercise_repository = GymExerciseRepository::new(db.clone());
         let gym_session_repository = GymSessionRepository::new(db.clone());
         let gym_set_repository = GymSetRepository::new(db.clone());
+        let gym_personal_record_repository = GymPersonalRecordRepository::new(db.clone());
+        let workout_template_repository = WorkoutTemplateRepository::new(db.clone());
+        let training_program_repository = TrainingProgramRepository::new(db.clone());
+        let data_import_repository = DataImportRepository::new(db.clone());
 
         Self {
             user_repository,
             email_verification_repository,
             password_reset_repository,
+            magic_link_repository,
+            account_deletion_repository,
             refresh_token_repository,
+            two_factor_repository,
             user_info_repository,
+            user_login_device_repository,
             user_group_repository,
             user_watch_permission_repository,
             user_weight_repository,
+            watch_request_repository,
             food_item_repository,
             meal_repository,
             meal_item_repository,
             gym_exercise_repository,
             gym_session_repository,
             gym_set_repository,
+            gym_personal_record_repository,
+            workout_template_repository,
+            training_program_repository,
+            data_import_repository,
         }
     }
 }
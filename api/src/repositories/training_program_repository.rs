@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use entities::{training_program, training_program_day, workout_template};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::schemas::training_program_schemas::{
+    CreateTrainingProgramRequest, ProgramDayRequest, ProgramDayResponse, TrainingProgramResponse,
+    UpdateTrainingProgramRequest,
+};
+
+#[derive(Clone)]
+pub struct TrainingProgramRepository {
+    db: DatabaseConnection,
+}
+
+impl TrainingProgramRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    async fn insert_days<C: ConnectionTrait>(
+        db: &C,
+        program_id: Uuid,
+        days: Vec<ProgramDayRequest>,
+    ) -> Result<(), sea_orm::DbErr> {
+        let days: Vec<training_program_day::ActiveModel> = days
+            .into_iter()
+            .map(|day| training_program_day::ActiveModel {
+                id: NotSet,
+                program_id: Set(program_id),
+                template_id: Set(day.template_id),
+                week: Set(day.week),
+                weekday: Set(day.weekday),
+                created_at: NotSet,
+                updated_at: NotSet,
+            })
+            .collect();
+        training_program_day::Entity::insert_many(days)
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn build_responses<C: ConnectionTrait>(
+        db: &C,
+        programs: Vec<training_program::Model>,
+    ) -> Result<Vec<TrainingProgramResponse>, sea_orm::DbErr> {
+        let program_ids: Vec<Uuid> = programs.iter().map(|p| p.id).collect();
+        let all_days = training_program_day::Entity::find()
+            .find_also_related(workout_template::Entity)
+            .filter(training_program_day::Column::ProgramId.is_in(program_ids))
+            .order_by_asc(training_program_day::Column::Week)
+            .order_by_asc(training_program_day::Column::Weekday)
+            .all(db)
+            .await?;
+
+        let mut days_by_program: HashMap<Uuid, Vec<ProgramDayResponse>> = HashMap::new();
+        for (day, template) in all_days {
+            days_by_program
+                .entry(day.program_id)
+                .or_default()
+                .push(ProgramDayResponse {
+                    id: day.id,
+                    template_id: day.template_id,
+                    template_name: template.map(|t| t.name).unwrap_or_default(),
+                    week: day.week,
+                    weekday: day.weekday,
+                });
+        }
+
+        Ok(programs
+            .into_iter()
+            .map(|program| TrainingProgramResponse {
+                days: days_by_program.remove(&program.id).unwrap_or_default(),
+                id: program.id,
+                name: program.name,
+                description: program.description,
+                start_date: program.start_date,
+                weeks: program.weeks,
+                increment_kg: program.increment_kg,
+                failures_before_deload: program.failures_before_deload,
+                deload_percent: program.deload_percent,
+            })
+            .collect())
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        request: CreateTrainingProgramRequest,
+    ) -> Result<TrainingProgramResponse, sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+        let program = training_program::ActiveModel {
+            id: NotSet,
+            user_id: Set(user_id),
+            name: Set(request.name),
+            description: Set(request.description),
+            start_date: Set(request.start_date),
+            weeks: Set(request.weeks),
+            increment_kg: request.increment_kg.map_or(NotSet, Set),
+            failures_before_deload: request.failures_before_deload.map_or(NotSet, Set),
+            deload_percent: request.deload_percent.map_or(NotSet, Set),
+            created_at: NotSet,
+            updated_at: NotSet,
+        }
+        .insert(&txn)
+        .await?;
+        Self::insert_days(&txn, program.id, request.days).await?;
+
+        let response = Self::build_responses(&txn, vec![program]).await?;
+        txn.commit().await?;
+
+        Ok(response.into_iter().next().unwrap())
+    }
+
+    pub async fn find_by_id(
+        &self,
+        id: &Uuid,
+    ) -> Result<Option<training_program::Model>, sea_orm::DbErr> {
+        training_program::Entity::find_by_id(id.to_owned())
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn find_by_id_with_days(
+        &self,
+        id: &Uuid,
+    ) -> Result<Option<TrainingProgramResponse>, sea_orm::DbErr> {
+        match self.find_by_id(id).await? {
+            Some(program) => Ok(Self::build_responses(&self.db, vec![program])
+                .await?
+                .into_iter()
+                .next()),
+            None => Ok(None),
+        }
+    }
+
+    /// Programs of the user, the latest started first
+    pub async fn find_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<TrainingProgramResponse>, sea_orm::DbErr> {
+        let programs = training_program::Entity::find()
+            .filter(training_program::Column::UserId.eq(user_id.to_owned()))
+            .order_by_desc(training_program::Column::StartDate)
+            .order_by_asc(training_program::Column::Name)
+            .all(&self.db)
+            .await?;
+
+        Self::build_responses(&self.db, programs).await
+    }
+
+    /// Programs of the user started on or before the date, with their days. Whether they
+    /// are over is left to the caller.
+    pub async fn find_started_with_days(
+        &self,
+        user_id: &Uuid,
+        date: NaiveDate,
+    ) -> Result<Vec<(training_program::Model, Vec<training_program_day::Model>)>, sea_orm::DbErr>
+    {
+        let programs = training_program::Entity::find()
+            .filter(training_program::Column::UserId.eq(user_id.to_owned()))
+            .filter(training_program::Column::StartDate.lte(date))
+            .order_by_asc(training_program::Column::StartDate)
+            .order_by_asc(training_program::Column::Name)
+            .all(&self.db)
+            .await?;
+
+        let program_ids: Vec<Uuid> = programs.iter().map(|p| p.id).collect();
+        let mut days_by_program: HashMap<Uuid, Vec<training_program_day::Model>> = HashMap::new();
+        for day in training_program_day::Entity::find()
+            .filter(training_program_day::Column::ProgramId.is_in(program_ids))
+            .all(&self.db)
+            .await?
+        {
+            days_by_program.entry(day.program_id).or_default().push(day);
+        }
+
+        Ok(programs
+            .into_iter()
+            .map(|program| {
+                let days = days_by_program.remove(&program.id).unwrap_or_default();
+                (program, days)
+            })
+            .collect())
+    }
+
+    pub async fn update(
+        &self,
+        id: Uuid,
+        request: UpdateTrainingProgramRequest,
+    ) -> Result<TrainingProgramResponse, sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+        let mut program: training_program::ActiveModel = training_program::Entity::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or(sea_orm::DbErr::RecordNotFound(
+                "Program not found".to_owned(),
+            ))?
+            .into();
+
+        if let Some(name) = request.name {
+            program.name = Set(name);
+        }
+        if let Some(description) = request.description {
+            program.description = Set(description);
+        }
+        if let Some(start_date) = request.start_date {
+            program.start_date = Set(start_date);
+        }
+        if let Some(weeks) = request.weeks {
+            program.weeks = Set(weeks);
+        }
+        if let Some(increment_kg) = request.increment_kg {
+            program.increment_kg = Set(increment_kg);
+        }
+        if let Some(failures_before_deload) = request.failures_before_deload {
+            program.failures_before_deload = Set(failures_before_deload);
+        }
+        if let Some(deload_percent) = request.deload_percent {
+            program.deload_percent = Set(deload_percent);
+        }
+        let program = program.update(&txn).await?;
+
+        if let Some(days) = request.days {
+            training_program_day::Entity::delete_many()
+                .filter(training_program_day::Column::ProgramId.eq(id))
+                .exec(&txn)
+                .await?;
+            Self::insert_days(&txn, id, days).await?;
+        }
+
+        let response = Self::build_responses(&txn, vec![program]).await?;
+        txn.commit().await?;
+
+        Ok(response.into_iter().next().unwrap())
+    }
+
+    pub async fn delete(&self, id: &Uuid) -> Result<(), sea_orm::DbErr> {
+        training_program::Entity::delete_by_id(id.to_owned())
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use chrono::Utc;
+use entities::{user_recovery_code, user_two_factor};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    TransactionTrait,
+    sea_query::Expr,
+};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TwoFactorRepository {
+    db: DatabaseConnection,
+}
+
+impl TwoFactorRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn find_by_user(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Option<user_two_factor::Model>, sea_orm::DbErr> {
+        user_two_factor::Entity::find_by_id(*user_id)
+            .one(&self.db)
+            .await
+    }
+
+    /// Store a new secret waiting for confirmation, replacing any previous unconfirmed one
+    pub async fn save_pending_secret(
+        &self,
+        existing: Option<user_two_factor::Model>,
+        user_id: &Uuid,
+        secret: &str,
+    ) -> Result<user_two_factor::Model, sea_orm::DbErr> {
+        match existing {
+            Some(two_factor) => {
+                let mut two_factor: user_two_factor::ActiveModel = two_factor.into();
+                two_factor.secret = Set(secret.to_string());
+                two_factor.enabled = Set(false);
+                two_factor.last_used_step = Set(None);
+                two_factor.enabled_at = Set(None);
+                two_factor.update(&self.db).await
+            }
+            None => {
+                let two_factor = user_two_factor::ActiveModel {
+                    user_id: Set(*user_id),
+                    secret: Set(secret.to_string()),
+                    enabled: Set(false),
+                    last_used_step: Set(None),
+                    enabled_at: Set(None),
+                    created_at: NotSet,
+                    updated_at: NotSet,
+                };
+                two_factor.insert(&self.db).await
+            }
+        }
+    }
+
+    /// Enable two-factor authentication along with its first recovery codes
+    pub async fn enable(
+        &self,
+        two_factor: user_two_factor::Model,
+        used_step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<user_two_factor::Model, sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+
+        let user_id = two_factor.user_id;
+        let mut two_factor: user_two_factor::ActiveModel = two_factor.into();
+        two_factor.enabled = Set(true);
+        two_factor.last_used_step = Set(Some(used_step));
+        two_factor.enabled_at = Set(Some(Utc::now().into()));
+        let two_factor = two_factor.update(&txn).await?;
+
+        Self::insert_recovery_codes(&txn, &user_id, recovery_code_hashes).await?;
+
+        txn.commit().await?;
+
+        Ok(two_factor)
+    }
+
+    /// Remember the last accepted time step, a code can only be used once
+    pub async fn set_last_used_step(
+        &self,
+        two_factor: user_two_factor::Model,
+        step: i64,
+    ) -> Result<user_two_factor::Model, sea_orm::DbErr> {
+        let mut two_factor: user_two_factor::ActiveModel = two_factor.into();
+        two_factor.last_used_step = Set(Some(step));
+        two_factor.update(&self.db).await
+    }
+
+    pub async fn disable(&self, user_id: &Uuid) -> Result<(), sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+
+        user_recovery_code::Entity::delete_many()
+            .filter(user_recovery_code::Column::UserId.eq(*user_id))
+            .exec(&txn)
+            .await?;
+        user_two_factor::Entity::delete_by_id(*user_id)
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await
+    }
+
+    /// Replace all recovery codes of the user, used or not
+    pub async fn replace_recovery_codes(
+        &self,
+        user_id: &Uuid,
+        recovery_code_hashes: &[String],
+    ) -> Result<(), sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+        Self::insert_recovery_codes(&txn, user_id, recovery_code_hashes).await?;
+        txn.commit().await
+    }
+
+    /// Mark an unused recovery code as used, returns false if there is no such code
+    pub async fn use_recovery_code(
+        &self,
+        user_id: &Uuid,
+        code_hash: &str,
+    ) -> Result<bool, sea_orm::DbErr> {
+        let result = user_recovery_code::Entity::update_many()
+            .col_expr(
+                user_recovery_code::Column::UsedAt,
+                Expr::current_timestamp(),
+            )
+            .filter(user_recovery_code::Column::UserId.eq(*user_id))
+            .filter(user_recovery_code::Column::CodeHash.eq(code_hash))
+            .filter(user_recovery_code::Column::UsedAt.is_null())
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    pub async fn count_unused_recovery_codes(&self, user_id: &Uuid) -> Result<u64, sea_orm::DbErr> {
+        user_recovery_code::Entity::find()
+            .filter(user_recovery_code::Column::UserId.eq(*user_id))
+            .filter(user_recovery_code::Column::UsedAt.is_null())
+            .count(&self.db)
+            .await
+    }
+
+    async fn insert_recovery_codes<C: ConnectionTrait>(
+        db: &C,
+        user_id: &Uuid,
+        recovery_code_hashes: &[String],
+    ) -> Result<(), sea_orm::DbErr> {
+        user_recovery_code::Entity::delete_many()
+            .filter(user_recovery_code::Column::UserId.eq(*user_id))
+            .exec(db)
+            .await?;
+
+        let codes = recovery_code_hashes
+            .iter()
+            .map(|hash| user_recovery_code::ActiveModel {
+                id: NotSet,
+                user_id: Set(*user_id),
+                code_hash: Set(hash.clone()),
+                used_at: NotSet,
+                created_at: NotSet,
+            });
+        user_recovery_code::Entity::insert_many(codes)
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::Utc;
+use entities::user_login_device;
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct UserLoginDeviceRepository {
+    db: DatabaseConnection,
+}
+
+impl UserLoginDeviceRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn find_all_by_user(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<user_login_device::Model>, sea_orm::DbErr> {
+        user_login_device::Entity::find()
+            .filter(user_login_device::Column::UserId.eq(*user_id))
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn create(
+        &self,
+        user_id: &Uuid,
+        user_agent: &str,
+        ip: Option<String>,
+    ) -> Result<user_login_device::Model, sea_orm::DbErr> {
+        let device = user_login_device::ActiveModel {
+            id: NotSet,
+            user_id: Set(*user_id),
+            user_agent: Set(user_agent.to_string()),
+            last_ip: Set(ip),
+            created_at: NotSet,
+            last_seen_at: NotSet,
+        };
+        device.insert(&self.db).await
+    }
+
+    /// Mark a known device as seen now, from the given IP
+    pub async fn touch(
+        &self,
+        device: user_login_device::Model,
+        ip: Option<String>,
+    ) -> Result<user_login_device::Model, sea_orm::DbErr> {
+        let mut device: user_login_device::ActiveModel = device.into();
+        device.last_ip = Set(ip);
+        device.last_seen_at = Set(Utc::now().into());
+        device.update(&self.db).await
+    }
+}
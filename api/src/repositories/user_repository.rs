@@ -1,9 +1,11 @@
-use entities::{sea_orm_active_enums::UserGroup, user_groups, users};
+use entities::{
+    refresh_token, sea_orm_active_enums::UserGroup, user_groups, users, users_ext::SYSTEM_USER_ID,
+};
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
     ColumnTrait, Condition, DatabaseConnection, EntityTrait, ExprTrait, JoinType, PaginatorTrait,
-    QueryFilter, QuerySelect, RelationTrait,
+    QueryFilter, QuerySelect, RelationTrait, TransactionTrait,
 };
 
 use uuid::Uuid;
@@ -128,6 +130,41 @@ impl UserRepository {
         active.update(&self.db).await
     }
 
+    /// Turn a guest into a regular account keeping all its data: the guest group is left
+    /// and the sessions opened as a guest are revoked. The email stays the verified guest
+    /// one until the new email is confirmed with an email change token.
+    pub async fn upgrade_guest(
+        &self,
+        id: &Uuid,
+        username: &str,
+        password_hash: &str,
+    ) -> Result<users::Model, sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+
+        let active = users::ActiveModel {
+            id: Set(*id),
+            username: Set(username.to_owned()),
+            password_hash: Set(password_hash.to_owned()),
+            ..Default::default()
+        };
+        let user = active.update(&txn).await?;
+
+        user_groups::Entity::delete_many()
+            .filter(user_groups::Column::UserId.eq(*id))
+            .filter(user_groups::Column::Group.eq(UserGroup::GuestGroup))
+            .exec(&txn)
+            .await?;
+
+        refresh_token::Entity::delete_many()
+            .filter(refresh_token::Column::UserId.eq(*id))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+
+        Ok(user)
+    }
+
     pub async fn search_by_username(
         &self,
         query: &str,
@@ -138,6 +175,7 @@ impl UserRepository {
                 Condition::all()
                     .add(users::Column::Username.contains(query))
                     .add(users::Column::EmailVerified.eq(true))
+                    .add(users::Column::Id.ne(SYSTEM_USER_ID))
                     .add(
                         user_groups::Column::Group
                             .ne(UserGroup::GuestGroup)
@@ -1,11 +1,14 @@
+use chrono::{DateTime, FixedOffset, Utc};
 use entities::{user_watch_permissions, users};
 use sea_orm::RelationTrait;
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    ColumnTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter, QuerySelect,
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, JoinType, QueryFilter, QuerySelect,
 };
 
+use crate::schemas::user_watch_permission_schemas::{WatchScopes, WatchScopesUpdate};
+
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -22,49 +25,94 @@ impl UserWatchPermissionRepository {
         &self,
         user_watched_id: &Uuid,
         user_watching_id: &Uuid,
+        scopes: &WatchScopes,
+        expires_at: Option<DateTime<FixedOffset>>,
     ) -> Result<user_watch_permissions::Model, sea_orm::DbErr> {
         let user_watch_permissions = user_watch_permissions::ActiveModel {
             user_watched_id: Set(*user_watched_id),
             user_watching_id: Set(*user_watching_id),
             created_at: NotSet,
+            can_view_weight: Set(scopes.weight),
+            can_view_meals: Set(scopes.meals),
+            can_view_gym: Set(scopes.gym),
+            can_view_body_infos: Set(scopes.body_infos),
+            expires_at: Set(expires_at),
         };
         let user_watch_permissions = user_watch_permissions.insert(&self.db).await?;
 
         Ok(user_watch_permissions)
     }
 
+    pub async fn update(
+        &self,
+        permission: user_watch_permissions::Model,
+        scopes: &WatchScopesUpdate,
+        expires_at: Option<Option<DateTime<FixedOffset>>>,
+    ) -> Result<user_watch_permissions::Model, sea_orm::DbErr> {
+        let mut permission: user_watch_permissions::ActiveModel = permission.into();
+        if let Some(weight) = scopes.weight {
+            permission.can_view_weight = Set(weight);
+        }
+        if let Some(meals) = scopes.meals {
+            permission.can_view_meals = Set(meals);
+        }
+        if let Some(gym) = scopes.gym {
+            permission.can_view_gym = Set(gym);
+        }
+        if let Some(body_infos) = scopes.body_infos {
+            permission.can_view_body_infos = Set(body_infos);
+        }
+        if let Some(expires_at) = expires_at {
+            permission.expires_at = Set(expires_at);
+        }
+        permission.update(&self.db).await
+    }
+
+    /// Find all permissions granted by the watched user, expired ones included
     pub async fn find_all_watched(
         &self,
         user_watched_id: &Uuid,
-    ) -> Result<Vec<users::Model>, sea_orm::DbErr> {
+    ) -> Result<Vec<(user_watch_permissions::Model, users::Model)>, sea_orm::DbErr> {
         let res = user_watch_permissions::Entity::find()
             .filter(user_watch_permissions::Column::UserWatchedId.eq(*user_watched_id))
             .join(
                 JoinType::InnerJoin,
-                user_watch_permissions::Relation::Users2.def(),
+                user_watch_permissions::Relation::Users1.def(),
             )
             .select_also(users::Entity)
             .all(&self.db)
             .await?;
 
-        Ok(res.into_iter().filter_map(|(_, user)| user).collect())
+        Ok(res
+            .into_iter()
+            .filter_map(|(permission, user)| user.map(|user| (permission, user)))
+            .collect())
     }
 
+    /// Find all non-expired permissions granted to the watching user
     pub async fn find_all_watching(
         &self,
         user_watching_id: &Uuid,
-    ) -> Result<Vec<users::Model>, sea_orm::DbErr> {
+    ) -> Result<Vec<(user_watch_permissions::Model, users::Model)>, sea_orm::DbErr> {
         let res = user_watch_permissions::Entity::find()
             .filter(user_watch_permissions::Column::UserWatchingId.eq(*user_watching_id))
+            .filter(
+                Condition::any()
+                    .add(user_watch_permissions::Column::ExpiresAt.is_null())
+                    .add(user_watch_permissions::Column::ExpiresAt.gt(Utc::now())),
+            )
             .join(
                 JoinType::InnerJoin,
-                user_watch_permissions::Relation::Users1.def(),
+                user_watch_permissions::Relation::Users2.def(),
             )
             .select_also(users::Entity)
             .all(&self.db)
             .await?;
 
-        Ok(res.into_iter().filter_map(|(_, user)| user).collect())
+        Ok(res
+            .into_iter()
+            .filter_map(|(permission, user)| user.map(|user| (permission, user)))
+            .collect())
     }
 
     pub async fn find_by_user_ids(
@@ -2,7 +2,7 @@ use entities::user_weight;
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
     prelude::Decimal,
 };
 use uuid::Uuid;
@@ -65,6 +65,34 @@ impl UserWeightRepository {
             .await
     }
 
+    /// Last recorded weight of each user, in a single query
+    pub async fn find_last_by_user_ids(
+        &self,
+        user_ids: &[Uuid],
+    ) -> Result<Vec<user_weight::Model>, sea_orm::DbErr> {
+        user_weight::Entity::find()
+            .distinct_on([user_weight::Column::UserId])
+            .filter(user_weight::Column::UserId.is_in(user_ids.to_vec()))
+            .order_by_asc(user_weight::Column::UserId)
+            .order_by_desc(user_weight::Column::RecordedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Weights of all the given users recorded since `since` (included), oldest first
+    pub async fn find_by_user_ids_since(
+        &self,
+        user_ids: &[Uuid],
+        since: chrono::NaiveDate,
+    ) -> Result<Vec<user_weight::Model>, sea_orm::DbErr> {
+        user_weight::Entity::find()
+            .filter(user_weight::Column::UserId.is_in(user_ids.to_vec()))
+            .filter(user_weight::Column::RecordedAt.gte(since))
+            .order_by_asc(user_weight::Column::RecordedAt)
+            .all(&self.db)
+            .await
+    }
+
     pub async fn update(
         &self,
         id: Uuid,
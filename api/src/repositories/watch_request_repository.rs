@@ -0,0 +1,159 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use entities::{
+    sea_orm_active_enums::WatchRequestStatusEnum, user_watch_permissions, users, watch_request,
+};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect,
+    RelationTrait, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::schemas::user_watch_permission_schemas::WatchScopes;
+
+#[derive(Clone)]
+pub struct WatchRequestRepository {
+    db: DatabaseConnection,
+}
+
+impl WatchRequestRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        requester_id: &Uuid,
+        target_id: &Uuid,
+        message: Option<String>,
+    ) -> Result<watch_request::Model, sea_orm::DbErr> {
+        let request = watch_request::ActiveModel {
+            id: NotSet,
+            requester_id: Set(*requester_id),
+            target_id: Set(*target_id),
+            status: Set(WatchRequestStatusEnum::Pending),
+            message: Set(message),
+            responded_at: NotSet,
+            created_at: NotSet,
+            updated_at: NotSet,
+        };
+        request.insert(&self.db).await
+    }
+
+    pub async fn find_by_id(
+        &self,
+        id: &Uuid,
+    ) -> Result<Option<watch_request::Model>, sea_orm::DbErr> {
+        watch_request::Entity::find_by_id(*id).one(&self.db).await
+    }
+
+    pub async fn find_pending_by_user_ids(
+        &self,
+        requester_id: &Uuid,
+        target_id: &Uuid,
+    ) -> Result<Option<watch_request::Model>, sea_orm::DbErr> {
+        watch_request::Entity::find()
+            .filter(watch_request::Column::RequesterId.eq(*requester_id))
+            .filter(watch_request::Column::TargetId.eq(*target_id))
+            .filter(watch_request::Column::Status.eq(WatchRequestStatusEnum::Pending))
+            .one(&self.db)
+            .await
+    }
+
+    /// Find pending requests received by the target user, with the requesting user
+    pub async fn find_pending_by_target(
+        &self,
+        target_id: &Uuid,
+    ) -> Result<Vec<(watch_request::Model, users::Model)>, sea_orm::DbErr> {
+        let res = watch_request::Entity::find()
+            .filter(watch_request::Column::TargetId.eq(*target_id))
+            .filter(watch_request::Column::Status.eq(WatchRequestStatusEnum::Pending))
+            .join(JoinType::InnerJoin, watch_request::Relation::Users2.def())
+            .select_also(users::Entity)
+            .order_by_desc(watch_request::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(res
+            .into_iter()
+            .filter_map(|(request, user)| user.map(|user| (request, user)))
+            .collect())
+    }
+
+    /// Find all requests sent by the requester, with the target user
+    pub async fn find_all_by_requester(
+        &self,
+        requester_id: &Uuid,
+    ) -> Result<Vec<(watch_request::Model, users::Model)>, sea_orm::DbErr> {
+        let res = watch_request::Entity::find()
+            .filter(watch_request::Column::RequesterId.eq(*requester_id))
+            .join(JoinType::InnerJoin, watch_request::Relation::Users1.def())
+            .select_also(users::Entity)
+            .order_by_desc(watch_request::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(res
+            .into_iter()
+            .filter_map(|(request, user)| user.map(|user| (request, user)))
+            .collect())
+    }
+
+    /// Mark the request as accepted and grant the matching watch permission in one transaction.
+    /// An existing (possibly expired) permission between the two users is overwritten.
+    pub async fn accept(
+        &self,
+        request: watch_request::Model,
+        scopes: &WatchScopes,
+        expires_at: Option<DateTime<FixedOffset>>,
+    ) -> Result<watch_request::Model, sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+
+        let existing = user_watch_permissions::Entity::find()
+            .filter(user_watch_permissions::Column::UserWatchedId.eq(request.target_id))
+            .filter(user_watch_permissions::Column::UserWatchingId.eq(request.requester_id))
+            .one(&txn)
+            .await?;
+
+        let mut permission: user_watch_permissions::ActiveModel = match &existing {
+            Some(permission) => permission.clone().into(),
+            None => user_watch_permissions::ActiveModel {
+                user_watched_id: Set(request.target_id),
+                user_watching_id: Set(request.requester_id),
+                created_at: NotSet,
+                ..Default::default()
+            },
+        };
+        permission.can_view_weight = Set(scopes.weight);
+        permission.can_view_meals = Set(scopes.meals);
+        permission.can_view_gym = Set(scopes.gym);
+        permission.can_view_body_infos = Set(scopes.body_infos);
+        permission.expires_at = Set(expires_at);
+
+        if existing.is_some() {
+            permission.update(&txn).await?;
+        } else {
+            permission.insert(&txn).await?;
+        }
+
+        let mut request: watch_request::ActiveModel = request.into();
+        request.status = Set(WatchRequestStatusEnum::Accepted);
+        request.responded_at = Set(Some(Utc::now().into()));
+        let request = request.update(&txn).await?;
+
+        txn.commit().await?;
+
+        Ok(request)
+    }
+
+    pub async fn decline(
+        &self,
+        request: watch_request::Model,
+    ) -> Result<watch_request::Model, sea_orm::DbErr> {
+        let mut request: watch_request::ActiveModel = request.into();
+        request.status = Set(WatchRequestStatusEnum::Declined);
+        request.responded_at = Set(Some(Utc::now().into()));
+        request.update(&self.db).await
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use entities::{gym_exercise, workout_template, workout_template_exercise};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::schemas::workout_template_schemas::{
+    CreateWorkoutTemplateRequest, UpdateWorkoutTemplateRequest, WorkoutTemplateExerciseRequest,
+    WorkoutTemplateExerciseResponse, WorkoutTemplateResponse,
+};
+
+#[derive(Clone)]
+pub struct WorkoutTemplateRepository {
+    db: DatabaseConnection,
+}
+
+impl WorkoutTemplateRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Positions follow the order of the request
+    async fn insert_exercises<C: ConnectionTrait>(
+        db: &C,
+        template_id: Uuid,
+        exercises: Vec<WorkoutTemplateExerciseRequest>,
+    ) -> Result<(), sea_orm::DbErr> {
+        let exercises: Vec<workout_template_exercise::ActiveModel> = exercises
+            .into_iter()
+            .zip(1..)
+            .map(
+                |(exercise, position)| workout_template_exercise::ActiveModel {
+                    id: NotSet,
+                    template_id: Set(template_id),
+                    exercise_id: Set(exercise.exercise_id),
+                    position: Set(position),
+                    target_sets: Set(exercise.target_sets),
+                    target_repetitions: Set(exercise.target_repetitions),
+                    target_weight_kg: Set(exercise.target_weight_kg),
+                    created_at: NotSet,
+                    updated_at: NotSet,
+                },
+            )
+            .collect();
+        workout_template_exercise::Entity::insert_many(exercises)
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn build_responses<C: ConnectionTrait>(
+        db: &C,
+        templates: Vec<workout_template::Model>,
+    ) -> Result<Vec<WorkoutTemplateResponse>, sea_orm::DbErr> {
+        let template_ids: Vec<Uuid> = templates.iter().map(|t| t.id).collect();
+        let all_exercises = workout_template_exercise::Entity::find()
+            .find_also_related(gym_exercise::Entity)
+            .filter(workout_template_exercise::Column::TemplateId.is_in(template_ids))
+            .order_by_asc(workout_template_exercise::Column::Position)
+            .all(db)
+            .await?;
+
+        let mut exercises_by_template: HashMap<Uuid, Vec<WorkoutTemplateExerciseResponse>> =
+            HashMap::new();
+        for (exercise, gym_exercise) in all_exercises {
+            exercises_by_template
+                .entry(exercise.template_id)
+                .or_default()
+                .push(WorkoutTemplateExerciseResponse {
+                    id: exercise.id,
+                    exercise_id: exercise.exercise_id,
+                    exercise_name: gym_exercise.map(|e| e.name).unwrap_or_default(),
+                    position: exercise.position,
+                    target_sets: exercise.target_sets,
+                    target_repetitions: exercise.target_repetitions,
+                    target_weight_kg: exercise.target_weight_kg,
+                });
+        }
+
+        Ok(templates
+            .into_iter()
+            .map(|template| WorkoutTemplateResponse {
+                exercises: exercises_by_template
+                    .remove(&template.id)
+                    .unwrap_or_default(),
+                id: template.id,
+                name: template.name,
+                description: template.description,
+            })
+            .collect())
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        request: CreateWorkoutTemplateRequest,
+    ) -> Result<WorkoutTemplateResponse, sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+        let template = workout_template::ActiveModel {
+            id: NotSet,
+            user_id: Set(user_id),
+            name: Set(request.name),
+            description: Set(request.description),
+            created_at: NotSet,
+            updated_at: NotSet,
+        }
+        .insert(&txn)
+        .await?;
+        Self::insert_exercises(&txn, template.id, request.exercises).await?;
+
+        let response = Self::build_responses(&txn, vec![template]).await?;
+        txn.commit().await?;
+
+        Ok(response.into_iter().next().unwrap())
+    }
+
+    pub async fn find_by_id(
+        &self,
+        id: &Uuid,
+    ) -> Result<Option<workout_template::Model>, sea_orm::DbErr> {
+        workout_template::Entity::find_by_id(id.to_owned())
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn find_by_ids(
+        &self,
+        ids: Vec<Uuid>,
+    ) -> Result<Vec<workout_template::Model>, sea_orm::DbErr> {
+        workout_template::Entity::find()
+            .filter(workout_template::Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn find_by_id_with_exercises(
+        &self,
+        id: &Uuid,
+    ) -> Result<Option<WorkoutTemplateResponse>, sea_orm::DbErr> {
+        match self.find_by_id(id).await? {
+            Some(template) => Ok(Self::build_responses(&self.db, vec![template])
+                .await?
+                .into_iter()
+                .next()),
+            None => Ok(None),
+        }
+    }
+
+    /// Templates of the user sorted by name
+    pub async fn find_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<Vec<WorkoutTemplateResponse>, sea_orm::DbErr> {
+        let templates = workout_template::Entity::find()
+            .filter(workout_template::Column::UserId.eq(user_id.to_owned()))
+            .order_by_asc(workout_template::Column::Name)
+            .all(&self.db)
+            .await?;
+
+        Self::build_responses(&self.db, templates).await
+    }
+
+    /// Exercises of the template in their order
+    pub async fn find_exercises(
+        &self,
+        template_id: &Uuid,
+    ) -> Result<Vec<workout_template_exercise::Model>, sea_orm::DbErr> {
+        workout_template_exercise::Entity::find()
+            .filter(workout_template_exercise::Column::TemplateId.eq(template_id.to_owned()))
+            .order_by_asc(workout_template_exercise::Column::Position)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn update(
+        &self,
+        id: Uuid,
+        request: UpdateWorkoutTemplateRequest,
+    ) -> Result<WorkoutTemplateResponse, sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+        let mut template: workout_template::ActiveModel = workout_template::Entity::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or(sea_orm::DbErr::RecordNotFound(
+                "Template not found".to_owned(),
+            ))?
+            .into();
+
+        if let Some(name) = request.name {
+            template.name = Set(name);
+        }
+        if let Some(description) = request.description {
+            template.description = Set(description);
+        }
+        let template = template.update(&txn).await?;
+
+        if let Some(exercises) = request.exercises {
+            workout_template_exercise::Entity::delete_many()
+                .filter(workout_template_exercise::Column::TemplateId.eq(id))
+                .exec(&txn)
+                .await?;
+            Self::insert_exercises(&txn, id, exercises).await?;
+        }
+
+        let response = Self::build_responses(&txn, vec![template]).await?;
+        txn.commit().await?;
+
+        Ok(response.into_iter().next().unwrap())
+    }
+
+    pub async fn delete(&self, id: &Uuid) -> Result<(), sea_orm::DbErr> {
+        workout_template::Entity::delete_by_id(id.to_owned())
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
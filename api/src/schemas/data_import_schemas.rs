@@ -0,0 +1,31 @@
+use chrono::{DateTime, FixedOffset};
+use entities::sea_orm_active_enums::{ImportKindEnum, ImportStatusEnum};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DataImportResponse {
+    pub id: Uuid,
+    pub kind: ImportKindEnum,
+    pub status: ImportStatusEnum,
+    pub dry_run: bool,
+    /// Rows imported (or that would be for a dry run), created exercises and the
+    /// errors of the rows skipped, once the import is finished
+    pub report: Option<serde_json::Value>,
+    pub created_at: DateTime<FixedOffset>,
+    pub finished_at: Option<DateTime<FixedOffset>>,
+}
+
+impl From<entities::data_import::Model> for DataImportResponse {
+    fn from(data_import: entities::data_import::Model) -> Self {
+        Self {
+            id: data_import.id,
+            kind: data_import.kind,
+            status: data_import.status,
+            dry_run: data_import.dry_run,
+            report: data_import.report,
+            created_at: data_import.created_at,
+            finished_at: data_import.finished_at,
+        }
+    }
+}
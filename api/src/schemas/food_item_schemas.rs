@@ -1,4 +1,5 @@
 use chrono::{DateTime, FixedOffset};
+use entities::sea_orm_active_enums::VisibilityEnum;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
@@ -57,6 +58,7 @@ pub struct FoodItemResponse {
     pub fat_per100g: i32,
     pub added_by: Uuid,
     pub added_at: DateTime<FixedOffset>,
+    pub visibility: VisibilityEnum,
 }
 
 impl From<entities::food_item::Model> for FoodItemResponse {
@@ -72,6 +74,7 @@ impl From<entities::food_item::Model> for FoodItemResponse {
             fat_per100g: food_item.fat_per100g,
             added_by: food_item.added_by,
             added_at: food_item.added_at,
+            visibility: food_item.visibility,
         }
     }
 }
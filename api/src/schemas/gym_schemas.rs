@@ -1,12 +1,17 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, FixedOffset, NaiveDate};
 use sea_orm::prelude::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::{Validate, ValidationError};
 
-use entities::sea_orm_active_enums::MuscleEnum;
+use entities::sea_orm_active_enums::{
+    EquipmentEnum, MovementPatternEnum, MuscleEnum, OneRepMaxFormulaEnum, PersonalRecordKindEnum,
+    SetTypeEnum, VisibilityEnum,
+};
 
-fn validate_weight_kg(weight: &Decimal) -> Result<(), ValidationError> {
+use crate::schemas::double_option;
+
+pub fn validate_weight_kg(weight: &Decimal) -> Result<(), ValidationError> {
     if *weight < Decimal::ZERO {
         return Err(ValidationError::new("weight_kg must be non-negative"));
     }
@@ -16,6 +21,29 @@ fn validate_weight_kg(weight: &Decimal) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Rate of perceived exertion, from 1 to 10 by steps of 0.5
+fn validate_rpe(rpe: &Decimal) -> Result<(), ValidationError> {
+    if *rpe < Decimal::ONE || *rpe > Decimal::TEN {
+        return Err(ValidationError::new("rpe must be between 1 and 10"));
+    }
+    if !(*rpe * Decimal::TWO).fract().is_zero() {
+        return Err(ValidationError::new("rpe must be a multiple of 0.5"));
+    }
+    Ok(())
+}
+
+fn validate_distance_meters(distance: &Decimal) -> Result<(), ValidationError> {
+    if *distance < Decimal::ZERO {
+        return Err(ValidationError::new("distance_meters must be non-negative"));
+    }
+    if *distance > Decimal::new(99999999, 2) {
+        return Err(ValidationError::new(
+            "distance_meters must be less than 1000 km",
+        ));
+    }
+    Ok(())
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateGymExerciseRequest {
     #[validate(length(
@@ -26,6 +54,10 @@ pub struct CreateGymExerciseRequest {
     pub name: String,
     #[validate(length(max = 1000, message = "Description must be less than 1000 characters"))]
     pub description: Option<String>,
+    pub equipment: Option<EquipmentEnum>,
+    pub movement_pattern: Option<MovementPatternEnum>,
+    #[serde(default)]
+    pub is_unilateral: bool,
     pub primary_muscles: Vec<MuscleEnum>,
     pub secondary_muscles: Vec<MuscleEnum>,
 }
@@ -40,6 +72,11 @@ pub struct UpdateGymExerciseRequest {
     pub name: Option<String>,
     #[validate(length(max = 1000, message = "Description must be less than 1000 characters"))]
     pub description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub equipment: Option<Option<EquipmentEnum>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub movement_pattern: Option<Option<MovementPatternEnum>>,
+    pub is_unilateral: Option<bool>,
     pub primary_muscles: Option<Vec<MuscleEnum>>,
     pub secondary_muscles: Option<Vec<MuscleEnum>>,
 }
@@ -49,6 +86,11 @@ pub struct GymExerciseResponse {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    pub equipment: Option<EquipmentEnum>,
+    pub movement_pattern: Option<MovementPatternEnum>,
+    pub is_unilateral: bool,
+    pub visibility: VisibilityEnum,
+    pub added_by: Uuid,
     pub primary_muscles: Vec<MuscleEnum>,
     pub secondary_muscles: Vec<MuscleEnum>,
 }
@@ -56,11 +98,39 @@ pub struct GymExerciseResponse {
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateGymSessionRequest {
     pub date: NaiveDate,
+    pub started_at: Option<DateTime<FixedOffset>>,
+    pub ended_at: Option<DateTime<FixedOffset>>,
+    #[validate(length(max = 2000, message = "Notes must be less than 2000 characters"))]
+    pub notes: Option<String>,
+    #[validate(range(
+        min = 1,
+        max = 10,
+        message = "Perceived difficulty must be between 1 and 10"
+    ))]
+    pub perceived_difficulty: Option<i32>,
+    /// A weight of the user recorded on the date of the session
+    pub user_weight_id: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct UpdateGymSessionRequest {
     pub date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub started_at: Option<Option<DateTime<FixedOffset>>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub ended_at: Option<Option<DateTime<FixedOffset>>>,
+    #[validate(length(max = 2000, message = "Notes must be less than 2000 characters"))]
+    #[serde(default, deserialize_with = "double_option")]
+    pub notes: Option<Option<String>>,
+    #[validate(range(
+        min = 1,
+        max = 10,
+        message = "Perceived difficulty must be between 1 and 10"
+    ))]
+    #[serde(default, deserialize_with = "double_option")]
+    pub perceived_difficulty: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub user_weight_id: Option<Option<Uuid>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -68,19 +138,35 @@ pub struct GymSessionResponse {
     pub id: Uuid,
     pub user_id: Uuid,
     pub date: NaiveDate,
+    pub started_at: Option<DateTime<FixedOffset>>,
+    pub ended_at: Option<DateTime<FixedOffset>>,
+    /// Only known when the session has both started and ended
+    pub duration_seconds: Option<i64>,
+    pub notes: Option<String>,
+    pub perceived_difficulty: Option<i32>,
+    pub user_weight_id: Option<Uuid>,
 }
 
 impl From<entities::gym_session::Model> for GymSessionResponse {
     fn from(session: entities::gym_session::Model) -> Self {
         Self {
+            duration_seconds: session
+                .started_at
+                .zip(session.ended_at)
+                .map(|(started_at, ended_at)| (ended_at - started_at).num_seconds()),
             id: session.id,
             user_id: session.user_id,
             date: session.date,
+            started_at: session.started_at,
+            ended_at: session.ended_at,
+            notes: session.notes,
+            perceived_difficulty: session.perceived_difficulty,
+            user_weight_id: session.user_weight_id,
         }
     }
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateGymSetRequest {
     pub exercise_id: Uuid,
     #[validate(range(min = 1, max = 100, message = "Set number must be between 1 and 100"))]
@@ -93,9 +179,29 @@ pub struct CreateGymSetRequest {
     pub repetitions: i32,
     #[validate(custom(function = "validate_weight_kg"))]
     pub weight_kg: Decimal,
+    /// Normal when not given
+    pub set_type: Option<SetTypeEnum>,
+    #[validate(custom(function = "validate_rpe"))]
+    pub rpe: Option<Decimal>,
+    #[validate(range(min = 0, max = 10, message = "RIR must be between 0 and 10"))]
+    pub rir: Option<i32>,
+    #[validate(range(
+        min = 0,
+        max = 3600,
+        message = "Rest must be between 0 and 3600 seconds"
+    ))]
+    pub rest_seconds: Option<i32>,
+    #[validate(range(
+        min = 0,
+        max = 86400,
+        message = "Duration must be between 0 and 86400 seconds"
+    ))]
+    pub duration_seconds: Option<i32>,
+    #[validate(custom(function = "validate_distance_meters"))]
+    pub distance_meters: Option<Decimal>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct UpdateGymSetRequest {
     #[validate(range(min = 1, max = 100, message = "Set number must be between 1 and 100"))]
     pub set_number: Option<i32>,
@@ -107,6 +213,82 @@ pub struct UpdateGymSetRequest {
     pub repetitions: Option<i32>,
     #[validate(custom(function = "validate_weight_kg"))]
     pub weight_kg: Option<Decimal>,
+    pub set_type: Option<SetTypeEnum>,
+    #[validate(custom(function = "validate_rpe"))]
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rpe: Option<Option<Decimal>>,
+    #[validate(range(min = 0, max = 10, message = "RIR must be between 0 and 10"))]
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rir: Option<Option<i32>>,
+    #[validate(range(
+        min = 0,
+        max = 3600,
+        message = "Rest must be between 0 and 3600 seconds"
+    ))]
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rest_seconds: Option<Option<i32>>,
+    #[validate(range(
+        min = 0,
+        max = 86400,
+        message = "Duration must be between 0 and 86400 seconds"
+    ))]
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration_seconds: Option<Option<i32>>,
+    #[validate(custom(function = "validate_distance_meters"))]
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub distance_meters: Option<Option<Decimal>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct BulkUpdateGymSetRequest {
+    pub id: Uuid,
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub changes: UpdateGymSetRequest,
+}
+
+/// Changes to the sets of a session, applied all together or not at all
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkGymSetsRequest {
+    #[serde(default)]
+    #[validate(
+        length(max = 100, message = "At most 100 sets can be created at once"),
+        nested
+    )]
+    pub create: Vec<CreateGymSetRequest>,
+    #[serde(default)]
+    #[validate(
+        length(max = 100, message = "At most 100 sets can be updated at once"),
+        nested
+    )]
+    pub update: Vec<BulkUpdateGymSetRequest>,
+    #[serde(default)]
+    #[validate(length(max = 100, message = "At most 100 sets can be deleted at once"))]
+    pub delete: Vec<Uuid>,
+    /// Number the sets of each exercise from 1, in the order of their numbers, instead of
+    /// rejecting duplicates
+    #[serde(default)]
+    pub renumber: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -117,17 +299,185 @@ pub struct GymSetResponse {
     pub set_number: i32,
     pub repetitions: i32,
     pub weight_kg: Decimal,
+    pub set_type: SetTypeEnum,
+    pub rpe: Option<Decimal>,
+    pub rir: Option<i32>,
+    pub rest_seconds: Option<i32>,
+    pub duration_seconds: Option<i32>,
+    pub distance_meters: Option<Decimal>,
+    /// Not given for warm-ups
+    pub estimated_one_rep_max: Option<Decimal>,
 }
 
-impl From<entities::gym_set::Model> for GymSetResponse {
-    fn from(gym_set: entities::gym_set::Model) -> Self {
+impl GymSetResponse {
+    pub fn with_formula(gym_set: entities::gym_set::Model, formula: &OneRepMaxFormulaEnum) -> Self {
         Self {
+            estimated_one_rep_max: gym_set
+                .is_working_set()
+                .then(|| gym_set.estimated_one_rep_max(formula))
+                .flatten(),
             id: gym_set.id,
             session_id: gym_set.session_id,
             exercise_id: gym_set.exercise_id,
             set_number: gym_set.set_number,
             repetitions: gym_set.repetitions,
             weight_kg: gym_set.weight_kg,
+            set_type: gym_set.set_type,
+            rpe: gym_set.rpe,
+            rir: gym_set.rir,
+            rest_seconds: gym_set.rest_seconds,
+            duration_seconds: gym_set.duration_seconds,
+            distance_meters: gym_set.distance_meters,
+        }
+    }
+}
+
+impl From<entities::gym_set::Model> for GymSetResponse {
+    fn from(gym_set: entities::gym_set::Model) -> Self {
+        Self::with_formula(gym_set, &OneRepMaxFormulaEnum::Epley)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PersonalRecordResponse {
+    pub id: Uuid,
+    pub kind: PersonalRecordKindEnum,
+    pub formula: Option<OneRepMaxFormulaEnum>,
+    pub value: Decimal,
+    pub previous_value: Option<Decimal>,
+    pub weight_kg: Option<Decimal>,
+    pub repetitions: Option<i32>,
+    pub session_id: Uuid,
+    pub set_id: Option<Uuid>,
+    pub achieved_on: NaiveDate,
+}
+
+impl From<entities::gym_personal_record::Model> for PersonalRecordResponse {
+    fn from(record: entities::gym_personal_record::Model) -> Self {
+        Self {
+            id: record.id,
+            kind: record.kind,
+            formula: record.formula,
+            value: record.value,
+            previous_value: record.previous_value,
+            weight_kg: record.weight_kg,
+            repetitions: record.repetitions,
+            session_id: record.session_id,
+            set_id: record.set_id,
+            achieved_on: record.achieved_on,
         }
     }
 }
+
+/// Current personal records of an exercise
+#[derive(Debug, Serialize)]
+pub struct PersonalRecordsResponse {
+    pub exercise_id: Uuid,
+    pub formula: OneRepMaxFormulaEnum,
+    pub heaviest_weight: Option<PersonalRecordResponse>,
+    pub best_one_rep_max: Option<PersonalRecordResponse>,
+    pub best_session_volume: Option<PersonalRecordResponse>,
+    /// Most repetitions done with each weight, heaviest first
+    pub most_reps: Vec<PersonalRecordResponse>,
+}
+
+impl PersonalRecordsResponse {
+    /// Each record only grows, so the current one is the best of its history
+    pub fn from_history(
+        exercise_id: Uuid,
+        formula: OneRepMaxFormulaEnum,
+        history: Vec<entities::gym_personal_record::Model>,
+    ) -> Self {
+        let mut response = Self {
+            exercise_id,
+            formula,
+            heaviest_weight: None,
+            best_one_rep_max: None,
+            best_session_volume: None,
+            most_reps: Vec::new(),
+        };
+
+        for record in history {
+            let current = match record.kind {
+                PersonalRecordKindEnum::HeaviestWeight => &mut response.heaviest_weight,
+                PersonalRecordKindEnum::BestOneRepMax => &mut response.best_one_rep_max,
+                PersonalRecordKindEnum::BestSessionVolume => &mut response.best_session_volume,
+                PersonalRecordKindEnum::MostReps => {
+                    match response
+                        .most_reps
+                        .iter_mut()
+                        .find(|best| best.weight_kg == record.weight_kg)
+                    {
+                        Some(best) if best.value >= record.value => {}
+                        Some(best) => *best = PersonalRecordResponse::from(record),
+                        None => response
+                            .most_reps
+                            .push(PersonalRecordResponse::from(record)),
+                    }
+                    continue;
+                }
+            };
+            if current
+                .as_ref()
+                .is_none_or(|best| record.value > best.value)
+            {
+                *current = Some(PersonalRecordResponse::from(record));
+            }
+        }
+
+        response
+            .most_reps
+            .sort_by_key(|record| std::cmp::Reverse(record.weight_kg));
+        response
+    }
+}
+
+/// Summary of an exercise in one session
+#[derive(Debug, Serialize)]
+pub struct ExerciseHistoryResponse {
+    pub session_id: Uuid,
+    pub date: NaiveDate,
+    pub top_set: GymSetResponse,
+    pub total_sets: i64,
+    pub total_reps: i64,
+    pub total_volume: Decimal,
+    pub estimated_one_rep_max: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MuscleVolumeResponse {
+    pub muscle: MuscleEnum,
+    /// Secondary muscles count for half a set
+    pub sets: Decimal,
+    pub volume: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeeklyVolumeResponse {
+    pub week_start: NaiveDate,
+    pub muscles: Vec<MuscleVolumeResponse>,
+}
+
+/// An exercise of a session with its sets, compared to the previous session it was done in
+#[derive(Debug, Serialize)]
+pub struct SessionExerciseResponse {
+    pub exercise_id: Uuid,
+    pub exercise_name: String,
+    pub primary_muscles: Vec<MuscleEnum>,
+    pub secondary_muscles: Vec<MuscleEnum>,
+    pub sets: Vec<GymSetResponse>,
+    pub total_sets: i64,
+    pub total_reps: i64,
+    pub total_volume: Decimal,
+    pub estimated_one_rep_max: Option<Decimal>,
+    pub previous: Option<ExerciseHistoryResponse>,
+    pub volume_change: Option<Decimal>,
+    pub one_rep_max_change: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GymSessionDetailsResponse {
+    pub session: GymSessionResponse,
+    /// In the order they were first logged
+    pub exercises: Vec<SessionExerciseResponse>,
+}
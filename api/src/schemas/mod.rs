@@ -1,11 +1,33 @@
+pub mod account_deletion_schemas;
 pub mod auth_schemas;
+pub mod data_export_schemas;
+pub mod data_import_schemas;
 pub mod food_item_schemas;
 pub mod gym_schemas;
+pub mod magic_link_schemas;
 pub mod meal_schemas;
 pub mod password_reset_schemas;
 pub mod settings_schemas;
 pub mod token_schemas;
+pub mod training_program_schemas;
+pub mod two_factor_schemas;
 pub mod user_group_schemas;
+pub mod user_info_schemas;
 pub mod user_schema;
 pub mod user_watch_permission_schemas;
 pub mod user_weight_schemas;
+pub mod watch_request_schemas;
+pub mod watching_schemas;
+pub mod workout_template_schemas;
+
+use serde::{Deserialize, Deserializer};
+
+/// For `Option<Option<T>>` update fields with `#[serde(default)]`, so that a missing
+/// field stays `None` while `null` becomes `Some(None)` and clears the value
+pub fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,142 @@
+use chrono::NaiveDate;
+use sea_orm::prelude::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+fn validate_increment_kg(increment: &Decimal) -> Result<(), ValidationError> {
+    if *increment < Decimal::ZERO || *increment > Decimal::from(50) {
+        return Err(ValidationError::new(
+            "increment_kg must be between 0 and 50",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ProgramDayRequest {
+    pub template_id: Uuid,
+    /// Every week of the program when not given
+    #[validate(range(min = 1, max = 52, message = "Week must be between 1 and 52"))]
+    pub week: Option<i32>,
+    /// From 1 (Monday) to 7 (Sunday)
+    #[validate(range(min = 1, max = 7, message = "Weekday must be between 1 and 7"))]
+    pub weekday: i32,
+}
+
+/// The progression rules left out take the default ones: 2.5 kg more after a completed
+/// session, and 10% less after 3 missed in a row
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateTrainingProgramRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+    #[validate(length(max = 1000, message = "Description must be less than 1000 characters"))]
+    pub description: Option<String>,
+    pub start_date: NaiveDate,
+    #[validate(range(min = 1, max = 52, message = "Weeks must be between 1 and 52"))]
+    pub weeks: i32,
+    #[validate(custom(function = "validate_increment_kg"))]
+    pub increment_kg: Option<Decimal>,
+    #[validate(range(
+        min = 1,
+        max = 10,
+        message = "Failures before deload must be between 1 and 10"
+    ))]
+    pub failures_before_deload: Option<i32>,
+    #[validate(range(min = 0, max = 50, message = "Deload percent must be between 0 and 50"))]
+    pub deload_percent: Option<i32>,
+    #[validate(
+        length(
+            min = 1,
+            max = 50,
+            message = "A program must have between 1 and 50 days"
+        ),
+        nested
+    )]
+    pub days: Vec<ProgramDayRequest>,
+}
+
+/// The days, when given, replace all the previous ones
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTrainingProgramRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: Option<String>,
+    #[validate(length(max = 1000, message = "Description must be less than 1000 characters"))]
+    pub description: Option<Option<String>>,
+    pub start_date: Option<NaiveDate>,
+    #[validate(range(min = 1, max = 52, message = "Weeks must be between 1 and 52"))]
+    pub weeks: Option<i32>,
+    #[validate(custom(function = "validate_increment_kg"))]
+    pub increment_kg: Option<Decimal>,
+    #[validate(range(
+        min = 1,
+        max = 10,
+        message = "Failures before deload must be between 1 and 10"
+    ))]
+    pub failures_before_deload: Option<i32>,
+    #[validate(range(min = 0, max = 50, message = "Deload percent must be between 0 and 50"))]
+    pub deload_percent: Option<i32>,
+    #[validate(
+        length(
+            min = 1,
+            max = 50,
+            message = "A program must have between 1 and 50 days"
+        ),
+        nested
+    )]
+    pub days: Option<Vec<ProgramDayRequest>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProgramDayResponse {
+    pub id: Uuid,
+    pub template_id: Uuid,
+    pub template_name: String,
+    pub week: Option<i32>,
+    pub weekday: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrainingProgramResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub start_date: NaiveDate,
+    pub weeks: i32,
+    pub increment_kg: Decimal,
+    pub failures_before_deload: i32,
+    pub deload_percent: i32,
+    pub days: Vec<ProgramDayResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExerciseTargetResponse {
+    pub exercise_id: Uuid,
+    pub exercise_name: String,
+    pub position: i32,
+    pub target_sets: i32,
+    pub target_repetitions: i32,
+    pub target_weight_kg: Decimal,
+    pub consecutive_failures: i32,
+    /// The weight was lowered after too many missed sessions
+    pub is_deload: bool,
+}
+
+/// A workout scheduled by a program, with the targets computed from the past sessions
+#[derive(Debug, Serialize)]
+pub struct TodayWorkoutResponse {
+    pub program_id: Uuid,
+    pub program_name: String,
+    pub week: i32,
+    pub template_id: Uuid,
+    pub template_name: String,
+    pub exercises: Vec<ExerciseTargetResponse>,
+}
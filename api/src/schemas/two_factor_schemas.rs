@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorStatusResponse {
+    pub enabled: bool,
+    pub recovery_codes_left: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorSetupResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorCodeRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// Returned by the login instead of the tokens when a second factor is needed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+    pub expires_in: u64,
+}
+
+/// Either a code of the authenticator app or one of the recovery codes
+#[derive(Debug, Deserialize)]
+pub struct SecondFactor {
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyTwoFactorRequest {
+    pub challenge_token: String,
+    #[serde(flatten)]
+    pub second_factor: SecondFactor,
+}
+
+#[derive(Deserialize)]
+pub struct DisableTwoFactorRequest {
+    pub password: String,
+    #[serde(flatten)]
+    pub second_factor: SecondFactor,
+}
+
+impl std::fmt::Debug for DisableTwoFactorRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DisableTwoFactorRequest")
+            .field("password", &"[REDACTED]")
+            .field("second_factor", &self.second_factor)
+            .finish()
+    }
+}
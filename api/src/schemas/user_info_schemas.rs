@@ -0,0 +1,28 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use entities::sea_orm_active_enums::GenderEnum;
+use sea_orm::prelude::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct UserInfosResponse {
+    pub user_id: Uuid,
+    pub birth_date: NaiveDate,
+    pub height_in_cm: i32,
+    pub gender: GenderEnum,
+    pub activity_level: Decimal,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<entities::user_additional_infos::Model> for UserInfosResponse {
+    fn from(infos: entities::user_additional_infos::Model) -> Self {
+        Self {
+            user_id: infos.user_id,
+            birth_date: infos.birth_date,
+            height_in_cm: infos.height_in_cm,
+            gender: infos.gender,
+            activity_level: infos.activity_level,
+            updated_at: infos.updated_at,
+        }
+    }
+}
@@ -1,7 +1,10 @@
+use chrono::{DateTime, FixedOffset, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::schemas::double_option;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UserSearchResult {
     pub id: Uuid,
@@ -28,10 +31,79 @@ pub struct SearchUsersResponse {
     pub users: Vec<UserSearchResult>,
 }
 
+/// Data domains a watch permission gives access to. Omitted scopes are granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchScopes {
+    #[serde(default = "granted")]
+    pub weight: bool,
+    #[serde(default = "granted")]
+    pub meals: bool,
+    #[serde(default = "granted")]
+    pub gym: bool,
+    #[serde(default = "granted")]
+    pub body_infos: bool,
+}
+
+fn granted() -> bool {
+    true
+}
+
+impl Default for WatchScopes {
+    fn default() -> Self {
+        Self {
+            weight: true,
+            meals: true,
+            gym: true,
+            body_infos: true,
+        }
+    }
+}
+
+impl From<&entities::user_watch_permissions::Model> for WatchScopes {
+    fn from(permission: &entities::user_watch_permissions::Model) -> Self {
+        Self {
+            weight: permission.can_view_weight,
+            meals: permission.can_view_meals,
+            gym: permission.can_view_gym,
+            body_infos: permission.can_view_body_infos,
+        }
+    }
+}
+
+/// Scopes to change on an existing permission. Omitted scopes keep their current value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchScopesUpdate {
+    pub weight: Option<bool>,
+    pub meals: Option<bool>,
+    pub gym: Option<bool>,
+    pub body_infos: Option<bool>,
+}
+
+/// Expiry dates must be in the future, a missing one means the permission never expires
+pub fn is_valid_expiry(expires_at: Option<DateTime<FixedOffset>>) -> bool {
+    expires_at.is_none_or(|expires_at| expires_at > Utc::now())
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WatchPermissionWithUser {
     pub user_id: Uuid,
     pub username: String,
+    pub scopes: WatchScopes,
+    pub expires_at: Option<DateTime<FixedOffset>>,
+}
+
+impl WatchPermissionWithUser {
+    pub fn new(
+        permission: &entities::user_watch_permissions::Model,
+        user: entities::users::Model,
+    ) -> Self {
+        Self {
+            user_id: user.id,
+            username: user.username,
+            scopes: WatchScopes::from(permission),
+            expires_at: permission.expires_at,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -47,6 +119,19 @@ pub struct WatchingResponse {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GrantWatchPermissionRequest {
     pub user_id: Uuid,
+    #[serde(default)]
+    pub scopes: WatchScopes,
+    pub expires_at: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateWatchPermissionRequest {
+    pub user_id: Uuid,
+    #[serde(default)]
+    pub scopes: WatchScopesUpdate,
+    /// Unchanged when missing, `null` makes the permission permanent
+    #[serde(default, deserialize_with = "double_option")]
+    pub expires_at: Option<Option<DateTime<FixedOffset>>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,57 @@
+use chrono::{DateTime, FixedOffset};
+use entities::sea_orm_active_enums::WatchRequestStatusEnum;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schemas::user_watch_permission_schemas::WatchScopes;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SendWatchRequestRequest {
+    pub user_id: Uuid,
+    #[validate(length(max = 500, message = "Message must be less than 500 characters"))]
+    pub message: Option<String>,
+}
+
+/// Scopes and expiry of the permission granted when accepting a request
+#[derive(Debug, Deserialize)]
+pub struct AcceptWatchRequestRequest {
+    #[serde(default)]
+    pub scopes: WatchScopes,
+    pub expires_at: Option<DateTime<FixedOffset>>,
+}
+
+/// A watch request, `user_id` and `username` being the other party
+/// (the requester for incoming requests, the target for outgoing ones)
+#[derive(Debug, Serialize)]
+pub struct WatchRequestResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub username: String,
+    pub status: WatchRequestStatusEnum,
+    pub message: Option<String>,
+    pub created_at: DateTime<FixedOffset>,
+    pub responded_at: Option<DateTime<FixedOffset>>,
+}
+
+impl WatchRequestResponse {
+    pub fn new(
+        request: entities::watch_request::Model,
+        other_user: entities::users::Model,
+    ) -> Self {
+        Self {
+            id: request.id,
+            user_id: other_user.id,
+            username: other_user.username,
+            status: request.status,
+            message: request.message,
+            created_at: request.created_at,
+            responded_at: request.responded_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchRequestsResponse {
+    pub requests: Vec<WatchRequestResponse>,
+}
@@ -0,0 +1,27 @@
+use chrono::NaiveDate;
+use sea_orm::prelude::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::schemas::{
+    user_watch_permission_schemas::WatchScopes, user_weight_schemas::UserWeightResponse,
+};
+
+/// Summary of a watched user. A field is null when there is no data for it
+/// or when its scope is not granted.
+#[derive(Debug, Serialize)]
+pub struct WatchedUserOverview {
+    pub user_id: Uuid,
+    pub username: String,
+    pub scopes: WatchScopes,
+    pub last_weight: Option<UserWeightResponse>,
+    /// Difference between the most recent and the oldest weight of the last 7 days
+    pub weight_trend_7_days: Option<Decimal>,
+    pub last_gym_session_date: Option<NaiveDate>,
+    pub yesterday_calories: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchingOverviewResponse {
+    pub users: Vec<WatchedUserOverview>,
+}
@@ -0,0 +1,99 @@
+use chrono::NaiveDate;
+use sea_orm::prelude::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schemas::gym_schemas::{GymSessionResponse, GymSetResponse, validate_weight_kg};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct WorkoutTemplateExerciseRequest {
+    pub exercise_id: Uuid,
+    #[validate(range(min = 1, max = 20, message = "Target sets must be between 1 and 20"))]
+    pub target_sets: i32,
+    #[validate(range(
+        min = 0,
+        max = 1000,
+        message = "Target repetitions must be between 0 and 1000"
+    ))]
+    pub target_repetitions: i32,
+    #[validate(custom(function = "validate_weight_kg"))]
+    pub target_weight_kg: Option<Decimal>,
+}
+
+/// Exercises are done in the order they are given
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateWorkoutTemplateRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+    #[validate(length(max = 1000, message = "Description must be less than 1000 characters"))]
+    pub description: Option<String>,
+    #[validate(
+        length(
+            min = 1,
+            max = 50,
+            message = "A template must have between 1 and 50 exercises"
+        ),
+        nested
+    )]
+    pub exercises: Vec<WorkoutTemplateExerciseRequest>,
+}
+
+/// The exercises, when given, replace all the previous ones
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateWorkoutTemplateRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: Option<String>,
+    #[validate(length(max = 1000, message = "Description must be less than 1000 characters"))]
+    pub description: Option<Option<String>>,
+    #[validate(
+        length(
+            min = 1,
+            max = 50,
+            message = "A template must have between 1 and 50 exercises"
+        ),
+        nested
+    )]
+    pub exercises: Option<Vec<WorkoutTemplateExerciseRequest>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkoutTemplateExerciseResponse {
+    pub id: Uuid,
+    pub exercise_id: Uuid,
+    pub exercise_name: String,
+    pub position: i32,
+    pub target_sets: i32,
+    pub target_repetitions: i32,
+    pub target_weight_kg: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkoutTemplateResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub exercises: Vec<WorkoutTemplateExerciseResponse>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StartWorkoutTemplateRequest {
+    pub date: NaiveDate,
+    /// Plan the sets from the last session of each exercise instead of the targets
+    #[serde(default)]
+    pub prefill_from_last: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StartedSessionResponse {
+    pub session: GymSessionResponse,
+    pub sets: Vec<GymSetResponse>,
+}
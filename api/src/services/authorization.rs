@@ -1,2 +1,4 @@
 // Re-export the authorization service for convenience
-pub use crate::auth::user_view_authorization::{UserViewAuthorization, ViewAuthorizationError};
+pub use crate::auth::user_view_authorization::{
+    UserViewAuthorization, ViewAuthorizationError, WatchScope,
+};
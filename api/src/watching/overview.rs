@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use entities::{user_watch_permissions, user_weight, users};
+use sea_orm::prelude::Decimal;
+use uuid::Uuid;
+
+use crate::{
+    auth::user_view_authorization::WatchScope,
+    schemas::{
+        user_watch_permission_schemas::WatchScopes, user_weight_schemas::UserWeightResponse,
+        watching_schemas::WatchedUserOverview,
+    },
+};
+
+/// Data of all watched users, fetched in batch
+pub struct WatchingOverviewData {
+    pub last_weights: Vec<user_weight::Model>,
+    /// Weights of the last 7 days, oldest first
+    pub recent_weights: Vec<user_weight::Model>,
+    pub last_gym_session_dates: Vec<(Uuid, NaiveDate)>,
+    pub yesterday_calories: Vec<(Uuid, i64)>,
+}
+
+pub fn watching_overview(
+    watched_users: Vec<(user_watch_permissions::Model, users::Model)>,
+    data: WatchingOverviewData,
+) -> Vec<WatchedUserOverview> {
+    let mut last_weights: HashMap<Uuid, user_weight::Model> = data
+        .last_weights
+        .into_iter()
+        .map(|w| (w.user_id, w))
+        .collect();
+
+    // (oldest, most recent) weight of the last 7 days per user
+    let mut weight_bounds: HashMap<Uuid, (Decimal, Decimal)> = HashMap::new();
+    for weight in &data.recent_weights {
+        weight_bounds
+            .entry(weight.user_id)
+            .and_modify(|(_, last)| *last = weight.weight_in_kg)
+            .or_insert((weight.weight_in_kg, weight.weight_in_kg));
+    }
+
+    let last_gym_session_dates: HashMap<Uuid, NaiveDate> =
+        data.last_gym_session_dates.into_iter().collect();
+    let yesterday_calories: HashMap<Uuid, i64> = data.yesterday_calories.into_iter().collect();
+
+    watched_users
+        .into_iter()
+        .map(|(permission, user)| {
+            let can_view_weight = WatchScope::Weight.is_granted_by(&permission);
+            let can_view_gym = WatchScope::Gym.is_granted_by(&permission);
+            let can_view_meals = WatchScope::Meals.is_granted_by(&permission);
+
+            WatchedUserOverview {
+                user_id: user.id,
+                username: user.username,
+                scopes: WatchScopes::from(&permission),
+                last_weight: last_weights
+                    .remove(&user.id)
+                    .filter(|_| can_view_weight)
+                    .map(UserWeightResponse::from),
+                weight_trend_7_days: weight_bounds
+                    .get(&user.id)
+                    .filter(|_| can_view_weight)
+                    .map(|(first, last)| last - first),
+                last_gym_session_date: last_gym_session_dates
+                    .get(&user.id)
+                    .copied()
+                    .filter(|_| can_view_gym),
+                yesterday_calories: yesterday_calories
+                    .get(&user.id)
+                    .copied()
+                    .filter(|_| can_view_meals),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn user(username: &str) -> users::Model {
+        users::Model {
+            id: Uuid::new_v4(),
+            username: username.to_string(),
+            email: format!("{username}@example.com"),
+            password_hash: String::new(),
+            email_verified: true,
+            profile_image: entities::sea_orm_active_enums::UserProfileImage::Avatar1,
+            created_at: Utc::now().into(),
+            updated_at: Utc::now().into(),
+        }
+    }
+
+    fn permission(watched: &users::Model, can_view_weight: bool) -> user_watch_permissions::Model {
+        user_watch_permissions::Model {
+            user_watched_id: watched.id,
+            user_watching_id: Uuid::new_v4(),
+            created_at: Utc::now().into(),
+            can_view_weight,
+            can_view_meals: true,
+            can_view_gym: true,
+            can_view_body_infos: true,
+            expires_at: None,
+        }
+    }
+
+    fn weight(user_id: Uuid, kg: i64, days_ago: i64) -> user_weight::Model {
+        user_weight::Model {
+            id: Uuid::new_v4(),
+            user_id,
+            weight_in_kg: Decimal::from(kg),
+            recorded_at: (Utc::now() - Duration::days(days_ago)).date_naive(),
+            created_at: Utc::now().into(),
+            updated_at: Utc::now().into(),
+        }
+    }
+
+    #[test]
+    fn test_watching_overview_trend_and_scopes() {
+        let alice = user("alice");
+        let bob = user("bob");
+        let today = Utc::now().date_naive();
+
+        let data = WatchingOverviewData {
+            last_weights: vec![weight(alice.id, 78, 0), weight(bob.id, 90, 0)],
+            recent_weights: vec![
+                weight(alice.id, 80, 6),
+                weight(bob.id, 91, 5),
+                weight(alice.id, 79, 3),
+                weight(alice.id, 78, 0),
+                weight(bob.id, 90, 0),
+            ],
+            last_gym_session_dates: vec![(alice.id, today)],
+            yesterday_calories: vec![(bob.id, 2100)],
+        };
+
+        let overview = watching_overview(
+            vec![
+                (permission(&alice, true), alice.clone()),
+                (permission(&bob, false), bob.clone()),
+            ],
+            data,
+        );
+
+        assert_eq!(overview.len(), 2);
+
+        let alice_overview = &overview[0];
+        assert_eq!(alice_overview.weight_trend_7_days, Some(Decimal::from(-2)));
+        assert_eq!(
+            alice_overview.last_weight.as_ref().unwrap().weight_in_kg,
+            Decimal::from(78)
+        );
+        assert_eq!(alice_overview.last_gym_session_date, Some(today));
+        assert_eq!(alice_overview.yesterday_calories, None);
+
+        // Bob did not grant the weight scope
+        let bob_overview = &overview[1];
+        assert!(bob_overview.last_weight.is_none());
+        assert!(bob_overview.weight_trend_7_days.is_none());
+        assert_eq!(bob_overview.yesterday_calories, Some(2100));
+    }
+}
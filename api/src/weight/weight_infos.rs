@@ -13,7 +13,7 @@ pub fn user_weight_infos(
 
     // Sort weights by recorded_at in descending order (most recent first)
     let mut sorted_weights = all_user_weights.clone();
-    sorted_weights.sort_by(|a, b| b.recorded_at.cmp(&a.recorded_at));
+    sorted_weights.sort_by_key(|w| std::cmp::Reverse(w.recorded_at));
 
     let last_3_weights: Vec<UserWeightResponse> = sorted_weights
         .iter()
@@ -0,0 +1,102 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use chrono::{Duration, Utc};
+use dimdim_health_api::schemas::{
+    account_deletion_schemas::AccountDeletionResponse, auth_schemas::LoginResponse,
+};
+use entities::{Job, JobEmail, JobEmailAccountDeletion};
+use redis::AsyncCommands;
+use serde_json::json;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+/// Whether an account deletion email was queued for this address
+async fn account_deletion_email_sent(redis: &redis::aio::ConnectionManager, email: &str) -> bool {
+    let mut redis = redis.clone();
+    let jobs: Vec<String> = redis.lrange("jobs", 0, -1).await.unwrap();
+    jobs.iter()
+        .filter_map(|job| serde_json::from_str::<Job>(job).ok())
+        .filter_map(|job| serde_json::from_value::<JobEmail>(job.data).ok())
+        .filter(|job| job.email_type.to_string() == "AccountDeletion")
+        .filter_map(|job| serde_json::from_value::<JobEmailAccountDeletion>(job.data).ok())
+        .any(|job| job.email == email)
+}
+
+#[tokio::test]
+async fn test_account_deletion_cancelled_by_login() {
+    let td = TestData::with_base_name("deleteacc");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    let res = server
+        .get(APP_PATHS.account_deletion)
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+
+    // The password has to be entered again
+    let res = server
+        .post(APP_PATHS.account_deletion)
+        .add_header("Authorization", auth_header(&login.access_token))
+        .json(&json!({"password": "wrongpassword"}))
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+
+    let res = server
+        .post(APP_PATHS.account_deletion)
+        .add_header("Authorization", auth_header(&login.access_token))
+        .json(&json!({"password": td.password}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let deletion = res.json::<AccountDeletionResponse>();
+    let grace_period = Duration::days(app_test.account_deletion.grace_period_days.into());
+    assert!(deletion.delete_after > Utc::now() + grace_period - Duration::minutes(1));
+    assert!(account_deletion_email_sent(&app_test.redis, &td.email).await);
+
+    let res = server
+        .get(APP_PATHS.account_deletion)
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(
+        res.json::<AccountDeletionResponse>().delete_after,
+        deletion.delete_after
+    );
+
+    // Every session is closed
+    let res = server
+        .post(APP_PATHS.refresh_token)
+        .json(&json!({"refresh_token": login.refresh_token}))
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+
+    // Logging in again cancels the deletion
+    let res = server
+        .post(APP_PATHS.login_user)
+        .json(&json!({"user": {"email": td.email, "password": td.password}}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    let res = server
+        .get(APP_PATHS.account_deletion)
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+}
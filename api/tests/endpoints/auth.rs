@@ -4,8 +4,15 @@ use crate::helpers::{
     test_server::{get_app_state, get_test_server},
 };
 use axum::http::{HeaderValue, StatusCode};
+use chrono::{Duration, Utc};
 use dimdim_health_api::schemas::auth_schemas::{LoginResponse, UserResponse};
+use entities::{email_verification_token, sea_orm_active_enums::UserGroup, users};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder,
+};
 use serde_json::json;
+use uuid::Uuid;
 
 #[tokio::test]
 async fn test_create_user() {
@@ -188,3 +195,204 @@ async fn test_create_guest_user() {
     assert!(current_user_data.email.ends_with("@dimdim.guest"));
     assert!(current_user_data.email_verified);
 }
+
+/// Token of the last email change asked by the user
+async fn pending_email_token(db: &DatabaseConnection, user_id: Uuid) -> String {
+    email_verification_token::Entity::find()
+        .filter(email_verification_token::Column::UserId.eq(user_id))
+        .filter(email_verification_token::Column::PendingEmail.is_not_null())
+        .order_by_desc(email_verification_token::Column::CreatedAt)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap()
+        .token
+}
+
+async fn is_guest(user_id: Uuid) -> bool {
+    get_app_state()
+        .await
+        .repositories
+        .user_group_repository
+        .is_user_id_in_group(&user_id, UserGroup::GuestGroup)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_upgrade_guest_user() {
+    let td = TestData::with_base_name("upgrade");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server.post(APP_PATHS.create_guest_user).await;
+    res.assert_status(StatusCode::OK);
+    let guest = res.json::<LoginResponse>();
+    let auth_header =
+        HeaderValue::from_str(format!("Token {}", guest.access_token).as_str()).unwrap();
+
+    let res = server
+        .post(APP_PATHS.upgrade_guest_user)
+        .add_header("Authorization", auth_header.clone())
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let upgraded = res.json::<LoginResponse>();
+    assert_eq!(upgraded.user.username, td.username);
+    // The guest email is kept until the new one is confirmed
+    assert_eq!(upgraded.user.email, guest.user.email);
+    assert!(upgraded.user.email_verified);
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_username(&td.username)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!is_guest(user.id).await);
+
+    // The sessions opened as a guest are revoked
+    let res = server
+        .post(APP_PATHS.refresh_token)
+        .json(&json!({"refresh_token": guest.refresh_token}))
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+
+    let res = server
+        .post(APP_PATHS.login_user)
+        .json(&json!({"user": {"email": td.email, "password": td.password}}))
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+
+    let token = pending_email_token(&app_test.db, user.id).await;
+    let res = server
+        .get(APP_PATHS.verify_email)
+        .add_query_param("token", token)
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let res = server
+        .post(APP_PATHS.login_user)
+        .json(&json!({"user": {"email": td.email, "password": td.password}}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+    assert_eq!(login.user.email, td.email);
+    assert!(login.user.email_verified);
+    assert!(!login.user.is_guest);
+
+    // A regular account cannot be upgraded again
+    let res = server
+        .post(APP_PATHS.upgrade_guest_user)
+        .add_header("Authorization", auth_header)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_upgraded_old_guest_survives_unverified_cleanup() {
+    let td = TestData::with_base_name("oldguest");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server.post(APP_PATHS.create_guest_user).await;
+    res.assert_status(StatusCode::OK);
+    let guest = res.json::<LoginResponse>();
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&guest.user.email)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut old_guest: users::ActiveModel = user.clone().into();
+    old_guest.created_at = Set((Utc::now() - Duration::days(30)).into());
+    old_guest.update(&app_test.db).await.unwrap();
+
+    let res = server
+        .post(APP_PATHS.upgrade_guest_user)
+        .add_header(
+            "Authorization",
+            HeaderValue::from_str(&format!("Token {}", guest.access_token)).unwrap(),
+        )
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    // Condition of the nightly cleanup of unverified accounts
+    let cleaned_up = users::Entity::find()
+        .filter(users::Column::Id.eq(user.id))
+        .filter(users::Column::EmailVerified.eq(false))
+        .filter(users::Column::CreatedAt.lt(Utc::now() - Duration::days(5)))
+        .count(&app_test.db)
+        .await
+        .unwrap();
+    assert_eq!(cleaned_up, 0);
+}
+
+#[tokio::test]
+async fn test_login_rehashes_bcrypt_password() {
+    let td = TestData::with_base_name("rehash");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    // Account created before the move to Argon2id
+    let bcrypt_hash = bcrypt::hash(&td.password, 4).unwrap();
+    let user = app_test
+        .repositories
+        .user_repository
+        .create(&td.username, &td.email, &bcrypt_hash, false)
+        .await
+        .unwrap();
+
+    let res = server
+        .post(APP_PATHS.login_user)
+        .json(&json!({"user": {"email": td.email, "password": td.password}}))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_id(&user.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(user.password_hash.starts_with("$argon2id$"));
+
+    // The new hash works for the next login
+    let res = server
+        .post(APP_PATHS.login_user)
+        .json(&json!({"user": {"email": td.email, "password": td.password}}))
+        .await;
+    res.assert_status(StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_create_user_breached_password() {
+    let td = TestData::with_base_name("breached");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": "Password123"}
+        }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert!(res.text().contains("breach"));
+}
@@ -0,0 +1,133 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use dimdim_health_api::schemas::auth_schemas::LoginResponse;
+use entities::{Job, JobDataExport, TaskType, data_export_key, data_export_pending_key};
+use redis::AsyncCommands;
+use serde_json::json;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn test_data_export_request_and_download() {
+    let td = TestData::with_base_name("dataexport");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    // The link is sent by email, so it has to be verified
+    let res = server
+        .post(APP_PATHS.data_export)
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    let res = server
+        .post(APP_PATHS.data_export)
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::ACCEPTED);
+
+    let mut redis = app_test.redis.clone();
+    let jobs: Vec<String> = redis.lrange("jobs", 0, -1).await.unwrap();
+    assert!(
+        jobs.iter()
+            .filter_map(|job| serde_json::from_str::<Job>(job).ok())
+            .filter(|job| matches!(job.task_type, TaskType::DataExport))
+            .filter_map(|job| serde_json::from_value::<JobDataExport>(job.data).ok())
+            .any(|job| job.user_id == user.id)
+    );
+
+    // Only one export at a time
+    let res = server
+        .post(APP_PATHS.data_export)
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    // Archive stored by the worker
+    redis
+        .del::<_, ()>(data_export_pending_key(&user.id))
+        .await
+        .unwrap();
+    let archive = b"PK\x05\x06".to_vec();
+    redis
+        .set_ex::<_, _, ()>(
+            data_export_key(&user.id, "exporttoken"),
+            archive.clone(),
+            60,
+        )
+        .await
+        .unwrap();
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .download_data_export
+                .replace("{token}", "exporttoken"),
+        )
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.header("content-type"), "application/zip");
+    assert_eq!(res.as_bytes().to_vec(), archive);
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .download_data_export
+                .replace("{token}", "unknowntoken"),
+        )
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+
+    // The link is useless to anyone else
+    let other = TestData::with_base_name("dataexpot");
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": other.username, "email": other.email, "password": other.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let other_login = res.json::<LoginResponse>();
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .download_data_export
+                .replace("{token}", "exporttoken"),
+        )
+        .add_header("Authorization", auth_header(&other_login.access_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,153 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use dimdim_health_api::schemas::{
+    auth_schemas::LoginResponse, data_import_schemas::DataImportResponse,
+};
+use entities::{
+    Job, JobDataImport, TaskType,
+    sea_orm_active_enums::{ImportKindEnum, ImportStatusEnum},
+};
+use redis::AsyncCommands;
+use serde_json::json;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+const GYM_SETS_CSV: &str = "date,exercise,set,reps,kg\n\
+    2024-01-15,Bench Press,1,10,60\n\
+    2024-01-15,Bench Press,2,8,65\n";
+
+#[tokio::test]
+async fn test_data_import_dry_run() {
+    let td = TestData::with_base_name("dataimport");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    let res = server
+        .post(APP_PATHS.data_imports)
+        .add_query_params(json!({"kind": "GymSets", "dry_run": true}))
+        .add_header("Authorization", auth_header(&login.access_token))
+        .text(GYM_SETS_CSV)
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    // Nothing to import without rows
+    let res = server
+        .post(APP_PATHS.data_imports)
+        .add_query_params(json!({"kind": "GymSets"}))
+        .add_header("Authorization", auth_header(&login.access_token))
+        .text("date,exercise,set,reps,kg\n")
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let res = server
+        .post(APP_PATHS.data_imports)
+        .add_query_params(json!({"kind": "GymSets", "dry_run": true}))
+        .add_header("Authorization", auth_header(&login.access_token))
+        .text(GYM_SETS_CSV)
+        .await;
+    res.assert_status(StatusCode::ACCEPTED);
+    let data_import = res.json::<DataImportResponse>();
+    assert_eq!(data_import.kind, ImportKindEnum::GymSets);
+    assert_eq!(data_import.status, ImportStatusEnum::Pending);
+    assert!(data_import.dry_run);
+    assert!(data_import.report.is_none());
+
+    let mut redis = app_test.redis.clone();
+    let jobs: Vec<String> = redis.lrange("jobs", 0, -1).await.unwrap();
+    assert!(
+        jobs.iter()
+            .filter_map(|job| serde_json::from_str::<Job>(job).ok())
+            .filter(|job| matches!(job.task_type, TaskType::DataImport))
+            .filter_map(|job| serde_json::from_value::<JobDataImport>(job.data).ok())
+            .any(|job| job.import_id == data_import.id)
+    );
+
+    let res = server
+        .get(APP_PATHS.data_imports)
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let imports = res.json::<Vec<DataImportResponse>>();
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].id, data_import.id);
+
+    let path = APP_PATHS
+        .data_import
+        .replace("{id}", &data_import.id.to_string());
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    // The dry run has not been processed by the worker yet
+    let res = server
+        .post(
+            &APP_PATHS
+                .apply_data_import
+                .replace("{id}", &data_import.id.to_string()),
+        )
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    // Imports are private
+    let other = TestData::with_base_name("dataimpot");
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": other.username, "email": other.email, "password": other.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let other_login = res.json::<LoginResponse>();
+    let other_user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&other.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&other_user.id)
+        .await
+        .unwrap();
+
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&other_login.access_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,144 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use dimdim_health_api::schemas::auth_schemas::LoginResponse;
+use serde_json::{Value, json};
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+fn names(exercises: &[Value]) -> Vec<&str> {
+    exercises
+        .iter()
+        .map(|exercise| exercise["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_gym_exercise_catalog_filters() {
+    let td = TestData::with_base_name("gymcatalog");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    // The catalog is there from the start
+    let res = server
+        .get(APP_PATHS.gym_exercises)
+        .add_query_params(json!({"equipment": "Barbell", "movement_pattern": "Hinge"}))
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let exercises = res.json::<Vec<Value>>();
+    assert!(names(&exercises).contains(&"Deadlift"));
+    assert!(exercises.iter().all(|exercise| {
+        exercise["equipment"] == "Barbell" && exercise["movement_pattern"] == "Hinge"
+    }));
+
+    let res = server
+        .get(APP_PATHS.gym_exercises)
+        .add_query_params(json!({"is_unilateral": true, "muscle": "Quadriceps"}))
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let exercises = res.json::<Vec<Value>>();
+    assert!(names(&exercises).contains(&"Bulgarian Split Squat"));
+    assert!(!names(&exercises).contains(&"Back Squat"));
+
+    // Catalog exercises belong to nobody
+    let deadlift = server
+        .get(APP_PATHS.gym_exercises)
+        .add_query_params(json!({"name": "Deadlift", "equipment": "Barbell"}))
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await
+        .json::<Vec<Value>>()
+        .into_iter()
+        .find(|exercise| exercise["name"] == "Deadlift")
+        .unwrap();
+    let res = server
+        .delete(
+            &APP_PATHS
+                .gym_exercise
+                .replace("{id}", deadlift["id"].as_str().unwrap()),
+        )
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let name = format!("{} Curl", td.username);
+    let res = server
+        .post(APP_PATHS.gym_exercises)
+        .json(&json!({
+            "name": name,
+            "equipment": "Cable",
+            "movement_pattern": "Isolation",
+            "is_unilateral": true,
+            "primary_muscles": ["Biceps"],
+            "secondary_muscles": []
+        }))
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let exercise = res.json::<Value>();
+    assert_eq!(exercise["equipment"], "Cable");
+    assert_eq!(exercise["is_unilateral"], true);
+
+    let res = server
+        .put(
+            &APP_PATHS
+                .gym_exercise
+                .replace("{id}", exercise["id"].as_str().unwrap()),
+        )
+        .json(&json!({"is_unilateral": false}))
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<Value>()["is_unilateral"], false);
+
+    let res = server
+        .put(
+            &APP_PATHS
+                .gym_exercise
+                .replace("{id}", exercise["id"].as_str().unwrap()),
+        )
+        .json(&json!({"equipment": null}))
+        .add_header("Authorization", auth_header(&login.access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let exercise = res.json::<Value>();
+    assert_eq!(exercise["equipment"], Value::Null);
+    assert_eq!(exercise["movement_pattern"], "Isolation");
+
+    // Nobody can log in as the owner of the catalog
+    let res = server
+        .post(APP_PATHS.login_user)
+        .json(&json!({"user": {"email": "system@dimdim-health.invalid", "password": "!"}}))
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+}
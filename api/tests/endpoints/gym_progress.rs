@@ -0,0 +1,166 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use axum_test::TestServer;
+use dimdim_health_api::{axummain::state::AppState, schemas::auth_schemas::LoginResponse};
+use serde_json::{Value, json};
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+async fn create_verified_user(server: &TestServer, app_test: &AppState, td: &TestData) -> String {
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    login.access_token
+}
+
+async fn create_exercise(
+    server: &TestServer,
+    token: &str,
+    name: String,
+    secondary: &str,
+) -> String {
+    let res = server
+        .post(APP_PATHS.gym_exercises)
+        .add_header("Authorization", auth_header(token))
+        .json(&json!({
+            "name": name,
+            "primary_muscles": ["Chest"],
+            "secondary_muscles": [secondary],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    res.json::<Value>()["id"].as_str().unwrap().to_string()
+}
+
+async fn log_session(server: &TestServer, token: &str, date: &str, sets: &[(&str, i32, i32)]) {
+    let res = server
+        .post(APP_PATHS.gym_sessions)
+        .add_header("Authorization", auth_header(token))
+        .json(&json!({"date": date}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let session_id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+
+    for (set_number, (exercise_id, repetitions, weight_kg)) in sets.iter().enumerate() {
+        let res = server
+            .post(&APP_PATHS.gym_sets.replace("{session_id}", &session_id))
+            .add_header("Authorization", auth_header(token))
+            .json(&json!({
+                "exercise_id": exercise_id,
+                "set_number": set_number + 1,
+                "repetitions": repetitions,
+                "weight_kg": weight_kg,
+            }))
+            .await;
+        res.assert_status(StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn test_gym_exercise_history_and_volume() {
+    let td = TestData::with_base_name("gymprogress");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let token = create_verified_user(&server, app_test, &td).await;
+
+    let bench = create_exercise(&server, &token, format!("{} Bench", td.username), "Triceps").await;
+    let fly = create_exercise(&server, &token, format!("{} Fly", td.username), "Shoulders").await;
+
+    // Monday 2024-03-04 and Sunday 2024-03-10 are in the same week
+    log_session(
+        &server,
+        &token,
+        "2024-03-04",
+        &[(&bench, 10, 80), (&bench, 8, 85)],
+    )
+    .await;
+    log_session(&server, &token, "2024-03-10", &[(&fly, 12, 20)]).await;
+    log_session(
+        &server,
+        &token,
+        "2024-03-11",
+        &[(&bench, 5, 90), (&bench, 5, 90)],
+    )
+    .await;
+
+    let history_path = APP_PATHS.gym_exercise_history.replace("{id}", &bench);
+    let res = server
+        .get(&history_path)
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let history = res.json::<Vec<Value>>();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["date"], "2024-03-04");
+    assert_eq!(history[0]["top_set"]["weight_kg"], "85.00");
+    assert_eq!(history[0]["total_reps"], 18);
+    assert_eq!(history[0]["total_volume"], "1480.00");
+    assert_eq!(history[1]["total_sets"], 2);
+    assert_eq!(history[1]["estimated_one_rep_max"], "105.00");
+
+    let res = server
+        .get(&history_path)
+        .add_query_params(json!({"from": "2024-03-05", "to": "2024-03-31"}))
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<Vec<Value>>().len(), 1);
+
+    let res = server
+        .get(&history_path)
+        .add_query_params(json!({"from": "2024-03-31", "to": "2024-03-05"}))
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let res = server
+        .get(APP_PATHS.gym_volume)
+        .add_query_params(json!({"from": "2024-03-01", "to": "2024-03-31"}))
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let weeks = res.json::<Vec<Value>>();
+    assert_eq!(weeks.len(), 2);
+    assert_eq!(weeks[0]["week_start"], "2024-03-04");
+    let muscle = |week: &Value, name: &str| -> Value {
+        week["muscles"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|volume| volume["muscle"] == name)
+            .cloned()
+            .unwrap()
+    };
+    assert_eq!(muscle(&weeks[0], "Chest")["sets"], "3");
+    assert_eq!(muscle(&weeks[0], "Triceps")["sets"], "1.0");
+    assert_eq!(muscle(&weeks[0], "Shoulders")["sets"], "0.5");
+    assert_eq!(weeks[1]["week_start"], "2024-03-11");
+    assert_eq!(weeks[1]["muscles"].as_array().unwrap().len(), 2);
+}
@@ -0,0 +1,349 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use axum_test::TestServer;
+use dimdim_health_api::{axummain::state::AppState, schemas::auth_schemas::LoginResponse};
+use serde_json::{Value, json};
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+async fn create_verified_user(server: &TestServer, app_test: &AppState, td: &TestData) -> String {
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    login.access_token
+}
+
+async fn create_session(server: &TestServer, token: &str, date: &str) -> String {
+    let res = server
+        .post(APP_PATHS.gym_sessions)
+        .add_header("Authorization", auth_header(token))
+        .json(&json!({"date": date}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    res.json::<Value>()["id"].as_str().unwrap().to_string()
+}
+
+async fn create_set(
+    server: &TestServer,
+    token: &str,
+    session_id: &str,
+    exercise_id: &str,
+    set_number: i32,
+    repetitions: i32,
+    weight_kg: i32,
+) -> Value {
+    let res = server
+        .post(&APP_PATHS.gym_sets.replace("{session_id}", session_id))
+        .add_header("Authorization", auth_header(token))
+        .json(&json!({
+            "exercise_id": exercise_id,
+            "set_number": set_number,
+            "repetitions": repetitions,
+            "weight_kg": weight_kg,
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    res.json::<Value>()
+}
+
+async fn get_records(server: &TestServer, token: &str, exercise_id: &str) -> Value {
+    let res = server
+        .get(&APP_PATHS.gym_exercise_records.replace("{id}", exercise_id))
+        .add_header("Authorization", auth_header(token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    res.json::<Value>()
+}
+
+#[tokio::test]
+async fn test_gym_exercise_records() {
+    let td = TestData::with_base_name("gymrecords");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let token = create_verified_user(&server, app_test, &td).await;
+
+    let res = server
+        .post(APP_PATHS.gym_exercises)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "name": format!("{} Squat", td.username),
+            "primary_muscles": ["Quadriceps"],
+            "secondary_muscles": ["Glutes"],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let exercise_id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+
+    let first = create_session(&server, &token, "2024-03-01").await;
+    let set = create_set(&server, &token, &first, &exercise_id, 1, 10, 100).await;
+    assert_eq!(set["estimated_one_rep_max"], "133.33");
+    create_set(&server, &token, &first, &exercise_id, 2, 8, 100).await;
+
+    let second = create_session(&server, &token, "2024-03-08").await;
+    let heavy = create_set(&server, &token, &second, &exercise_id, 1, 3, 120).await;
+
+    let records = get_records(&server, &token, &exercise_id).await;
+    assert_eq!(records["formula"], "Epley");
+    assert_eq!(records["heaviest_weight"]["value"], "120.00");
+    assert_eq!(records["heaviest_weight"]["previous_value"], "100.00");
+    assert_eq!(records["best_one_rep_max"]["value"], "133.33");
+    assert_eq!(records["best_session_volume"]["value"], "1800.00");
+    let most_reps = records["most_reps"].as_array().unwrap();
+    assert_eq!(most_reps.len(), 2);
+    assert_eq!(most_reps[0]["weight_kg"], "120.00");
+    assert_eq!(most_reps[1]["repetitions"], 10);
+
+    let res = server
+        .get(&APP_PATHS.gym_exercise_records.replace("{id}", &exercise_id))
+        .add_query_params(json!({"formula": "Brzycki"}))
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let records = res.json::<Value>();
+    assert_eq!(records["formula"], "Brzycki");
+    assert_eq!(records["best_one_rep_max"]["value"], "133.33");
+
+    // Records follow the sets they were made with
+    let heavy_path = APP_PATHS
+        .gym_set
+        .replace("{session_id}", &second)
+        .replace("{set_id}", heavy["id"].as_str().unwrap());
+    let res = server
+        .put(&heavy_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"repetitions": 8}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let records = get_records(&server, &token, &exercise_id).await;
+    assert_eq!(records["best_one_rep_max"]["value"], "152.00");
+
+    let res = server
+        .delete(&heavy_path)
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::NO_CONTENT);
+    let records = get_records(&server, &token, &exercise_id).await;
+    assert_eq!(records["heaviest_weight"]["value"], "100.00");
+    assert_eq!(records["best_one_rep_max"]["value"], "133.33");
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .gym_exercise_record_history
+                .replace("{id}", &exercise_id),
+        )
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let history = res.json::<Vec<Value>>();
+    assert!(
+        history
+            .iter()
+            .all(|record| record["session_id"] == first.as_str())
+    );
+    assert!(history.iter().all(|record| record["formula"] != "Brzycki"));
+
+    // Records of other users' exercises stay hidden
+    let other = TestData::with_base_name("gymrecords2");
+    let other_token = create_verified_user(&server, app_test, &other).await;
+    let res = server
+        .get(&APP_PATHS.gym_exercise_records.replace("{id}", &exercise_id))
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_gym_set_details() {
+    let td = TestData::with_base_name("gymdetails");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let token = create_verified_user(&server, app_test, &td).await;
+
+    let res = server
+        .post(APP_PATHS.gym_exercises)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "name": format!("{} Deadlift", td.username),
+            "primary_muscles": ["Hamstrings"],
+            "secondary_muscles": [],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let exercise_id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+
+    let session_id = create_session(&server, &token, "2024-04-01").await;
+    let sets_path = APP_PATHS.gym_sets.replace("{session_id}", &session_id);
+
+    for invalid in [
+        json!({"rpe": 10.5}),
+        json!({"rpe": 7.3}),
+        json!({"rir": -1}),
+        json!({"rest_seconds": 4000}),
+        json!({"distance_meters": -5}),
+    ] {
+        let mut body = json!({
+            "exercise_id": exercise_id,
+            "set_number": 1,
+            "repetitions": 5,
+            "weight_kg": 100,
+        });
+        body.as_object_mut()
+            .unwrap()
+            .extend(invalid.as_object().unwrap().clone());
+        let res = server
+            .post(&sets_path)
+            .add_header("Authorization", auth_header(&token))
+            .json(&body)
+            .await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    // A warm-up heavier than the working sets
+    let res = server
+        .post(&sets_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "exercise_id": exercise_id,
+            "set_number": 1,
+            "repetitions": 5,
+            "weight_kg": 150,
+            "set_type": "WarmUp",
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let warm_up = res.json::<Value>();
+    assert_eq!(warm_up["set_type"], "WarmUp");
+    assert_eq!(warm_up["estimated_one_rep_max"], Value::Null);
+
+    let res = server
+        .post(&sets_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "exercise_id": exercise_id,
+            "set_number": 2,
+            "repetitions": 5,
+            "weight_kg": 120,
+            "set_type": "Failure",
+            "rpe": 9.5,
+            "rir": 0,
+            "rest_seconds": 180,
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let set = res.json::<Value>();
+    assert_eq!(set["set_type"], "Failure");
+    assert_eq!(set["rpe"], "9.5");
+    assert_eq!(set["rir"], 0);
+    assert_eq!(set["rest_seconds"], 180);
+    assert_eq!(set["duration_seconds"], Value::Null);
+
+    let records = get_records(&server, &token, &exercise_id).await;
+    assert_eq!(records["heaviest_weight"]["value"], "120.00");
+    assert_eq!(records["best_session_volume"]["value"], "600.00");
+
+    // Only the given fields change, and a warm-up can become a working set
+    let res = server
+        .put(
+            &APP_PATHS
+                .gym_set
+                .replace("{session_id}", &session_id)
+                .replace("{set_id}", set["id"].as_str().unwrap()),
+        )
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"rpe": 8, "rir": 2}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let set = res.json::<Value>();
+    assert_eq!(set["rpe"], "8.0");
+    assert_eq!(set["rir"], 2);
+    assert_eq!(set["rest_seconds"], 180);
+
+    let res = server
+        .put(
+            &APP_PATHS
+                .gym_set
+                .replace("{session_id}", &session_id)
+                .replace("{set_id}", set["id"].as_str().unwrap()),
+        )
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"rpe": null}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let set = res.json::<Value>();
+    assert_eq!(set["rpe"], Value::Null);
+    assert_eq!(set["rir"], 2);
+
+    let res = server
+        .put(
+            &APP_PATHS
+                .gym_set
+                .replace("{session_id}", &session_id)
+                .replace("{set_id}", warm_up["id"].as_str().unwrap()),
+        )
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"set_type": "Normal"}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let records = get_records(&server, &token, &exercise_id).await;
+    assert_eq!(records["heaviest_weight"]["value"], "150.00");
+
+    // Timed and cardio sets
+    let res = server
+        .post(&sets_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "exercise_id": exercise_id,
+            "set_number": 3,
+            "repetitions": 0,
+            "weight_kg": 0,
+            "duration_seconds": 600,
+            "distance_meters": 2000.5,
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let set = res.json::<Value>();
+    assert_eq!(set["set_type"], "Normal");
+    assert_eq!(set["duration_seconds"], 600);
+    assert_eq!(set["distance_meters"], "2000.50");
+
+    // Bulk updates clear fields the same way
+    let res = server
+        .post(&APP_PATHS.gym_sets_bulk.replace("{session_id}", &session_id))
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"update": [{"id": set["id"], "distance_meters": null}]}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let sets = res.json::<Vec<Value>>();
+    let set = sets.iter().find(|other| other["id"] == set["id"]).unwrap();
+    assert_eq!(set["distance_meters"], Value::Null);
+    assert_eq!(set["duration_seconds"], 600);
+}
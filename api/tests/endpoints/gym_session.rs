@@ -0,0 +1,546 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use axum_test::TestServer;
+use chrono::NaiveDate;
+use dimdim_health_api::{
+    axummain::state::AppState,
+    gym::sets::number_sets,
+    schemas::{auth_schemas::LoginResponse, gym_schemas::BulkGymSetsRequest},
+};
+use sea_orm::{SqlErr, prelude::Decimal};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+/// Registers a user with a verified email and returns its access token and id
+async fn create_verified_user(
+    server: &TestServer,
+    app_test: &AppState,
+    td: &TestData,
+) -> (String, Uuid) {
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    (login.access_token, user.id)
+}
+
+async fn create_session(server: &TestServer, token: &str, date: &str) -> String {
+    let res = server
+        .post(APP_PATHS.gym_sessions)
+        .add_header("Authorization", auth_header(token))
+        .json(&json!({"date": date}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    res.json::<Value>()["id"].as_str().unwrap().to_string()
+}
+
+async fn create_exercise(server: &TestServer, token: &str, name: String) -> String {
+    let res = server
+        .post(APP_PATHS.gym_exercises)
+        .add_header("Authorization", auth_header(token))
+        .json(&json!({
+            "name": name,
+            "primary_muscles": ["Chest"],
+            "secondary_muscles": ["Triceps"],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    res.json::<Value>()["id"].as_str().unwrap().to_string()
+}
+
+fn decimal(value: &Value) -> f64 {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_gym_session_details() {
+    let td = TestData::with_base_name("gsession");
+    let other = TestData::with_base_name("gsession2");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let (token, user_id) = create_verified_user(&server, app_test, &td).await;
+    let (_, other_id) = create_verified_user(&server, app_test, &other).await;
+
+    let day = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+    let next_day = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+    let weight = app_test
+        .repositories
+        .user_weight_repository
+        .create(user_id, Decimal::from(80), day)
+        .await
+        .unwrap();
+    let next_day_weight = app_test
+        .repositories
+        .user_weight_repository
+        .create(user_id, Decimal::from(81), next_day)
+        .await
+        .unwrap();
+    let other_weight = app_test
+        .repositories
+        .user_weight_repository
+        .create(other_id, Decimal::from(70), day)
+        .await
+        .unwrap();
+
+    // Only a weight of the user on that day can be linked
+    for user_weight_id in [other_weight.id, next_day_weight.id] {
+        let res = server
+            .post(APP_PATHS.gym_sessions)
+            .add_header("Authorization", auth_header(&token))
+            .json(&json!({"date": "2024-03-04", "user_weight_id": user_weight_id}))
+            .await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+    }
+    let res = server
+        .post(APP_PATHS.gym_sessions)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "date": "2024-03-04",
+            "started_at": "2024-03-04T18:00:00+01:00",
+            "ended_at": "2024-03-04T17:00:00+01:00",
+        }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let res = server
+        .post(APP_PATHS.gym_sessions)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"date": "2024-03-04", "perceived_difficulty": 11}))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let res = server
+        .post(APP_PATHS.gym_sessions)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "date": "2024-03-04",
+            "started_at": "2024-03-04T07:00:00+01:00",
+            "ended_at": "2024-03-04T08:15:00+01:00",
+            "notes": "Morning lift",
+            "perceived_difficulty": 7,
+            "user_weight_id": weight.id,
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let morning = res.json::<Value>();
+    assert_eq!(morning["duration_seconds"], 4500);
+    assert_eq!(morning["notes"], "Morning lift");
+    assert_eq!(morning["perceived_difficulty"], 7);
+    assert_eq!(morning["user_weight_id"], weight.id.to_string());
+
+    // A second session the same day
+    let res = server
+        .post(APP_PATHS.gym_sessions)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"date": "2024-03-04", "started_at": "2024-03-04T18:00:00+01:00"}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let evening = res.json::<Value>();
+    assert!(evening["duration_seconds"].is_null());
+    let evening_path = APP_PATHS
+        .gym_session
+        .replace("{id}", evening["id"].as_str().unwrap());
+
+    let res = server
+        .get(APP_PATHS.gym_sessions)
+        .add_query_params(json!({"date": "2024-03-04"}))
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let sessions = res.json::<Vec<Value>>();
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0]["id"], morning["id"]);
+    assert_eq!(sessions[1]["id"], evening["id"]);
+
+    let res = server
+        .put(&evening_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"ended_at": "2024-03-04T17:30:00+01:00"}))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let res = server
+        .put(&evening_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"ended_at": "2024-03-04T19:00:00+01:00", "user_weight_id": weight.id}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let evening = res.json::<Value>();
+    assert_eq!(evening["duration_seconds"], 3600);
+    assert_eq!(evening["user_weight_id"], weight.id.to_string());
+
+    // Moving the session to another day unlinks the weight of the previous one
+    let res = server
+        .put(&evening_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"date": "2024-03-05"}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert!(res.json::<Value>()["user_weight_id"].is_null());
+
+    let res = server
+        .put(&evening_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"user_weight_id": weight.id}))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let res = server
+        .put(&evening_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"user_weight_id": next_day_weight.id}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(
+        res.json::<Value>()["user_weight_id"],
+        next_day_weight.id.to_string()
+    );
+
+    // `null` clears a field, leaving it out keeps it
+    let res = server
+        .put(&evening_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"notes": "Evening lift"}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<Value>()["notes"], "Evening lift");
+    let res = server
+        .put(&evening_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"notes": null, "user_weight_id": null}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let evening = res.json::<Value>();
+    assert!(evening["notes"].is_null());
+    assert!(evening["user_weight_id"].is_null());
+    assert_eq!(evening["duration_seconds"], 3600);
+}
+
+#[tokio::test]
+async fn test_gym_session_expanded_view() {
+    let td = TestData::with_base_name("gdetails");
+    let other = TestData::with_base_name("gdetails2");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let (token, _) = create_verified_user(&server, app_test, &td).await;
+    let (other_token, _) = create_verified_user(&server, app_test, &other).await;
+
+    let bench = create_exercise(&server, &token, format!("{} Bench", td.username)).await;
+    let press = create_exercise(&server, &token, format!("{} Press", td.username)).await;
+
+    let morning = create_session(&server, &token, "2024-04-01").await;
+    let evening = create_session(&server, &token, "2024-04-01").await;
+    for (session_id, exercise_id, set_number, repetitions, weight_kg, set_type) in [
+        (&morning, &bench, 1, 10, 20, "WarmUp"),
+        (&morning, &bench, 2, 10, 60, "Normal"),
+        (&morning, &bench, 3, 10, 60, "Normal"),
+        (&evening, &press, 1, 8, 40, "Normal"),
+        (&evening, &bench, 2, 8, 65, "Normal"),
+        (&evening, &bench, 1, 10, 65, "Normal"),
+    ] {
+        let res = server
+            .post(&APP_PATHS.gym_sets.replace("{session_id}", session_id))
+            .add_header("Authorization", auth_header(&token))
+            .json(&json!({
+                "exercise_id": exercise_id,
+                "set_number": set_number,
+                "repetitions": repetitions,
+                "weight_kg": weight_kg,
+                "set_type": set_type,
+            }))
+            .await;
+        res.assert_status(StatusCode::OK);
+    }
+
+    let path = APP_PATHS.gym_session_details.replace("{id}", &evening);
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let details = res.json::<Value>();
+    assert_eq!(details["session"]["id"], evening.as_str());
+
+    // Exercises come in the order they were logged, sets by number
+    let exercises = details["exercises"].as_array().unwrap();
+    assert_eq!(exercises.len(), 2);
+    assert_eq!(exercises[0]["exercise_id"], press.as_str());
+    assert_eq!(
+        exercises[0]["exercise_name"],
+        format!("{} Press", td.username)
+    );
+    assert_eq!(exercises[0]["primary_muscles"], json!(["Chest"]));
+    assert_eq!(exercises[0]["secondary_muscles"], json!(["Triceps"]));
+    assert!(exercises[0]["previous"].is_null());
+
+    let bench_details = &exercises[1];
+    assert_eq!(bench_details["sets"][0]["set_number"], 1);
+    assert_eq!(bench_details["sets"][0]["repetitions"], 10);
+    assert_eq!(bench_details["total_sets"], 2);
+    assert_eq!(bench_details["total_reps"], 18);
+    assert_eq!(decimal(&bench_details["total_volume"]), 1170.0);
+
+    // Compared to the morning session, its warm-up aside
+    let previous = &bench_details["previous"];
+    assert_eq!(previous["session_id"], morning.as_str());
+    assert_eq!(previous["total_sets"], 2);
+    assert_eq!(decimal(&previous["total_volume"]), 1200.0);
+    assert_eq!(decimal(&bench_details["volume_change"]), -30.0);
+    // 65 kg x 10 against 60 kg x 10
+    assert_eq!(decimal(&bench_details["one_rep_max_change"]), 6.67);
+
+    // Nothing before the first session
+    let res = server
+        .get(&APP_PATHS.gym_session_details.replace("{id}", &morning))
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let details = res.json::<Value>();
+    assert_eq!(details["exercises"].as_array().unwrap().len(), 1);
+    assert!(details["exercises"][0]["previous"].is_null());
+    assert_eq!(details["exercises"][0]["sets"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_gym_sets_bulk() {
+    let td = TestData::with_base_name("gbulk");
+    let other = TestData::with_base_name("gbulk2");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let (token, _) = create_verified_user(&server, app_test, &td).await;
+    let (other_token, _) = create_verified_user(&server, app_test, &other).await;
+
+    let bench = create_exercise(&server, &token, format!("{} Bench", td.username)).await;
+    let press = create_exercise(&server, &token, format!("{} Press", td.username)).await;
+    let hidden = create_exercise(&server, &other_token, format!("{} Dip", other.username)).await;
+    let session_id = create_session(&server, &token, "2024-05-01").await;
+    let path = APP_PATHS.gym_sets_bulk.replace("{session_id}", &session_id);
+    let sets_path = APP_PATHS.gym_sets.replace("{session_id}", &session_id);
+
+    let res = server
+        .post(&path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "create": [
+                {"exercise_id": bench, "set_number": 1, "repetitions": 10, "weight_kg": 60},
+                {"exercise_id": bench, "set_number": 2, "repetitions": 8, "weight_kg": 65},
+                {"exercise_id": bench, "set_number": 3, "repetitions": 8, "weight_kg": 65},
+                {"exercise_id": press, "set_number": 1, "repetitions": 10, "weight_kg": 30},
+            ],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let sets = res.json::<Vec<Value>>();
+    assert_eq!(sets.len(), 4);
+    let bench_sets: Vec<&Value> = sets
+        .iter()
+        .filter(|set| set["exercise_id"] == bench.as_str())
+        .collect();
+
+    let res = server
+        .post(&path)
+        .add_header("Authorization", auth_header(&other_token))
+        .json(&json!({"delete": [bench_sets[0]["id"]]}))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    // Any invalid change leaves the session untouched
+    let new_bench_set =
+        json!({"exercise_id": bench, "set_number": 2, "repetitions": 5, "weight_kg": 70});
+    for body in [
+        json!({"create": [new_bench_set]}),
+        json!({"create": [new_bench_set], "delete": [Uuid::new_v4()]}),
+        json!({"create": [{"exercise_id": hidden, "set_number": 1, "repetitions": 5, "weight_kg": 20}]}),
+        json!({
+            "update": [{"id": bench_sets[0]["id"], "repetitions": 12}],
+            "delete": [bench_sets[0]["id"]],
+        }),
+    ] {
+        let res = server
+            .post(&path)
+            .add_header("Authorization", auth_header(&token))
+            .json(&body)
+            .await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+    }
+    let res = server
+        .post(&path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"create": [new_bench_set]}))
+        .await;
+    let error = res.json::<Value>();
+    assert_eq!(error["exercise_id"], bench.as_str());
+    assert_eq!(error["set_number"], 2);
+
+    let res = server
+        .get(&sets_path)
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<Vec<Value>>().len(), 4);
+
+    // Numbers close the gaps in the order of the sets
+    let res = server
+        .post(&path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "create": [{"exercise_id": bench, "set_number": 4, "repetitions": 5, "weight_kg": 70}],
+            "update": [{"id": bench_sets[2]["id"], "repetitions": 6}],
+            "delete": [bench_sets[0]["id"]],
+            "renumber": true,
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let sets = res.json::<Vec<Value>>();
+    assert_eq!(sets.len(), 4);
+    let bench_sets_after: Vec<(i64, i64)> = sets
+        .iter()
+        .filter(|set| set["exercise_id"] == bench.as_str())
+        .map(|set| {
+            (
+                set["set_number"].as_i64().unwrap(),
+                set["repetitions"].as_i64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(bench_sets_after, vec![(1, 8), (2, 6), (3, 5)]);
+
+    // Personal records follow the new sets
+    let res = server
+        .get(&APP_PATHS.gym_exercise_records.replace("{id}", &bench))
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(
+        decimal(&res.json::<Value>()["heaviest_weight"]["value"]),
+        70.0
+    );
+}
+
+#[tokio::test]
+async fn test_gym_set_numbers_stay_unique() {
+    let td = TestData::with_base_name("gnumber");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let (token, user_id) = create_verified_user(&server, app_test, &td).await;
+
+    let bench = create_exercise(&server, &token, format!("{} Bench", td.username)).await;
+    let session_id = create_session(&server, &token, "2024-06-01").await;
+    let other_session_id = create_session(&server, &token, "2024-06-02").await;
+    let sets_path = APP_PATHS.gym_sets.replace("{session_id}", &session_id);
+
+    let mut set_ids = Vec::new();
+    for set_number in [1, 2] {
+        let res = server
+            .post(&sets_path)
+            .add_header("Authorization", auth_header(&token))
+            .json(&json!({
+                "exercise_id": bench, "set_number": set_number, "repetitions": 10, "weight_kg": 60
+            }))
+            .await;
+        res.assert_status(StatusCode::OK);
+        set_ids.push(res.json::<Value>()["id"].as_str().unwrap().to_string());
+    }
+
+    let res = server
+        .post(&sets_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"exercise_id": bench, "set_number": 2, "repetitions": 8, "weight_kg": 60}))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    let res = server
+        .put(
+            &APP_PATHS
+                .gym_set
+                .replace("{session_id}", &session_id)
+                .replace("{set_id}", &set_ids[1]),
+        )
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"set_number": 1}))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    // Numbers checked against sets that changed since are refused by the database
+    let session_id: Uuid = session_id.parse().unwrap();
+    let repository = &app_test.repositories.gym_set_repository;
+    let existing = repository.find_by_session_id(&session_id).await.unwrap();
+    let request: BulkGymSetsRequest = serde_json::from_value(json!({
+        "create": [{"exercise_id": bench, "set_number": 3, "repetitions": 5, "weight_kg": 70}],
+    }))
+    .unwrap();
+    let numbers = number_sets(&existing, &request).unwrap();
+    let res = server
+        .post(&sets_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"exercise_id": bench, "set_number": 3, "repetitions": 6, "weight_kg": 65}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let err = repository
+        .apply_bulk(user_id, session_id, request, numbers)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.sql_err(),
+        Some(SqlErr::UniqueConstraintViolation(_))
+    ));
+    assert_eq!(
+        repository
+            .find_by_session_id(&session_id)
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+
+    // A bulk request only deletes sets of its own session
+    let other_session_id: Uuid = other_session_id.parse().unwrap();
+    let request: BulkGymSetsRequest =
+        serde_json::from_value(json!({"delete": [set_ids[0]]})).unwrap();
+    repository
+        .apply_bulk(user_id, other_session_id, request, vec![])
+        .await
+        .unwrap();
+    assert_eq!(
+        repository
+            .find_by_session_id(&session_id)
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+}
@@ -0,0 +1,96 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum_test::TestServer;
+use dimdim_health_api::schemas::auth_schemas::LoginResponse;
+use redis::AsyncCommands;
+use serde_json::json;
+
+async fn register(server: &TestServer, td: &TestData) -> LoginResponse {
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    res.json::<LoginResponse>()
+}
+
+#[tokio::test]
+async fn test_account_locked_after_failed_logins() {
+    let td = TestData::with_base_name("lockout");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    register(&server, &td).await;
+
+    for _ in 0..app_test.login_security.max_failed_attempts_per_account {
+        let res = server
+            .post(APP_PATHS.login_user)
+            .json(&json!({"user": {"email": td.email, "password": "wrongpassword"}}))
+            .await;
+        res.assert_status(StatusCode::UNAUTHORIZED);
+    }
+
+    // The right password is refused while the account is locked
+    let res = server
+        .post(APP_PATHS.login_user)
+        .json(&json!({"user": {"email": td.email, "password": td.password}}))
+        .await;
+    res.assert_status(StatusCode::LOCKED);
+    assert!(res.maybe_header(header::RETRY_AFTER).is_some());
+}
+
+#[tokio::test]
+async fn test_login_from_new_device_sends_alert() {
+    let td = TestData::with_base_name("newdevice");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    register(&server, &td).await;
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    let body = json!({"user": {"email": td.email, "password": td.password}});
+    for user_agent in ["DimDim phone", "DimDim phone", "DimDim laptop"] {
+        let res = server
+            .post(APP_PATHS.login_user)
+            .add_header(header::USER_AGENT, HeaderValue::from_static(user_agent))
+            .json(&body)
+            .await;
+        res.assert_status(StatusCode::OK);
+    }
+
+    let devices = app_test
+        .repositories
+        .user_login_device_repository
+        .find_all_by_user(&user.id)
+        .await
+        .unwrap();
+    assert_eq!(devices.len(), 2);
+
+    // Only the laptop is new, the phone was the first device of the account
+    let mut redis = app_test.redis.clone();
+    let jobs: Vec<String> = redis.lrange("jobs", 0, -1).await.unwrap();
+    let alerts: Vec<&String> = jobs
+        .iter()
+        .filter(|job| job.contains("SecurityAlert") && job.contains(&td.email))
+        .collect();
+    assert_eq!(alerts.len(), 1);
+    assert!(alerts[0].contains("DimDim laptop"));
+}
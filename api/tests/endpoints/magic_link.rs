@@ -0,0 +1,95 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::StatusCode;
+use dimdim_health_api::schemas::auth_schemas::LoginResponse;
+use entities::{Job, JobEmail, JobEmailMagicLink};
+use redis::AsyncCommands;
+use serde_json::json;
+
+/// Token of the last magic link email queued for this address
+async fn last_magic_link_token(
+    redis: &redis::aio::ConnectionManager,
+    email: &str,
+) -> Option<String> {
+    let mut redis = redis.clone();
+    let jobs: Vec<String> = redis.lrange("jobs", 0, -1).await.unwrap();
+    jobs.iter()
+        .filter_map(|job| serde_json::from_str::<Job>(job).ok())
+        .filter_map(|job| serde_json::from_value::<JobEmail>(job.data).ok())
+        .filter(|job| job.email_type.to_string() == "MagicLink")
+        .filter_map(|job| serde_json::from_value::<JobEmailMagicLink>(job.data).ok())
+        .filter(|job| job.email == email)
+        .map(|job| job.token)
+        .next_back()
+}
+
+#[tokio::test]
+async fn test_magic_link_login() {
+    let td = TestData::with_base_name("magiclink");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let res = server
+        .post(APP_PATHS.magic_link)
+        .json(&json!({"email": td.email}))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let token = last_magic_link_token(&app_test.redis, &td.email)
+        .await
+        .expect("a magic link email should be queued");
+
+    let res = server
+        .post(APP_PATHS.verify_magic_link)
+        .json(&json!({"token": token}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+    assert_eq!(login.user.email, td.email);
+    assert!(!login.refresh_token.is_empty());
+
+    // A link can only be used once
+    let res = server
+        .post(APP_PATHS.verify_magic_link)
+        .json(&json!({"token": token}))
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_magic_link_unknown_email() {
+    let td = TestData::with_base_name("magicnone");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    // Same answer as for a registered email, but nothing is sent
+    let res = server
+        .post(APP_PATHS.magic_link)
+        .json(&json!({"email": td.email}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert!(
+        last_magic_link_token(&app_test.redis, &td.email)
+            .await
+            .is_none()
+    );
+
+    let res = server
+        .post(APP_PATHS.verify_magic_link)
+        .json(&json!({"token": "not-a-real-token"}))
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+}
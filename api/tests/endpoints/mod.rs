@@ -1,3 +1,21 @@
+mod account_deletion;
 mod auth;
+mod data_export;
+mod data_import;
+mod gym_exercise;
+mod gym_progress;
+mod gym_records;
+mod gym_session;
+mod login_security;
+mod magic_link;
+mod moderation;
+mod rate_limit;
 mod server_health;
+mod training_program;
+mod two_factor;
 mod user_group;
+mod user_watch_permissions;
+mod user_weight;
+mod watch_request;
+mod watching;
+mod workout_template;
@@ -0,0 +1,369 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use axum_test::TestServer;
+use chrono::Utc;
+use dimdim_health_api::{axummain::state::AppState, schemas::auth_schemas::LoginResponse};
+use entities::sea_orm_active_enums::{MealTypeEnum, UserGroup, VisibilityEnum};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+/// Registers a user with a verified email and returns its access token and id
+async fn create_verified_user(
+    server: &TestServer,
+    app_test: &AppState,
+    td: &TestData,
+) -> (String, Uuid) {
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    (login.access_token, user.id)
+}
+
+#[tokio::test]
+async fn test_moderation_gym_exercise() {
+    let owner = TestData::with_base_name("modowner");
+    let other = TestData::with_base_name("modother");
+    let admin = TestData::with_base_name("modadmin");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let (owner_token, _) = create_verified_user(&server, app_test, &owner).await;
+    let (other_token, _) = create_verified_user(&server, app_test, &other).await;
+    let (admin_token, admin_id) = create_verified_user(&server, app_test, &admin).await;
+    app_test
+        .repositories
+        .user_group_repository
+        .create(&admin_id, UserGroup::AdminGroup)
+        .await
+        .unwrap();
+
+    let name = format!("{} Press", owner.username);
+    let res = server
+        .post(APP_PATHS.gym_exercises)
+        .add_header("Authorization", auth_header(&owner_token))
+        .json(&json!({
+            "name": name,
+            "primary_muscles": ["Chest"],
+            "secondary_muscles": [],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let exercise = res.json::<Value>();
+    assert_eq!(exercise["visibility"], "Private");
+    let id = exercise["id"].as_str().unwrap().to_string();
+    let path = APP_PATHS.gym_exercise.replace("{id}", &id);
+
+    // Private exercises are only seen by their creator
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+    let res = server
+        .get(APP_PATHS.gym_exercises)
+        .add_query_params(json!({"name": name}))
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert!(res.json::<Vec<Value>>().is_empty());
+
+    let res = server
+        .post(&APP_PATHS.share_gym_exercise.replace("{id}", &id))
+        .add_header("Authorization", auth_header(&owner_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<Value>()["visibility"], "Pending");
+
+    // Only admins moderate
+    let approve_path = APP_PATHS.approve_exercise.replace("{id}", &id);
+    let res = server
+        .post(&approve_path)
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let res = server
+        .get(APP_PATHS.moderation_exercises)
+        .add_header("Authorization", auth_header(&admin_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert!(
+        res.json::<Vec<Value>>()
+            .iter()
+            .any(|exercise| exercise["id"] == id.as_str())
+    );
+
+    let res = server
+        .post(&approve_path)
+        .add_header("Authorization", auth_header(&admin_token))
+        .await;
+    res.assert_status(StatusCode::NO_CONTENT);
+
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<Value>()["visibility"], "Public");
+
+    // An exercise with the same name can not be shared again
+    let res = server
+        .post(APP_PATHS.gym_exercises)
+        .add_header("Authorization", auth_header(&other_token))
+        .json(&json!({
+            "name": name.to_uppercase(),
+            "primary_muscles": ["Chest"],
+            "secondary_muscles": [],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let duplicate_id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+    let res = server
+        .post(&APP_PATHS.share_gym_exercise.replace("{id}", &duplicate_id))
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+    assert_eq!(res.json::<Value>()["duplicate_id"], id.as_str());
+
+    // Public exercises stay as they are, an edit does not make them deletable
+    let res = server
+        .put(&path)
+        .add_header("Authorization", auth_header(&owner_token))
+        .json(&json!({"name": format!("{name} v2")}))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+    let res = server
+        .delete(&path)
+        .add_header("Authorization", auth_header(&owner_token))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<Value>()["name"], name.as_str());
+    assert_eq!(res.json::<Value>()["visibility"], "Public");
+}
+
+#[tokio::test]
+async fn test_moderation_food_item() {
+    let owner = TestData::with_base_name("modfowner");
+    let other = TestData::with_base_name("modfother");
+    let admin = TestData::with_base_name("modfadmin");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let (owner_token, _) = create_verified_user(&server, app_test, &owner).await;
+    let (other_token, _) = create_verified_user(&server, app_test, &other).await;
+    let (admin_token, admin_id) = create_verified_user(&server, app_test, &admin).await;
+    app_test
+        .repositories
+        .user_group_repository
+        .create(&admin_id, UserGroup::AdminGroup)
+        .await
+        .unwrap();
+
+    let name = format!("{} Granola", owner.username);
+    let res = server
+        .post(APP_PATHS.food_items)
+        .add_header("Authorization", auth_header(&owner_token))
+        .json(&json!({
+            "name": name,
+            "calories_per100g": 450,
+            "protein_per100g": 10,
+            "carbs_per100g": 60,
+            "fat_per100g": 18,
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+
+    let res = server
+        .get(APP_PATHS.food_items)
+        .add_query_params(json!({"name": name}))
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert!(res.json::<Vec<Value>>().is_empty());
+
+    // Only the creator shares
+    let share_path = APP_PATHS.share_food_item.replace("{id}", &id);
+    let res = server
+        .post(&share_path)
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+    let res = server
+        .post(&share_path)
+        .add_header("Authorization", auth_header(&owner_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let res = server
+        .post(&share_path)
+        .add_header("Authorization", auth_header(&owner_token))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    let res = server
+        .get(APP_PATHS.moderation_food_items)
+        .add_header("Authorization", auth_header(&admin_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert!(
+        res.json::<Vec<Value>>()
+            .iter()
+            .any(|food_item| food_item["id"] == id.as_str())
+    );
+
+    // A rejected food item goes back to private and leaves the queue
+    let res = server
+        .post(&APP_PATHS.reject_food_item.replace("{id}", &id))
+        .add_header("Authorization", auth_header(&admin_token))
+        .await;
+    res.assert_status(StatusCode::NO_CONTENT);
+    let res = server
+        .post(&APP_PATHS.approve_food_item.replace("{id}", &id))
+        .add_header("Authorization", auth_header(&admin_token))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    let res = server
+        .get(APP_PATHS.food_items)
+        .add_query_params(json!({"name": name}))
+        .add_header("Authorization", auth_header(&owner_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let food_items = res.json::<Vec<Value>>();
+    assert_eq!(food_items.len(), 1);
+    assert_eq!(food_items[0]["visibility"], "Private");
+}
+
+#[tokio::test]
+async fn test_food_item_used_by_others_is_kept() {
+    let owner = TestData::with_base_name("usedfowner");
+    let other = TestData::with_base_name("usedfother");
+    let admin = TestData::with_base_name("usedfadmin");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let (owner_token, _) = create_verified_user(&server, app_test, &owner).await;
+    let (_, other_id) = create_verified_user(&server, app_test, &other).await;
+    let (admin_token, admin_id) = create_verified_user(&server, app_test, &admin).await;
+    app_test
+        .repositories
+        .user_group_repository
+        .create(&admin_id, UserGroup::AdminGroup)
+        .await
+        .unwrap();
+
+    let res = server
+        .post(APP_PATHS.food_items)
+        .add_header("Authorization", auth_header(&owner_token))
+        .json(&json!({
+            "name": format!("{} Muesli", owner.username),
+            "calories_per100g": 380,
+            "protein_per100g": 9,
+            "carbs_per100g": 65,
+            "fat_per100g": 7,
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+    let path = APP_PATHS.food_item.replace("{id}", &id);
+
+    let res = server
+        .post(&APP_PATHS.share_food_item.replace("{id}", &id))
+        .add_header("Authorization", auth_header(&owner_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let res = server
+        .post(&APP_PATHS.approve_food_item.replace("{id}", &id))
+        .add_header("Authorization", auth_header(&admin_token))
+        .await;
+    res.assert_status(StatusCode::NO_CONTENT);
+
+    let meal = app_test
+        .repositories
+        .meal_repository
+        .create(
+            other_id,
+            MealTypeEnum::Breakfast,
+            Utc::now().date_naive(),
+            None,
+        )
+        .await
+        .unwrap();
+    let meal_item = app_test
+        .repositories
+        .meal_item_repository
+        .create(meal.id, Uuid::parse_str(&id).unwrap(), 80)
+        .await
+        .unwrap();
+
+    // A public food item can not be edited, so it never goes back to review to be deleted
+    let res = server
+        .put(&path)
+        .add_header("Authorization", auth_header(&owner_token))
+        .json(&json!({"calories_per100g": 1}))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+    let res = server
+        .delete(&path)
+        .add_header("Authorization", auth_header(&owner_token))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    // Even once it is not public anymore, the meals of the other user keep it
+    app_test
+        .repositories
+        .food_item_repository
+        .set_visibility(Uuid::parse_str(&id).unwrap(), VisibilityEnum::Private)
+        .await
+        .unwrap();
+    let res = server
+        .delete(&path)
+        .add_header("Authorization", auth_header(&owner_token))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    let meal_items = app_test
+        .repositories
+        .meal_item_repository
+        .find_by_meal_id(&meal.id)
+        .await
+        .unwrap();
+    assert_eq!(meal_items, vec![meal_item]);
+}
@@ -0,0 +1,115 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server, get_test_server_with_peer},
+};
+use axum::http::{HeaderValue, StatusCode, header};
+use serde_json::json;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use uuid::Uuid;
+
+/// Random address so that previous runs do not count
+fn random_ip() -> Ipv6Addr {
+    Ipv6Addr::from(Uuid::new_v4().as_u128())
+}
+
+fn ip_header(ip: Ipv6Addr) -> HeaderValue {
+    HeaderValue::from_str(&ip.to_string()).unwrap()
+}
+
+#[tokio::test]
+async fn test_login_rate_limited_by_account() {
+    let td = TestData::with_base_name("ratelogin");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let max_requests = app_test.rate_limits.login.per_account.unwrap().max_requests;
+
+    let body = json!({"user": {"email": td.email, "password": "wrongpassword"}});
+
+    for _ in 0..max_requests {
+        let res = server.post(APP_PATHS.login_user).json(&body).await;
+        res.assert_status(StatusCode::UNAUTHORIZED);
+    }
+
+    // The same account written differently is still counted
+    let res = server
+        .post(APP_PATHS.login_user)
+        .json(&json!({"user": {"email": td.email.to_uppercase(), "password": td.password}}))
+        .await;
+    res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = res
+        .header(header::RETRY_AFTER)
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after > 0);
+}
+
+#[tokio::test]
+async fn test_register_guest_rate_limited_by_ip() {
+    let app_test = get_app_state().await;
+    // Requests come through the trusted proxy which sets the client address
+    let proxy = SocketAddr::from((Ipv4Addr::LOCALHOST, 4000));
+    let server = get_test_server_with_peer(app_test.clone(), proxy).await;
+    let max_requests = app_test
+        .rate_limits
+        .register_guest
+        .per_ip
+        .unwrap()
+        .max_requests;
+
+    let ip = ip_header(random_ip());
+    let other_ip = ip_header(random_ip());
+
+    for _ in 0..max_requests {
+        let res = server
+            .post(APP_PATHS.create_guest_user)
+            .add_header("X-Real-IP", ip.clone())
+            .await;
+        res.assert_status_success();
+    }
+
+    let res = server
+        .post(APP_PATHS.create_guest_user)
+        .add_header("X-Real-IP", ip)
+        .await;
+    res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    assert!(res.maybe_header(header::RETRY_AFTER).is_some());
+
+    let res = server
+        .post(APP_PATHS.create_guest_user)
+        .add_header("X-Real-IP", other_ip)
+        .await;
+    res.assert_status_success();
+}
+
+#[tokio::test]
+async fn test_forged_client_ip_from_untrusted_peer() {
+    let app_test = get_app_state().await;
+    let client = SocketAddr::from((random_ip(), 4000));
+    let server = get_test_server_with_peer(app_test.clone(), client).await;
+    let max_requests = app_test
+        .rate_limits
+        .register_guest
+        .per_ip
+        .unwrap()
+        .max_requests;
+
+    // The client is not a trusted proxy, so a new address in the header does not
+    // give it a fresh bucket
+    for _ in 0..max_requests {
+        let res = server
+            .post(APP_PATHS.create_guest_user)
+            .add_header("X-Real-IP", ip_header(random_ip()))
+            .await;
+        res.assert_status_success();
+    }
+
+    let res = server
+        .post(APP_PATHS.create_guest_user)
+        .add_header("X-Real-IP", ip_header(random_ip()))
+        .await;
+    res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+}
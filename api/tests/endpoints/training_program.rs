@@ -0,0 +1,227 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use axum_test::TestServer;
+use dimdim_health_api::{axummain::state::AppState, schemas::auth_schemas::LoginResponse};
+use serde_json::{Value, json};
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+async fn create_verified_user(server: &TestServer, app_test: &AppState, td: &TestData) -> String {
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    login.access_token
+}
+
+async fn create_template(server: &TestServer, token: &str, exercise_name: String) -> String {
+    let res = server
+        .post(APP_PATHS.gym_exercises)
+        .add_header("Authorization", auth_header(token))
+        .json(&json!({
+            "name": exercise_name,
+            "primary_muscles": ["Quadriceps"],
+            "secondary_muscles": [],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let exercise_id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+
+    let res = server
+        .post(APP_PATHS.workout_templates)
+        .add_header("Authorization", auth_header(token))
+        .json(&json!({
+            "name": "Legs",
+            "exercises": [
+                {"exercise_id": exercise_id, "target_sets": 2, "target_repetitions": 5, "target_weight_kg": 100},
+            ],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    res.json::<Value>()["id"].as_str().unwrap().to_string()
+}
+
+async fn today_workout(server: &TestServer, token: &str, date: &str) -> Vec<Value> {
+    let res = server
+        .get(APP_PATHS.today_workout)
+        .add_query_params(json!({"date": date}))
+        .add_header("Authorization", auth_header(token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    res.json::<Vec<Value>>()
+}
+
+fn target_weight(workout: &Value) -> f64 {
+    workout["exercises"][0]["target_weight_kg"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_training_program() {
+    let td = TestData::with_base_name("tprogram");
+    let other = TestData::with_base_name("tprogram2");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let token = create_verified_user(&server, app_test, &td).await;
+    let other_token = create_verified_user(&server, app_test, &other).await;
+
+    let template_id = create_template(&server, &token, format!("{} Squat", td.username)).await;
+    let other_template_id =
+        create_template(&server, &other_token, format!("{} Squat", other.username)).await;
+
+    // Templates must belong to the user and days fit in the program
+    for (template_id, week) in [(&other_template_id, 1), (&template_id, 5)] {
+        let res = server
+            .post(APP_PATHS.training_programs)
+            .add_header("Authorization", auth_header(&token))
+            .json(&json!({
+                "name": "Linear",
+                "start_date": "2024-06-03",
+                "weeks": 4,
+                "days": [{"template_id": template_id, "week": week, "weekday": 1}],
+            }))
+            .await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    // Mondays every week, and an extra Wednesday on the second one
+    let res = server
+        .post(APP_PATHS.training_programs)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "name": "Linear",
+            "start_date": "2024-06-03",
+            "weeks": 4,
+            "increment_kg": 5,
+            "days": [
+                {"template_id": template_id, "weekday": 1},
+                {"template_id": template_id, "week": 2, "weekday": 3},
+            ],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let program = res.json::<Value>();
+    let id = program["id"].as_str().unwrap().to_string();
+    assert_eq!(program["failures_before_deload"], 3);
+    assert_eq!(program["deload_percent"], 10);
+    assert_eq!(program["days"].as_array().unwrap().len(), 2);
+    assert_eq!(program["days"][0]["template_name"], "Legs");
+    let path = APP_PATHS.training_program.replace("{id}", &id);
+
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let workouts = today_workout(&server, &token, "2024-06-03").await;
+    assert_eq!(workouts.len(), 1);
+    assert_eq!(workouts[0]["week"], 1);
+    assert_eq!(workouts[0]["template_id"], template_id.as_str());
+    assert_eq!(workouts[0]["exercises"][0]["target_sets"], 2);
+    assert_eq!(target_weight(&workouts[0]), 100.0);
+
+    // Rest day, and after the end of the program
+    assert!(
+        today_workout(&server, &token, "2024-06-04")
+            .await
+            .is_empty()
+    );
+    assert!(
+        today_workout(&server, &token, "2024-07-01")
+            .await
+            .is_empty()
+    );
+
+    // All the planned sets done, the weight goes up
+    let res = server
+        .post(
+            &APP_PATHS
+                .start_workout_template
+                .replace("{id}", &template_id),
+        )
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"date": "2024-06-03"}))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let workouts = today_workout(&server, &token, "2024-06-05").await;
+    assert!(workouts.is_empty());
+    let workouts = today_workout(&server, &token, "2024-06-12").await;
+    assert_eq!(workouts.len(), 1);
+    assert_eq!(workouts[0]["week"], 2);
+    assert_eq!(target_weight(&workouts[0]), 105.0);
+    assert_eq!(workouts[0]["exercises"][0]["consecutive_failures"], 0);
+
+    // The second week can not be cut off while a day is planned on it
+    let res = server
+        .put(&path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"weeks": 1}))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let res = server
+        .put(&path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "weeks": 1,
+            "days": [{"template_id": template_id, "weekday": 1}],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert!(
+        today_workout(&server, &token, "2024-06-10")
+            .await
+            .is_empty()
+    );
+
+    let res = server
+        .get(APP_PATHS.training_programs)
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let programs = res.json::<Vec<Value>>();
+    assert_eq!(programs.len(), 1);
+    assert_eq!(programs[0]["weeks"], 1);
+
+    let res = server
+        .delete(&path)
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::NO_CONTENT);
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,147 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use chrono::Utc;
+use dimdim_health_api::{
+    auth::totp,
+    schemas::{
+        auth_schemas::LoginResponse,
+        two_factor_schemas::{
+            RecoveryCodesResponse, TwoFactorChallengeResponse, TwoFactorSetupResponse,
+            TwoFactorStatusResponse,
+        },
+    },
+};
+use serde_json::json;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+fn current_code(secret: &str) -> String {
+    totp::code_at(secret, Utc::now().timestamp() as u64).unwrap()
+}
+
+#[tokio::test]
+async fn test_two_factor_login_flow() {
+    let td = TestData::with_base_name("twofactor");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let access_token = res.json::<LoginResponse>().access_token;
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    let res = server
+        .post(APP_PATHS.two_factor_setup)
+        .add_header("Authorization", auth_header(&access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let setup = res.json::<TwoFactorSetupResponse>();
+    assert!(setup.otpauth_uri.contains(&setup.secret));
+
+    let res = server
+        .post(APP_PATHS.two_factor_confirm)
+        .add_header("Authorization", auth_header(&access_token))
+        .json(&json!({"code": "abcdef"}))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let code = current_code(&setup.secret);
+    let res = server
+        .post(APP_PATHS.two_factor_confirm)
+        .add_header("Authorization", auth_header(&access_token))
+        .json(&json!({"code": code}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let recovery_codes = res.json::<RecoveryCodesResponse>().recovery_codes;
+    assert_eq!(recovery_codes.len(), 10);
+
+    // The password alone is not enough anymore
+    let res = server
+        .post(APP_PATHS.login_user)
+        .json(&json!({"user": {"email": td.email, "password": td.password}}))
+        .await;
+    res.assert_status(StatusCode::ACCEPTED);
+    let challenge = res.json::<TwoFactorChallengeResponse>();
+    assert!(challenge.two_factor_required);
+
+    // The code used for the enrollment cannot be replayed
+    let res = server
+        .post(APP_PATHS.two_factor_verify)
+        .json(&json!({"challenge_token": challenge.challenge_token, "code": code}))
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+
+    let res = server
+        .post(APP_PATHS.two_factor_verify)
+        .json(&json!({
+            "challenge_token": challenge.challenge_token,
+            "recovery_code": recovery_codes[0]
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let tokens = res.json::<LoginResponse>();
+
+    // The challenge is single use
+    let res = server
+        .post(APP_PATHS.two_factor_verify)
+        .json(&json!({
+            "challenge_token": challenge.challenge_token,
+            "recovery_code": recovery_codes[1]
+        }))
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+
+    let res = server
+        .get(APP_PATHS.two_factor_status)
+        .add_header("Authorization", auth_header(&tokens.access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let status = res.json::<TwoFactorStatusResponse>();
+    assert!(status.enabled);
+    assert_eq!(status.recovery_codes_left, 9);
+
+    // A used recovery code is refused
+    let res = server
+        .post(APP_PATHS.two_factor_disable)
+        .add_header("Authorization", auth_header(&tokens.access_token))
+        .json(&json!({"password": td.password, "recovery_code": recovery_codes[0]}))
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+
+    let res = server
+        .post(APP_PATHS.two_factor_disable)
+        .add_header("Authorization", auth_header(&tokens.access_token))
+        .json(&json!({"password": td.password, "recovery_code": recovery_codes[1]}))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let res = server
+        .post(APP_PATHS.login_user)
+        .json(&json!({"user": {"email": td.email, "password": td.password}}))
+        .await;
+    res.assert_status(StatusCode::OK);
+}
@@ -0,0 +1,320 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use chrono::{Duration, Utc};
+use dimdim_health_api::schemas::{
+    gym_schemas::CreateGymSessionRequest, user_watch_permission_schemas::WatchScopes,
+};
+use entities::{sea_orm_active_enums::MealTypeEnum, users_ext::SYSTEM_USER_ID};
+use serde_json::json;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn test_grant_watch_permission_with_scopes() {
+    let (watched, watched_token) = TestData::with_base_name("scoped")
+        .create_verified_user_with_token()
+        .await;
+    let (watching, watching_token) = TestData::with_base_name("scopedw")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.grant_watch_permission)
+        .add_header("Authorization", auth_header(&watched_token))
+        .json(&json!({
+            "user_id": watching.id,
+            "scopes": {"meals": false, "gym": false, "body_infos": false}
+        }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+
+    let res = server
+        .get(APP_PATHS.get_watchers)
+        .add_header("Authorization", auth_header(&watched_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let watchers = res.json::<serde_json::Value>();
+    assert_eq!(watchers["watchers"][0]["user_id"], json!(watching.id));
+    assert_eq!(watchers["watchers"][0]["scopes"]["weight"], json!(true));
+    assert_eq!(watchers["watchers"][0]["scopes"]["meals"], json!(false));
+
+    let user_id = watched.id.to_string();
+
+    let res = server
+        .get(&APP_PATHS.other_user_weights.replace("{user_id}", &user_id))
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    for path in [
+        APP_PATHS.other_user_meals,
+        APP_PATHS.other_user_gym_sessions,
+        APP_PATHS.other_user_infos,
+    ] {
+        let res = server
+            .get(&path.replace("{user_id}", &user_id))
+            .add_header("Authorization", auth_header(&watching_token))
+            .await;
+        res.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    // Scopes left out of an update keep their current value
+    let res = server
+        .post(APP_PATHS.update_watch_permission)
+        .add_header("Authorization", auth_header(&watched_token))
+        .json(&json!({
+            "user_id": watching.id,
+            "scopes": {"gym": true}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let permission = res.json::<serde_json::Value>();
+    assert_eq!(permission["scopes"]["weight"], json!(true));
+    assert_eq!(permission["scopes"]["meals"], json!(false));
+    assert_eq!(permission["scopes"]["gym"], json!(true));
+    assert_eq!(permission["scopes"]["body_infos"], json!(false));
+}
+
+#[tokio::test]
+async fn test_view_other_user_meals_and_gym() {
+    let (watched, _) = TestData::with_base_name("mealgym")
+        .create_verified_user_with_token()
+        .await;
+    let (watching, watching_token) = TestData::with_base_name("mealgymw")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let today = Utc::now().date_naive();
+    let meal = app_test
+        .repositories
+        .meal_repository
+        .create(watched.id, MealTypeEnum::Lunch, today, None)
+        .await
+        .unwrap();
+    let session = app_test
+        .repositories
+        .gym_session_repository
+        .create(
+            watched.id,
+            CreateGymSessionRequest {
+                date: today,
+                started_at: None,
+                ended_at: None,
+                notes: None,
+                perceived_difficulty: None,
+                user_weight_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    app_test
+        .repositories
+        .user_watch_permission_repository
+        .create(&watched.id, &watching.id, &WatchScopes::default(), None)
+        .await
+        .unwrap();
+
+    let user_id = watched.id.to_string();
+
+    let res = server
+        .get(&APP_PATHS.other_user_meals.replace("{user_id}", &user_id))
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<serde_json::Value>().as_array().unwrap().len(), 1);
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_meal_items
+                .replace("{user_id}", &user_id)
+                .replace("{meal_id}", &meal.id.to_string()),
+        )
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_gym_sessions
+                .replace("{user_id}", &user_id),
+        )
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<serde_json::Value>().as_array().unwrap().len(), 1);
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_gym_sets
+                .replace("{user_id}", &user_id)
+                .replace("{session_id}", &session.id.to_string()),
+        )
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    // A session of another user is not reachable through the watched user's path
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_gym_sets
+                .replace("{user_id}", &watching.id.to_string())
+                .replace("{session_id}", &session.id.to_string()),
+        )
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+
+    // No body infos filled in yet
+    let res = server
+        .get(&APP_PATHS.other_user_infos.replace("{user_id}", &user_id))
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_expired_watch_permission() {
+    let (watched, watched_token) = TestData::with_base_name("expired")
+        .create_verified_user_with_token()
+        .await;
+    let (watching, watching_token) = TestData::with_base_name("expiredw")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.grant_watch_permission)
+        .add_header("Authorization", auth_header(&watched_token))
+        .json(&json!({
+            "user_id": watching.id,
+            "expires_at": Utc::now() - Duration::days(1)
+        }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    app_test
+        .repositories
+        .user_watch_permission_repository
+        .create(
+            &watched.id,
+            &watching.id,
+            &WatchScopes::default(),
+            Some((Utc::now() - Duration::days(1)).into()),
+        )
+        .await
+        .unwrap();
+
+    let weights_path = APP_PATHS
+        .other_user_weights
+        .replace("{user_id}", &watched.id.to_string());
+
+    let res = server
+        .get(&weights_path)
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let res = server
+        .get(APP_PATHS.get_watching)
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert!(
+        res.json::<serde_json::Value>()["watching"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+
+    let res = server
+        .post(APP_PATHS.update_watch_permission)
+        .add_header("Authorization", auth_header(&watched_token))
+        .json(&json!({
+            "user_id": watching.id,
+            "scopes": {},
+            "expires_at": Utc::now() + Duration::days(7)
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let res = server
+        .get(&weights_path)
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    // Changing only the scopes keeps the permission temporary
+    let res = server
+        .post(APP_PATHS.update_watch_permission)
+        .add_header("Authorization", auth_header(&watched_token))
+        .json(&json!({
+            "user_id": watching.id,
+            "scopes": {"meals": false}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert!(!res.json::<serde_json::Value>()["expires_at"].is_null());
+
+    let res = server
+        .post(APP_PATHS.update_watch_permission)
+        .add_header("Authorization", auth_header(&watched_token))
+        .json(&json!({
+            "user_id": watching.id,
+            "expires_at": null
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let permission = res.json::<serde_json::Value>();
+    assert!(permission["expires_at"].is_null());
+    assert_eq!(permission["scopes"]["meals"], json!(false));
+}
+
+#[tokio::test]
+async fn test_system_user_cannot_be_watched() {
+    let (_, token) = TestData::with_base_name("sysuser")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let found = app_test
+        .repositories
+        .user_repository
+        .search_by_username("DimDim")
+        .await
+        .unwrap();
+    assert!(found.iter().all(|user| user.id != SYSTEM_USER_ID));
+
+    let res = server
+        .post(APP_PATHS.send_watch_request)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"user_id": SYSTEM_USER_ID}))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+
+    let res = server
+        .post(APP_PATHS.grant_watch_permission)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"user_id": SYSTEM_USER_ID}))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,154 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use chrono::Utc;
+use dimdim_health_api::schemas::user_watch_permission_schemas::WatchScopes;
+use sea_orm::prelude::Decimal;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn test_view_other_user_weights_allowed() {
+    let (watched, _) = TestData::with_base_name("watched")
+        .create_verified_user_with_token()
+        .await;
+    let (watching, watching_token) = TestData::with_base_name("watcher")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    app_test
+        .repositories
+        .user_weight_repository
+        .create(watched.id, Decimal::new(7550, 2), Utc::now().date_naive())
+        .await
+        .unwrap();
+    app_test
+        .repositories
+        .user_watch_permission_repository
+        .create(&watched.id, &watching.id, &WatchScopes::default(), None)
+        .await
+        .unwrap();
+
+    let user_id = watched.id.to_string();
+
+    let res = server
+        .get(&APP_PATHS.other_user_weights.replace("{user_id}", &user_id))
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let weights = res.json::<serde_json::Value>();
+    assert_eq!(weights.as_array().unwrap().len(), 1);
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_last_weight
+                .replace("{user_id}", &user_id),
+        )
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_weight_infos
+                .replace("{user_id}", &user_id),
+        )
+        .add_header("Authorization", auth_header(&watching_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_view_other_user_weights_forbidden() {
+    let (watched, _) = TestData::with_base_name("watchedfb")
+        .create_verified_user_with_token()
+        .await;
+    let (_, stranger_token) = TestData::with_base_name("stranger")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let user_id = watched.id.to_string();
+
+    for path in [
+        APP_PATHS.other_user_weights,
+        APP_PATHS.other_user_last_weight,
+        APP_PATHS.other_user_weight_infos,
+    ] {
+        let res = server
+            .get(&path.replace("{user_id}", &user_id))
+            .add_header("Authorization", auth_header(&stranger_token))
+            .await;
+        res.assert_status(StatusCode::FORBIDDEN);
+    }
+}
+
+#[tokio::test]
+async fn test_view_other_user_weights_self() {
+    let (user, access_token) = TestData::with_base_name("watchself")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let user_id = user.id.to_string();
+
+    let res = server
+        .get(&APP_PATHS.other_user_weights.replace("{user_id}", &user_id))
+        .add_header("Authorization", auth_header(&access_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    // No weight recorded yet
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_last_weight
+                .replace("{user_id}", &user_id),
+        )
+        .add_header("Authorization", auth_header(&access_token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_view_other_user_weights_unauthenticated_or_bad_id() {
+    let (_, access_token) = TestData::with_base_name("watchbad")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_weights
+                .replace("{user_id}", "not-a-uuid"),
+        )
+        .add_header("Authorization", auth_header(&access_token))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_weights
+                .replace("{user_id}", &uuid::Uuid::new_v4().to_string()),
+        )
+        .await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,181 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use serde_json::json;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn test_watch_request_accept() {
+    let (target, target_token) = TestData::with_base_name("reqtarget")
+        .create_verified_user_with_token()
+        .await;
+    let (requester, requester_token) = TestData::with_base_name("reqcoach")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.send_watch_request)
+        .add_header("Authorization", auth_header(&requester_token))
+        .json(&json!({"user_id": target.id, "message": "Your coach here"}))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let request_id = res.json::<serde_json::Value>()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let res = server
+        .get(APP_PATHS.incoming_watch_requests)
+        .add_header("Authorization", auth_header(&target_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let incoming = res.json::<serde_json::Value>();
+    assert_eq!(incoming["requests"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        incoming["requests"][0]["username"],
+        json!(requester.username)
+    );
+    assert_eq!(incoming["requests"][0]["message"], json!("Your coach here"));
+
+    // Only the target can answer
+    let accept_path = APP_PATHS.accept_watch_request.replace("{id}", &request_id);
+    let res = server
+        .post(&accept_path)
+        .add_header("Authorization", auth_header(&requester_token))
+        .json(&json!({}))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let res = server
+        .post(&accept_path)
+        .add_header("Authorization", auth_header(&target_token))
+        .json(&json!({"scopes": {"gym": false}}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<serde_json::Value>()["status"], json!("Accepted"));
+
+    let res = server
+        .post(&accept_path)
+        .add_header("Authorization", auth_header(&target_token))
+        .json(&json!({}))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    let user_id = target.id.to_string();
+    let res = server
+        .get(&APP_PATHS.other_user_weights.replace("{user_id}", &user_id))
+        .add_header("Authorization", auth_header(&requester_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_gym_sessions
+                .replace("{user_id}", &user_id),
+        )
+        .add_header("Authorization", auth_header(&requester_token))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    // Already watching, a new request is useless
+    let res = server
+        .post(APP_PATHS.send_watch_request)
+        .add_header("Authorization", auth_header(&requester_token))
+        .json(&json!({"user_id": target.id}))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    let res = server
+        .get(APP_PATHS.outgoing_watch_requests)
+        .add_header("Authorization", auth_header(&requester_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let outgoing = res.json::<serde_json::Value>();
+    assert_eq!(outgoing["requests"][0]["user_id"], json!(target.id));
+    assert_eq!(outgoing["requests"][0]["status"], json!("Accepted"));
+}
+
+#[tokio::test]
+async fn test_watch_request_decline() {
+    let (target, target_token) = TestData::with_base_name("decltarget")
+        .create_verified_user_with_token()
+        .await;
+    let (requester, requester_token) = TestData::with_base_name("declcoach")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+
+    let res = server
+        .post(APP_PATHS.send_watch_request)
+        .add_header("Authorization", auth_header(&requester_token))
+        .json(&json!({"user_id": requester.id}))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let res = server
+        .post(APP_PATHS.send_watch_request)
+        .add_header("Authorization", auth_header(&requester_token))
+        .json(&json!({"user_id": target.id}))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let request_id = res.json::<serde_json::Value>()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let res = server
+        .post(APP_PATHS.send_watch_request)
+        .add_header("Authorization", auth_header(&requester_token))
+        .json(&json!({"user_id": target.id}))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    let res = server
+        .post(&APP_PATHS.decline_watch_request.replace("{id}", &request_id))
+        .add_header("Authorization", auth_header(&target_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<serde_json::Value>()["status"], json!("Declined"));
+
+    let res = server
+        .get(APP_PATHS.incoming_watch_requests)
+        .add_header("Authorization", auth_header(&target_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert!(
+        res.json::<serde_json::Value>()["requests"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+
+    let res = server
+        .get(
+            &APP_PATHS
+                .other_user_weights
+                .replace("{user_id}", &target.id.to_string()),
+        )
+        .add_header("Authorization", auth_header(&requester_token))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    // A declined request can be sent again
+    let res = server
+        .post(APP_PATHS.send_watch_request)
+        .add_header("Authorization", auth_header(&requester_token))
+        .json(&json!({"user_id": target.id}))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+}
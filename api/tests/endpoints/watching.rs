@@ -0,0 +1,165 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use chrono::Duration;
+use dimdim_health_api::{
+    schemas::{
+        food_item_schemas::CreateFoodItemRequest, gym_schemas::CreateGymSessionRequest,
+        user_watch_permission_schemas::WatchScopes,
+    },
+    utils::get_now_time_paris::now_paris_fixed,
+};
+use entities::sea_orm_active_enums::MealTypeEnum;
+use sea_orm::prelude::Decimal;
+use serde_json::json;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn test_watching_overview() {
+    let (watched, _) = TestData::with_base_name("overview")
+        .create_verified_user_with_token()
+        .await;
+    let (no_weight, _) = TestData::with_base_name("overviewnw")
+        .create_verified_user_with_token()
+        .await;
+    let (coach, coach_token) = TestData::with_base_name("overviewc")
+        .create_verified_user_with_token()
+        .await;
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let repositories = &app_test.repositories;
+
+    let today = now_paris_fixed(Duration::zero()).date_naive();
+    let yesterday = today - Duration::days(1);
+
+    for (user_id, kg, day) in [
+        (watched.id, 8200, today - Duration::days(10)),
+        (watched.id, 8000, today - Duration::days(6)),
+        (watched.id, 7950, today),
+        (no_weight.id, 6000, today),
+    ] {
+        repositories
+            .user_weight_repository
+            .create(user_id, Decimal::new(kg, 2), day)
+            .await
+            .unwrap();
+    }
+
+    repositories
+        .gym_session_repository
+        .create(
+            watched.id,
+            CreateGymSessionRequest {
+                date: today - Duration::days(2),
+                started_at: None,
+                ended_at: None,
+                notes: None,
+                perceived_difficulty: None,
+                user_weight_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    repositories
+        .gym_session_repository
+        .create(
+            watched.id,
+            CreateGymSessionRequest {
+                date: yesterday,
+                started_at: None,
+                ended_at: None,
+                notes: None,
+                perceived_difficulty: None,
+                user_weight_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let food_item = repositories
+        .food_item_repository
+        .create(
+            CreateFoodItemRequest {
+                name: format!("Overview rice {}", uuid::Uuid::new_v4()),
+                description: None,
+                scan_code: None,
+                calories_per100g: 130,
+                protein_per100g: 3,
+                carbs_per100g: 28,
+                fat_per100g: 0,
+            },
+            watched.id,
+        )
+        .await
+        .unwrap();
+    for date in [yesterday, today] {
+        let meal = repositories
+            .meal_repository
+            .create(watched.id, MealTypeEnum::Lunch, date, None)
+            .await
+            .unwrap();
+        repositories
+            .meal_item_repository
+            .create(meal.id, food_item.id, 200)
+            .await
+            .unwrap();
+    }
+
+    repositories
+        .user_watch_permission_repository
+        .create(&watched.id, &coach.id, &WatchScopes::default(), None)
+        .await
+        .unwrap();
+    repositories
+        .user_watch_permission_repository
+        .create(
+            &no_weight.id,
+            &coach.id,
+            &WatchScopes {
+                weight: false,
+                ..WatchScopes::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let res = server
+        .get(APP_PATHS.watching_overview)
+        .add_header("Authorization", auth_header(&coach_token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let overview = res.json::<serde_json::Value>();
+    let users = overview["users"].as_array().unwrap();
+    assert_eq!(users.len(), 2);
+
+    let watched_overview = users
+        .iter()
+        .find(|u| u["user_id"] == json!(watched.id))
+        .unwrap();
+    assert_eq!(
+        watched_overview["last_weight"]["weight_in_kg"],
+        json!("79.50")
+    );
+    assert_eq!(watched_overview["weight_trend_7_days"], json!("-0.50"));
+    assert_eq!(
+        watched_overview["last_gym_session_date"],
+        json!(yesterday.to_string())
+    );
+    assert_eq!(watched_overview["yesterday_calories"], json!(260));
+
+    let no_weight_overview = users
+        .iter()
+        .find(|u| u["user_id"] == json!(no_weight.id))
+        .unwrap();
+    assert!(no_weight_overview["last_weight"].is_null());
+    assert!(no_weight_overview["weight_trend_7_days"].is_null());
+    assert!(no_weight_overview["yesterday_calories"].is_null());
+}
@@ -0,0 +1,225 @@
+use crate::helpers::{
+    app_paths::APP_PATHS,
+    test_data::TestData,
+    test_server::{get_app_state, get_test_server},
+};
+use axum::http::{HeaderValue, StatusCode};
+use axum_test::TestServer;
+use dimdim_health_api::{axummain::state::AppState, schemas::auth_schemas::LoginResponse};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+fn auth_header(access_token: &str) -> HeaderValue {
+    HeaderValue::from_str(format!("Token {}", access_token).as_str()).unwrap()
+}
+
+async fn create_verified_user(server: &TestServer, app_test: &AppState, td: &TestData) -> String {
+    let res = server
+        .post(APP_PATHS.create_user)
+        .json(&json!({
+            "user": {"username": td.username, "email": td.email, "password": td.password}
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let login = res.json::<LoginResponse>();
+
+    let user = app_test
+        .repositories
+        .user_repository
+        .find_by_email(&td.email)
+        .await
+        .unwrap()
+        .unwrap();
+    app_test
+        .repositories
+        .email_verification_repository
+        .verify_user_email(&user.id)
+        .await
+        .unwrap();
+
+    login.access_token
+}
+
+async fn create_exercise(server: &TestServer, token: &str, name: String) -> String {
+    let res = server
+        .post(APP_PATHS.gym_exercises)
+        .add_header("Authorization", auth_header(token))
+        .json(&json!({
+            "name": name,
+            "primary_muscles": ["Chest"],
+            "secondary_muscles": [],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    res.json::<Value>()["id"].as_str().unwrap().to_string()
+}
+
+fn planned(sets: &[Value]) -> Vec<(String, i64, i64, f64)> {
+    sets.iter()
+        .map(|set| {
+            (
+                set["exercise_id"].as_str().unwrap().to_string(),
+                set["set_number"].as_i64().unwrap(),
+                set["repetitions"].as_i64().unwrap(),
+                set["weight_kg"].as_str().unwrap().parse().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_workout_template() {
+    let td = TestData::with_base_name("wtemplate");
+    let other = TestData::with_base_name("wtemplate2");
+
+    let app_test = get_app_state().await;
+    let server = get_test_server(app_test.clone()).await;
+    let token = create_verified_user(&server, app_test, &td).await;
+    let other_token = create_verified_user(&server, app_test, &other).await;
+
+    let bench = create_exercise(&server, &token, format!("{} Bench", td.username)).await;
+    let fly = create_exercise(&server, &token, format!("{} Fly", td.username)).await;
+    let hidden = create_exercise(&server, &other_token, format!("{} Dip", other.username)).await;
+
+    // Exercises must exist and be visible
+    for exercise_id in [hidden, Uuid::new_v4().to_string()] {
+        let res = server
+            .post(APP_PATHS.workout_templates)
+            .add_header("Authorization", auth_header(&token))
+            .json(&json!({
+                "name": "Push",
+                "exercises": [
+                    {"exercise_id": exercise_id, "target_sets": 3, "target_repetitions": 10},
+                ],
+            }))
+            .await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+    }
+    let res = server
+        .post(APP_PATHS.workout_templates)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"name": "Push", "exercises": []}))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let res = server
+        .post(APP_PATHS.workout_templates)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "name": "Push",
+            "exercises": [
+                {"exercise_id": fly, "target_sets": 1, "target_repetitions": 12},
+                {"exercise_id": bench, "target_sets": 2, "target_repetitions": 8, "target_weight_kg": 60},
+            ],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let template = res.json::<Value>();
+    let id = template["id"].as_str().unwrap().to_string();
+    assert_eq!(template["exercises"][0]["exercise_id"], fly.as_str());
+    assert_eq!(template["exercises"][1]["position"], 2);
+    assert_eq!(
+        template["exercises"][1]["exercise_name"],
+        format!("{} Bench", td.username)
+    );
+    let path = APP_PATHS.workout_template.replace("{id}", &id);
+
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&other_token))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    // Exercises are replaced in the new order
+    let res = server
+        .put(&path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({
+            "description": "Chest day",
+            "exercises": [
+                {"exercise_id": bench, "target_sets": 2, "target_repetitions": 8, "target_weight_kg": 60},
+                {"exercise_id": fly, "target_sets": 1, "target_repetitions": 12},
+            ],
+        }))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let template = res.json::<Value>();
+    assert_eq!(template["name"], "Push");
+    assert_eq!(template["description"], "Chest day");
+    assert_eq!(template["exercises"][0]["exercise_id"], bench.as_str());
+
+    let res = server
+        .get(APP_PATHS.workout_templates)
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let templates = res.json::<Vec<Value>>();
+    assert_eq!(templates.len(), 1);
+    assert_eq!(templates[0]["exercises"].as_array().unwrap().len(), 2);
+
+    let start_path = APP_PATHS.start_workout_template.replace("{id}", &id);
+    let res = server
+        .post(&start_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"date": "2024-05-06"}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let started = res.json::<Value>();
+    assert_eq!(started["session"]["date"], "2024-05-06");
+    let sets = started["sets"].as_array().unwrap();
+    assert_eq!(
+        planned(sets),
+        vec![
+            (bench.clone(), 1, 8, 60.0),
+            (bench.clone(), 2, 8, 60.0),
+            (fly.clone(), 1, 12, 0.0),
+        ]
+    );
+
+    // What was really done is planned the next time
+    let session_id = started["session"]["id"].as_str().unwrap();
+    let res = server
+        .put(
+            &APP_PATHS
+                .gym_set
+                .replace("{session_id}", session_id)
+                .replace("{set_id}", sets[1]["id"].as_str().unwrap()),
+        )
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"repetitions": 6, "weight_kg": 65}))
+        .await;
+    res.assert_status(StatusCode::OK);
+
+    let res = server
+        .post(&start_path)
+        .add_header("Authorization", auth_header(&token))
+        .json(&json!({"date": "2024-05-13", "prefill_from_last": true}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(
+        planned(res.json::<Value>()["sets"].as_array().unwrap()),
+        vec![
+            (bench.clone(), 1, 8, 60.0),
+            (bench.clone(), 2, 6, 65.0),
+            (fly.clone(), 1, 12, 0.0),
+        ]
+    );
+
+    let res = server
+        .post(&start_path)
+        .add_header("Authorization", auth_header(&other_token))
+        .json(&json!({"date": "2024-05-13"}))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let res = server
+        .delete(&path)
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::NO_CONTENT);
+    let res = server
+        .get(&path)
+        .add_header("Authorization", auth_header(&token))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+}
@@ -4,23 +4,151 @@ pub struct TestAppPaths {
     // auth
     pub create_user: &'static str,
     pub create_guest_user: &'static str,
+    pub upgrade_guest_user: &'static str,
     pub current_user: &'static str,
+    pub account_deletion: &'static str,
+    pub data_export: &'static str,
+    pub download_data_export: &'static str,
+    pub data_imports: &'static str,
+    pub data_import: &'static str,
+    pub apply_data_import: &'static str,
     pub login_user: &'static str,
+    pub refresh_token: &'static str,
+    pub verify_email: &'static str,
+    pub magic_link: &'static str,
+    pub verify_magic_link: &'static str,
+    // two-factor authentication
+    pub two_factor_status: &'static str,
+    pub two_factor_setup: &'static str,
+    pub two_factor_confirm: &'static str,
+    pub two_factor_verify: &'static str,
+    pub two_factor_disable: &'static str,
     // user groups
     pub join_public_group: &'static str,
     pub leave_public_group: &'static str,
     pub get_user_groups: &'static str,
     pub get_public_group_members: &'static str,
+    // view other user's data ({user_id} must be replaced)
+    pub other_user_weights: &'static str,
+    pub other_user_last_weight: &'static str,
+    pub other_user_weight_infos: &'static str,
+    pub other_user_meals: &'static str,
+    pub other_user_meal_items: &'static str,
+    pub other_user_gym_sessions: &'static str,
+    pub other_user_gym_sets: &'static str,
+    pub other_user_infos: &'static str,
+    // watch permissions
+    pub get_watchers: &'static str,
+    pub get_watching: &'static str,
+    pub grant_watch_permission: &'static str,
+    pub update_watch_permission: &'static str,
+    // watch requests ({id} must be replaced)
+    pub send_watch_request: &'static str,
+    pub incoming_watch_requests: &'static str,
+    pub outgoing_watch_requests: &'static str,
+    pub accept_watch_request: &'static str,
+    pub decline_watch_request: &'static str,
+    // watching dashboard
+    pub watching_overview: &'static str,
+    // gym ({id} must be replaced)
+    pub gym_exercises: &'static str,
+    pub gym_exercise: &'static str,
+    pub share_gym_exercise: &'static str,
+    pub gym_exercise_records: &'static str,
+    pub gym_exercise_record_history: &'static str,
+    pub gym_exercise_history: &'static str,
+    pub gym_volume: &'static str,
+    pub gym_sessions: &'static str,
+    pub gym_session: &'static str,
+    pub gym_session_details: &'static str,
+    pub gym_sets: &'static str,
+    pub gym_sets_bulk: &'static str,
+    pub gym_set: &'static str,
+    pub workout_templates: &'static str,
+    pub workout_template: &'static str,
+    pub start_workout_template: &'static str,
+    pub training_programs: &'static str,
+    pub training_program: &'static str,
+    pub today_workout: &'static str,
+    pub food_items: &'static str,
+    pub food_item: &'static str,
+    pub share_food_item: &'static str,
+    pub moderation_exercises: &'static str,
+    pub approve_exercise: &'static str,
+    pub moderation_food_items: &'static str,
+    pub approve_food_item: &'static str,
+    pub reject_food_item: &'static str,
 }
 
 pub const APP_PATHS: TestAppPaths = TestAppPaths {
     health_check: "/health",
     create_user: "/api/users",
     create_guest_user: "/api/users/guest",
+    upgrade_guest_user: "/api/users/guest/upgrade",
     current_user: "/api/user",
+    account_deletion: "/api/user/deletion",
+    data_export: "/api/user/export",
+    download_data_export: "/api/user/export/{token}",
+    data_imports: "/api/imports",
+    data_import: "/api/imports/{id}",
+    apply_data_import: "/api/imports/{id}/apply",
     login_user: "/api/users/login",
+    refresh_token: "/api/auth/refresh-token",
+    verify_email: "/api/auth/verify-email",
+    magic_link: "/api/auth/magic-link",
+    verify_magic_link: "/api/auth/magic-link/verify",
+    two_factor_status: "/api/auth/2fa",
+    two_factor_setup: "/api/auth/2fa/setup",
+    two_factor_confirm: "/api/auth/2fa/confirm",
+    two_factor_verify: "/api/auth/2fa/verify",
+    two_factor_disable: "/api/auth/2fa/disable",
     join_public_group: "/api/user-groups/join-public",
     leave_public_group: "/api/user-groups/leave-public",
     get_user_groups: "/api/user-groups/myself",
     get_public_group_members: "/api/user-groups/public/members",
+    other_user_weights: "/api/users/{user_id}/weights",
+    other_user_last_weight: "/api/users/{user_id}/weights/last",
+    other_user_weight_infos: "/api/users/{user_id}/weights/infos",
+    other_user_meals: "/api/users/{user_id}/meals",
+    other_user_meal_items: "/api/users/{user_id}/meals/{meal_id}/items",
+    other_user_gym_sessions: "/api/users/{user_id}/gym/sessions",
+    other_user_gym_sets: "/api/users/{user_id}/gym/sessions/{session_id}/sets",
+    other_user_infos: "/api/users/{user_id}/infos",
+    get_watchers: "/api/watch-permissions/watchers",
+    get_watching: "/api/watch-permissions/watching",
+    grant_watch_permission: "/api/watch-permissions/grant",
+    update_watch_permission: "/api/watch-permissions/update",
+    send_watch_request: "/api/watch-requests",
+    incoming_watch_requests: "/api/watch-requests/incoming",
+    outgoing_watch_requests: "/api/watch-requests/outgoing",
+    accept_watch_request: "/api/watch-requests/{id}/accept",
+    decline_watch_request: "/api/watch-requests/{id}/decline",
+    watching_overview: "/api/watching/overview",
+    gym_exercises: "/api/gym/exercises",
+    gym_exercise: "/api/gym/exercises/{id}",
+    share_gym_exercise: "/api/gym/exercises/{id}/share",
+    gym_exercise_records: "/api/gym/exercises/{id}/records",
+    gym_exercise_record_history: "/api/gym/exercises/{id}/records/history",
+    gym_exercise_history: "/api/gym/exercises/{id}/history",
+    gym_volume: "/api/gym/volume",
+    gym_sessions: "/api/gym/sessions",
+    gym_session: "/api/gym/sessions/{id}",
+    gym_session_details: "/api/gym/sessions/{id}/details",
+    gym_sets: "/api/gym/sessions/{session_id}/sets",
+    gym_sets_bulk: "/api/gym/sessions/{session_id}/sets/bulk",
+    gym_set: "/api/gym/sessions/{session_id}/sets/{set_id}",
+    workout_templates: "/api/gym/templates",
+    workout_template: "/api/gym/templates/{id}",
+    start_workout_template: "/api/gym/templates/{id}/start",
+    training_programs: "/api/gym/programs",
+    training_program: "/api/gym/programs/{id}",
+    today_workout: "/api/gym/programs/today",
+    food_items: "/api/food-items",
+    food_item: "/api/food-items/{id}",
+    share_food_item: "/api/food-items/{id}/share",
+    moderation_exercises: "/api/moderation/exercises",
+    approve_exercise: "/api/moderation/exercises/{id}/approve",
+    moderation_food_items: "/api/moderation/food-items",
+    approve_food_item: "/api/moderation/food-items/{id}/approve",
+    reject_food_item: "/api/moderation/food-items/{id}/reject",
 };
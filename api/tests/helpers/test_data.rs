@@ -114,6 +114,23 @@ impl TestData {
             .expect("Failed to generate JWT token");
         (user, token)
     }
+
+    /// Create a user with a verified email in the database and generate a JWT token for them.
+    /// Returns the user model and the access token.
+    #[allow(dead_code)]
+    pub async fn create_verified_user_with_token(&self) -> (entities::users::Model, String) {
+        let user = self.create_user_in_db().await;
+        let app_state = get_app_state().await;
+        let user = app_state
+            .repositories
+            .email_verification_repository
+            .verify_user_email(&user.id)
+            .await
+            .unwrap();
+        let token = dimdim_health_api::auth::jwt::generate_token(&user.id, &app_state.jwt_secret)
+            .expect("Failed to generate JWT token");
+        (user, token)
+    }
 }
 
 impl Default for TestData {
@@ -0,0 +1,31 @@
+use sea_orm::{
+    ColumnTrait, Condition, EntityTrait, QueryFilter, QuerySelect, QueryTrait, prelude::Uuid,
+};
+
+use crate::{
+    food_item::{Column, Entity, Model},
+    meal, meal_item,
+    sea_orm_active_enums::VisibilityEnum,
+};
+
+impl Model {
+    /// Until a moderator approves it, a food item is only listed for whoever added it
+    pub fn is_visible_to(&self, user_id: &Uuid) -> bool {
+        self.visibility == VisibilityEnum::Public || self.added_by == *user_id
+    }
+}
+
+impl Entity {
+    /// Food items in the meals of anyone but `user_ids`, deleting them would delete
+    /// those meal items too
+    pub fn used_by_others(user_ids: Vec<Uuid>) -> Condition {
+        let meal_items = meal_item::Entity::find()
+            .select_only()
+            .column(meal_item::Column::FoodItemId)
+            .inner_join(meal::Entity)
+            .filter(meal::Column::UserId.is_not_in(user_ids))
+            .into_query();
+
+        Condition::all().add(Column::Id.in_subquery(meal_items))
+    }
+}
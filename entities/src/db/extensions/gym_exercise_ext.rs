@@ -0,0 +1,41 @@
+use sea_orm::{
+    ColumnTrait, Condition, EntityTrait, QueryFilter, QuerySelect, QueryTrait, prelude::Uuid,
+};
+
+use crate::{
+    gym_exercise::{Column, Entity, Model},
+    gym_session, gym_set,
+    sea_orm_active_enums::VisibilityEnum,
+    workout_template, workout_template_exercise,
+};
+
+impl Model {
+    /// Public exercises, including the catalog, are seen by everyone, others by their
+    /// creator only
+    pub fn is_visible_to(&self, user_id: &Uuid) -> bool {
+        self.visibility == VisibilityEnum::Public || self.added_by == *user_id
+    }
+}
+
+impl Entity {
+    /// Exercises in the sets or the templates of anyone but `user_ids`, deleting them
+    /// would delete those rows too
+    pub fn used_by_others(user_ids: Vec<Uuid>) -> Condition {
+        let sets = gym_set::Entity::find()
+            .select_only()
+            .column(gym_set::Column::ExerciseId)
+            .inner_join(gym_session::Entity)
+            .filter(gym_session::Column::UserId.is_not_in(user_ids.clone()))
+            .into_query();
+        let templates = workout_template_exercise::Entity::find()
+            .select_only()
+            .column(workout_template_exercise::Column::ExerciseId)
+            .inner_join(workout_template::Entity)
+            .filter(workout_template::Column::UserId.is_not_in(user_ids))
+            .into_query();
+
+        Condition::any()
+            .add(Column::Id.in_subquery(sets))
+            .add(Column::Id.in_subquery(templates))
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use sea_orm::{
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, Iterable, QueryFilter, QueryOrder,
+    prelude::{Decimal, Uuid},
+};
+
+use crate::{
+    gym_personal_record::{ActiveModel, Column, Entity},
+    gym_session, gym_set,
+    sea_orm_active_enums::{OneRepMaxFormulaEnum, PersonalRecordKindEnum},
+};
+
+/// A record beaten by a set, or by a whole session for the volume
+#[derive(Debug)]
+struct NewRecord {
+    kind: PersonalRecordKindEnum,
+    formula: Option<OneRepMaxFormulaEnum>,
+    value: Decimal,
+    previous_value: Option<Decimal>,
+    session_id: Uuid,
+    set: Option<(Uuid, Decimal, i32)>,
+    achieved_on: NaiveDate,
+}
+
+impl NewRecord {
+    fn for_set(
+        kind: PersonalRecordKindEnum,
+        value: Decimal,
+        previous_value: Option<Decimal>,
+        achieved_on: NaiveDate,
+        set: &gym_set::Model,
+    ) -> Self {
+        Self {
+            kind,
+            formula: None,
+            value,
+            previous_value,
+            session_id: set.session_id,
+            set: Some((set.id, set.weight_kg, set.repetitions)),
+            achieved_on,
+        }
+    }
+}
+
+fn beats(best: Option<Decimal>, value: Decimal) -> bool {
+    best.is_none_or(|best| value > best)
+}
+
+/// Every record in the order they were beaten, `sets` being sorted chronologically and
+/// grouped by session. Ties do not count, the first to reach a value keeps the record.
+fn record_history(sets: &[(NaiveDate, gym_set::Model)]) -> Vec<NewRecord> {
+    let mut records = Vec::new();
+    let mut heaviest_weight: Option<Decimal> = None;
+    let mut best_one_rep_max: Vec<(OneRepMaxFormulaEnum, Option<Decimal>)> =
+        OneRepMaxFormulaEnum::iter()
+            .map(|formula| (formula, None))
+            .collect();
+    let mut most_reps: BTreeMap<Decimal, i32> = BTreeMap::new();
+    let mut best_session_volume: Option<Decimal> = None;
+
+    for session in sets.chunk_by(|(_, a), (_, b)| a.session_id == b.session_id) {
+        let achieved_on = session[0].0;
+
+        for (_, set) in session.iter().filter(|(_, set)| set.is_working_set()) {
+            // Bodyweight sets only compete on repetitions
+            if set.weight_kg > Decimal::ZERO {
+                if beats(heaviest_weight, set.weight_kg) {
+                    records.push(NewRecord::for_set(
+                        PersonalRecordKindEnum::HeaviestWeight,
+                        set.weight_kg,
+                        heaviest_weight,
+                        achieved_on,
+                        set,
+                    ));
+                    heaviest_weight = Some(set.weight_kg);
+                }
+
+                for (formula, best) in &mut best_one_rep_max {
+                    if let Some(estimate) = set.estimated_one_rep_max(formula)
+                        && beats(*best, estimate)
+                    {
+                        records.push(NewRecord {
+                            formula: Some(formula.clone()),
+                            ..NewRecord::for_set(
+                                PersonalRecordKindEnum::BestOneRepMax,
+                                estimate,
+                                *best,
+                                achieved_on,
+                                set,
+                            )
+                        });
+                        *best = Some(estimate);
+                    }
+                }
+            }
+
+            let best_reps = most_reps.get(&set.weight_kg).copied();
+            if best_reps.is_none_or(|best| set.repetitions > best) {
+                records.push(NewRecord::for_set(
+                    PersonalRecordKindEnum::MostReps,
+                    Decimal::from(set.repetitions),
+                    best_reps.map(Decimal::from),
+                    achieved_on,
+                    set,
+                ));
+                most_reps.insert(set.weight_kg, set.repetitions);
+            }
+        }
+
+        let volume: Decimal = session
+            .iter()
+            .filter(|(_, set)| set.is_working_set())
+            .map(|(_, set)| set.volume())
+            .sum();
+        if volume > Decimal::ZERO && beats(best_session_volume, volume) {
+            records.push(NewRecord {
+                kind: PersonalRecordKindEnum::BestSessionVolume,
+                formula: None,
+                value: volume,
+                previous_value: best_session_volume,
+                session_id: session[0].1.session_id,
+                set: None,
+                achieved_on,
+            });
+            best_session_volume = Some(volume);
+        }
+    }
+
+    records
+}
+
+impl Entity {
+    /// Rebuilds the record history of a user for an exercise from all of its sets.
+    /// To be called in the same transaction as any change to those sets.
+    pub async fn recompute<C: ConnectionTrait>(
+        db: &C,
+        user_id: Uuid,
+        exercise_id: Uuid,
+    ) -> Result<(), DbErr> {
+        let sets: Vec<(NaiveDate, gym_set::Model)> = gym_set::Entity::find()
+            .find_also_related(gym_session::Entity)
+            .filter(gym_session::Column::UserId.eq(user_id))
+            .filter(gym_set::Column::ExerciseId.eq(exercise_id))
+            .order_by_asc(gym_session::Column::Date)
+            .order_by_asc(gym_session::Column::CreatedAt)
+            .order_by_asc(gym_set::Column::SessionId)
+            .order_by_asc(gym_set::Column::SetNumber)
+            .order_by_asc(gym_set::Column::CreatedAt)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|(set, session)| session.map(|session| (session.date, set)))
+            .collect();
+
+        Entity::delete_many()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ExerciseId.eq(exercise_id))
+            .exec(db)
+            .await?;
+
+        let records: Vec<ActiveModel> = record_history(&sets)
+            .into_iter()
+            .map(|record| ActiveModel {
+                id: NotSet,
+                user_id: Set(user_id),
+                exercise_id: Set(exercise_id),
+                session_id: Set(record.session_id),
+                set_id: Set(record.set.map(|(id, _, _)| id)),
+                kind: Set(record.kind),
+                formula: Set(record.formula),
+                value: Set(record.value),
+                previous_value: Set(record.previous_value),
+                weight_kg: Set(record.set.map(|(_, weight_kg, _)| weight_kg)),
+                repetitions: Set(record.set.map(|(_, _, repetitions)| repetitions)),
+                achieved_on: Set(record.achieved_on),
+                created_at: NotSet,
+            })
+            .collect();
+
+        if !records.is_empty() {
+            Entity::insert_many(records).exec(db).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sea_orm_active_enums::SetTypeEnum;
+    use chrono::Utc;
+
+    fn set(session_id: Uuid, set_number: i32, repetitions: i32, weight_kg: i64) -> gym_set::Model {
+        let now = Utc::now().fixed_offset();
+        gym_set::Model {
+            id: Uuid::new_v4(),
+            session_id,
+            exercise_id: Uuid::nil(),
+            set_number,
+            repetitions,
+            weight_kg: Decimal::from(weight_kg),
+            set_type: SetTypeEnum::Normal,
+            rpe: None,
+            rir: None,
+            rest_seconds: None,
+            duration_seconds: None,
+            distance_meters: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn values(records: &[NewRecord], kind: PersonalRecordKindEnum) -> Vec<Decimal> {
+        records
+            .iter()
+            .filter(|record| {
+                record.kind == kind && record.formula != Some(OneRepMaxFormulaEnum::Brzycki)
+            })
+            .map(|record| record.value)
+            .collect()
+    }
+
+    #[test]
+    fn test_one_rep_max_formulas() {
+        let epley = OneRepMaxFormulaEnum::Epley;
+        let brzycki = OneRepMaxFormulaEnum::Brzycki;
+
+        assert_eq!(
+            epley.estimate(Decimal::from(100), 1),
+            Some(Decimal::from(100))
+        );
+        assert_eq!(
+            epley.estimate(Decimal::from(100), 10),
+            Some(Decimal::new(13333, 2))
+        );
+        assert_eq!(
+            brzycki.estimate(Decimal::from(100), 10),
+            Some(Decimal::new(13333, 2))
+        );
+        assert_eq!(
+            brzycki.estimate(Decimal::from(100), 5),
+            Some(Decimal::new(11250, 2))
+        );
+        assert_eq!(brzycki.estimate(Decimal::from(100), 37), None);
+        assert_eq!(epley.estimate(Decimal::from(100), 0), None);
+    }
+
+    #[test]
+    fn test_record_history() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let warm_up = gym_set::Model {
+            set_type: SetTypeEnum::WarmUp,
+            ..set(second, 1, 3, 100)
+        };
+        let sets = vec![
+            (day(1), set(first, 1, 10, 60)),
+            (day(1), set(first, 2, 8, 60)),
+            (day(8), warm_up),
+            (day(8), set(second, 1, 5, 70)),
+            (day(8), set(second, 2, 10, 60)),
+        ];
+
+        let records = record_history(&sets);
+
+        // Warm-ups never make a record
+        assert_eq!(
+            values(&records, PersonalRecordKindEnum::HeaviestWeight),
+            vec![Decimal::from(60), Decimal::from(70)]
+        );
+        // 60 kg x 10 (80.00) is only tied, 70 kg x 5 (81.67) beats it
+        assert_eq!(
+            values(&records, PersonalRecordKindEnum::BestOneRepMax),
+            vec![Decimal::from(80), Decimal::new(8167, 2)]
+        );
+        // Repetitions are compared for the same weight only
+        assert_eq!(
+            values(&records, PersonalRecordKindEnum::MostReps),
+            vec![Decimal::from(10), Decimal::from(5)]
+        );
+        // 950 kg on the second session stays below the first one
+        assert_eq!(
+            values(&records, PersonalRecordKindEnum::BestSessionVolume),
+            vec![Decimal::from(1080)]
+        );
+
+        let volume = records
+            .iter()
+            .find(|record| record.kind == PersonalRecordKindEnum::BestSessionVolume)
+            .unwrap();
+        assert_eq!(volume.session_id, first);
+        assert!(volume.set.is_none());
+    }
+}
@@ -0,0 +1,45 @@
+use sea_orm::prelude::Decimal;
+
+use crate::{
+    gym_set::Model,
+    sea_orm_active_enums::{OneRepMaxFormulaEnum, SetTypeEnum},
+};
+
+impl OneRepMaxFormulaEnum {
+    /// Weight that could be lifted for a single repetition, rounded to the gram.
+    /// `None` without repetitions, or when the formula does not hold for that many
+    /// (Brzycki stops making sense at 37 reps).
+    pub fn estimate(&self, weight_kg: Decimal, repetitions: i32) -> Option<Decimal> {
+        let estimate = match repetitions {
+            ..=0 => return None,
+            1 => weight_kg,
+            _ => match self {
+                Self::Epley => {
+                    weight_kg * (Decimal::ONE + Decimal::from(repetitions) / Decimal::from(30))
+                }
+                Self::Brzycki if repetitions < 37 => {
+                    weight_kg * Decimal::from(36) / Decimal::from(37 - repetitions)
+                }
+                Self::Brzycki => return None,
+            },
+        };
+        Some(estimate.round_dp(2))
+    }
+}
+
+impl Model {
+    /// Sets done with at least one repetition, warm-ups aside. Only those count towards
+    /// volumes and personal records.
+    pub fn is_working_set(&self) -> bool {
+        self.set_type != SetTypeEnum::WarmUp && self.repetitions > 0
+    }
+
+    /// Repetitions times weight
+    pub fn volume(&self) -> Decimal {
+        self.weight_kg * Decimal::from(self.repetitions)
+    }
+
+    pub fn estimated_one_rep_max(&self, formula: &OneRepMaxFormulaEnum) -> Option<Decimal> {
+        formula.estimate(self.weight_kg, self.repetitions)
+    }
+}
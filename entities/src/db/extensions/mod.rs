@@ -1,3 +1,9 @@
 pub mod email_verification_token_ext;
+pub mod food_item_ext;
+pub mod gym_exercise_ext;
+pub mod gym_personal_record_ext;
+pub mod gym_set_ext;
 pub mod password_reset_token_ext;
 pub mod refresh_token_ext;
+pub mod user_watch_permissions_ext;
+pub mod users_ext;
@@ -0,0 +1,11 @@
+use chrono::Utc;
+
+use crate::user_watch_permissions::Model;
+
+impl Model {
+    /// A permission without expiry date never expires
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| Utc::now() > expires_at)
+    }
+}
@@ -0,0 +1,13 @@
+use sea_orm::prelude::Uuid;
+
+use crate::users::Model;
+
+/// Owner of the data shipped with the application, like the exercise catalog.
+/// Created by the migrations, nobody can log in as it.
+pub const SYSTEM_USER_ID: Uuid = Uuid::from_u128(1);
+
+impl Model {
+    pub fn is_system(&self) -> bool {
+        self.id == SYSTEM_USER_ID
+    }
+}
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0.0-rc.16
 
+use super::sea_orm_active_enums::VisibilityEnum;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -19,6 +20,7 @@ pub struct Model {
     pub added_by: Uuid,
     pub updated_at: DateTimeWithTimeZone,
     pub added_at: DateTimeWithTimeZone,
+    pub visibility: VisibilityEnum,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
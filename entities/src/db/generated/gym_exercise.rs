@@ -1,6 +1,8 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0.0-rc.16
 
-use super::sea_orm_active_enums::MuscleEnum;
+use super::sea_orm_active_enums::EquipmentEnum;
+use super::sea_orm_active_enums::MovementPatternEnum;
+use super::sea_orm_active_enums::VisibilityEnum;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +17,10 @@ pub struct Model {
     pub added_by: Uuid,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub equipment: Option<EquipmentEnum>,
+    pub movement_pattern: Option<MovementPatternEnum>,
+    pub is_unilateral: bool,
+    pub visibility: VisibilityEnum,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -12,6 +12,12 @@ pub struct Model {
     pub date: Date,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub started_at: Option<DateTimeWithTimeZone>,
+    pub ended_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub notes: Option<String>,
+    pub perceived_difficulty: Option<i32>,
+    pub user_weight_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -26,6 +32,14 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Users,
+    #[sea_orm(
+        belongs_to = "super::user_weight::Entity",
+        from = "Column::UserWeightId",
+        to = "super::user_weight::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    UserWeight,
 }
 
 impl Related<super::gym_set::Entity> for Entity {
@@ -40,4 +54,10 @@ impl Related<super::users::Entity> for Entity {
     }
 }
 
+impl Related<super::user_weight::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserWeight.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0.0-rc.16
 
+use super::sea_orm_active_enums::SetTypeEnum;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +15,14 @@ pub struct Model {
     pub repetitions: i32,
     #[sea_orm(column_type = "Decimal(Some((6, 2)))")]
     pub weight_kg: Decimal,
+    pub set_type: SetTypeEnum,
+    #[sea_orm(column_type = "Decimal(Some((3, 1)))", nullable)]
+    pub rpe: Option<Decimal>,
+    pub rir: Option<i32>,
+    pub rest_seconds: Option<i32>,
+    pub duration_seconds: Option<i32>,
+    #[sea_orm(column_type = "Decimal(Some((8, 2)))", nullable)]
+    pub distance_meters: Option<Decimal>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
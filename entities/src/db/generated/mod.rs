@@ -2,20 +2,32 @@
 
 pub mod prelude;
 
+pub mod account_deletion_request;
+pub mod data_import;
 pub mod email_preferences;
 pub mod email_verification_token;
 pub mod exercise_muscle;
 pub mod food_item;
 pub mod gym_exercise;
+pub mod gym_personal_record;
 pub mod gym_session;
 pub mod gym_set;
+pub mod magic_link_token;
 pub mod meal;
 pub mod meal_item;
 pub mod password_reset_token;
 pub mod refresh_token;
 pub mod sea_orm_active_enums;
+pub mod training_program;
+pub mod training_program_day;
 pub mod user_additional_infos;
 pub mod user_groups;
+pub mod user_login_device;
+pub mod user_recovery_code;
+pub mod user_two_factor;
 pub mod user_watch_permissions;
 pub mod user_weight;
 pub mod users;
+pub mod watch_request;
+pub mod workout_template;
+pub mod workout_template_exercise;
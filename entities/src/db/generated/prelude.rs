@@ -1,18 +1,30 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0.0-rc.16
 
+pub use super::account_deletion_request::Entity as AccountDeletionRequest;
+pub use super::data_import::Entity as DataImport;
 pub use super::email_preferences::Entity as EmailPreferences;
 pub use super::email_verification_token::Entity as EmailVerificationToken;
 pub use super::exercise_muscle::Entity as ExerciseMuscle;
 pub use super::food_item::Entity as FoodItem;
 pub use super::gym_exercise::Entity as GymExercise;
+pub use super::gym_personal_record::Entity as GymPersonalRecord;
 pub use super::gym_session::Entity as GymSession;
 pub use super::gym_set::Entity as GymSet;
+pub use super::magic_link_token::Entity as MagicLinkToken;
 pub use super::meal::Entity as Meal;
 pub use super::meal_item::Entity as MealItem;
 pub use super::password_reset_token::Entity as PasswordResetToken;
 pub use super::refresh_token::Entity as RefreshToken;
+pub use super::training_program::Entity as TrainingProgram;
+pub use super::training_program_day::Entity as TrainingProgramDay;
 pub use super::user_additional_infos::Entity as UserAdditionalInfos;
 pub use super::user_groups::Entity as UserGroups;
+pub use super::user_login_device::Entity as UserLoginDevice;
+pub use super::user_recovery_code::Entity as UserRecoveryCode;
+pub use super::user_two_factor::Entity as UserTwoFactor;
 pub use super::user_watch_permissions::Entity as UserWatchPermissions;
 pub use super::user_weight::Entity as UserWeight;
 pub use super::users::Entity as Users;
+pub use super::watch_request::Entity as WatchRequest;
+pub use super::workout_template::Entity as WorkoutTemplate;
+pub use super::workout_template_exercise::Entity as WorkoutTemplateExercise;
@@ -3,6 +3,20 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "equipment_enum")]
+pub enum EquipmentEnum {
+    #[sea_orm(string_value = "barbell")]
+    Barbell,
+    #[sea_orm(string_value = "dumbbell")]
+    Dumbbell,
+    #[sea_orm(string_value = "machine")]
+    Machine,
+    #[sea_orm(string_value = "cable")]
+    Cable,
+    #[sea_orm(string_value = "bodyweight")]
+    Bodyweight,
+}
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "gender_enum")]
 pub enum GenderEnum {
@@ -14,6 +28,28 @@ pub enum GenderEnum {
     Other,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "import_kind_enum")]
+pub enum ImportKindEnum {
+    #[sea_orm(string_value = "weights")]
+    Weights,
+    #[sea_orm(string_value = "gym_sets")]
+    GymSets,
+    #[sea_orm(string_value = "meals")]
+    Meals,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "import_status_enum")]
+pub enum ImportStatusEnum {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "processing")]
+    Processing,
+    #[sea_orm(string_value = "completed")]
+    Completed,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "meal_type_enum")]
 pub enum MealTypeEnum {
     #[sea_orm(string_value = "breakfast")]
@@ -26,6 +62,34 @@ pub enum MealTypeEnum {
     Dinner,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "movement_pattern_enum"
+)]
+pub enum MovementPatternEnum {
+    #[sea_orm(string_value = "horizontal_push")]
+    HorizontalPush,
+    #[sea_orm(string_value = "vertical_push")]
+    VerticalPush,
+    #[sea_orm(string_value = "horizontal_pull")]
+    HorizontalPull,
+    #[sea_orm(string_value = "vertical_pull")]
+    VerticalPull,
+    #[sea_orm(string_value = "squat")]
+    Squat,
+    #[sea_orm(string_value = "hinge")]
+    Hinge,
+    #[sea_orm(string_value = "lunge")]
+    Lunge,
+    #[sea_orm(string_value = "carry")]
+    Carry,
+    #[sea_orm(string_value = "core")]
+    Core,
+    #[sea_orm(string_value = "isolation")]
+    Isolation,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "muscle_enum")]
 pub enum MuscleEnum {
     #[sea_orm(string_value = "chest")]
@@ -68,6 +132,46 @@ pub enum MuscleRoleEnum {
     Secondary,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "one_rep_max_formula_enum"
+)]
+pub enum OneRepMaxFormulaEnum {
+    #[sea_orm(string_value = "epley")]
+    Epley,
+    #[sea_orm(string_value = "brzycki")]
+    Brzycki,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "personal_record_kind_enum"
+)]
+pub enum PersonalRecordKindEnum {
+    #[sea_orm(string_value = "heaviest_weight")]
+    HeaviestWeight,
+    #[sea_orm(string_value = "best_one_rep_max")]
+    BestOneRepMax,
+    #[sea_orm(string_value = "most_reps")]
+    MostReps,
+    #[sea_orm(string_value = "best_session_volume")]
+    BestSessionVolume,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "set_type_enum")]
+pub enum SetTypeEnum {
+    #[sea_orm(string_value = "normal")]
+    Normal,
+    #[sea_orm(string_value = "warm_up")]
+    WarmUp,
+    #[sea_orm(string_value = "drop")]
+    Drop,
+    #[sea_orm(string_value = "failure")]
+    Failure,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "user_group")]
 pub enum UserGroup {
     #[sea_orm(string_value = "admin_group")]
@@ -91,3 +195,27 @@ pub enum UserProfileImage {
     #[sea_orm(string_value = "avatar5")]
     Avatar5,
 }
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "visibility_enum")]
+pub enum VisibilityEnum {
+    #[sea_orm(string_value = "private")]
+    Private,
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "public")]
+    Public,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "watch_request_status_enum"
+)]
+pub enum WatchRequestStatusEnum {
+    #[sea_orm(string_value = "accepted")]
+    Accepted,
+    #[sea_orm(string_value = "declined")]
+    Declined,
+    #[sea_orm(string_value = "pending")]
+    Pending,
+}
@@ -11,6 +11,11 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub user_watching_id: Uuid,
     pub created_at: DateTimeWithTimeZone,
+    pub can_view_weight: bool,
+    pub can_view_meals: bool,
+    pub can_view_gym: bool,
+    pub can_view_body_infos: bool,
+    pub expires_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
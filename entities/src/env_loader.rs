@@ -4,6 +4,7 @@ use opentelemetry::{KeyValue, global, trace::TracerProvider as _};
 use opentelemetry_otlp::{HttpExporterBuilder, WithExportConfig, WithHttpConfig};
 use opentelemetry_sdk::{
     Resource,
+    metrics::{SdkMeterProvider, Temporality},
     trace::{RandomIdGenerator, Sampler, SdkTracerProvider},
 };
 use serde::Deserialize;
@@ -12,6 +13,7 @@ use std::sync::OnceLock;
 use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
 
 static TRACER_PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+static METER_PROVIDER: OnceLock<SdkMeterProvider> = OnceLock::new();
 
 const SERVICE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -37,6 +39,215 @@ pub struct Settings {
     pub openobserve_endpoint: String,
     pub openobserve_user: String,
     pub openobserve_password: String,
+
+    /// Reverse proxies allowed to set `X-Real-IP`, as addresses or CIDR blocks
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+
+    #[serde(default)]
+    pub rate_limits: RateLimitSettings,
+
+    #[serde(default)]
+    pub login_security: LoginSecuritySettings,
+
+    #[serde(default)]
+    pub maintenance: MaintenanceSettings,
+
+    #[serde(default)]
+    pub passwords: PasswordSettings,
+
+    #[serde(default)]
+    pub account_deletion: AccountDeletionSettings,
+
+    #[serde(default)]
+    pub data_export: DataExportSettings,
+}
+
+/// Archives of the user data built by the worker
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DataExportSettings {
+    /// How long the emailed download link stays valid
+    pub link_valid_hours: u32,
+}
+
+impl Default for DataExportSettings {
+    fn default() -> Self {
+        Self {
+            link_valid_hours: 24,
+        }
+    }
+}
+
+/// Accounts are only removed by the worker once the grace period is over
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AccountDeletionSettings {
+    /// Logging in again before the end of the grace period cancels the deletion
+    pub grace_period_days: u32,
+}
+
+impl Default for AccountDeletionSettings {
+    fn default() -> Self {
+        Self {
+            grace_period_days: 14,
+        }
+    }
+}
+
+/// Password policy and hashing parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PasswordSettings {
+    pub min_length: usize,
+    pub max_length: usize,
+    /// Text file with one known breached password per line, refused at registration
+    pub breached_passwords_file: Option<String>,
+    pub argon2: Argon2Settings,
+}
+
+impl Default for PasswordSettings {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            breached_passwords_file: None,
+            argon2: Argon2Settings::default(),
+        }
+    }
+}
+
+/// Argon2id cost parameters, hashes made with other parameters are upgraded on login
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Argon2Settings {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for Argon2Settings {
+    fn default() -> Self {
+        // OWASP recommended minimum for Argon2id
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+/// Periodic cleanup done by the worker
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MaintenanceSettings {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    /// Guests without any activity for this long are deleted with all their data
+    pub guest_inactive_days: u32,
+    /// Rows deleted per query, to keep the locks short
+    pub batch_size: u64,
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 3600,
+            guest_inactive_days: 30,
+            batch_size: 500,
+        }
+    }
+}
+
+/// Tracking of failed logins, accounts are locked once the threshold is reached
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LoginSecuritySettings {
+    pub max_failed_attempts_per_account: u32,
+    pub max_failed_attempts_per_ip: u32,
+    pub failed_attempts_window_secs: u64,
+    pub lockout_secs: u64,
+}
+
+impl Default for LoginSecuritySettings {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts_per_account: 5,
+            max_failed_attempts_per_ip: 20,
+            failed_attempts_window_secs: 900,
+            lockout_secs: 900,
+        }
+    }
+}
+
+/// Rate limits applied to the sensitive public endpoints
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RateLimitSettings {
+    pub enabled: bool,
+
+    pub login: RouteRateLimit,
+    pub register: RouteRateLimit,
+    pub register_guest: RouteRateLimit,
+    pub forgot_password: RouteRateLimit,
+    pub magic_link: RouteRateLimit,
+    pub search_users: RouteRateLimit,
+}
+
+/// Limits of a single route, counted by client IP and by targeted account
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RouteRateLimit {
+    pub per_ip: Option<RateLimitRule>,
+    pub per_account: Option<RateLimitRule>,
+}
+
+/// At most `max_requests` requests in any sliding window of `window_secs` seconds
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RateLimitRule {
+    pub max_requests: u32,
+    pub window_secs: u64,
+}
+
+impl RateLimitRule {
+    pub const fn new(max_requests: u32, window_secs: u64) -> Self {
+        Self {
+            max_requests,
+            window_secs,
+        }
+    }
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            login: RouteRateLimit {
+                per_ip: Some(RateLimitRule::new(20, 300)),
+                per_account: Some(RateLimitRule::new(5, 300)),
+            },
+            register: RouteRateLimit {
+                per_ip: Some(RateLimitRule::new(5, 3600)),
+                per_account: Some(RateLimitRule::new(3, 3600)),
+            },
+            register_guest: RouteRateLimit {
+                per_ip: Some(RateLimitRule::new(5, 3600)),
+                per_account: None,
+            },
+            forgot_password: RouteRateLimit {
+                per_ip: Some(RateLimitRule::new(5, 900)),
+                per_account: Some(RateLimitRule::new(3, 900)),
+            },
+            magic_link: RouteRateLimit {
+                per_ip: Some(RateLimitRule::new(5, 900)),
+                per_account: Some(RateLimitRule::new(3, 900)),
+            },
+            search_users: RouteRateLimit {
+                per_ip: Some(RateLimitRule::new(60, 60)),
+                per_account: Some(RateLimitRule::new(30, 60)),
+            },
+        }
+    }
 }
 
 impl Settings {
@@ -71,9 +282,20 @@ impl Settings {
             .with_endpoint(&self.openobserve_endpoint)
             .with_timeout(std::time::Duration::from_secs(3))
             .with_http_client(reqwest::Client::new())
-            .with_headers(headers)
+            .with_headers(headers.clone())
             .build_span_exporter()?;
 
+        // Metrics go to the same OpenObserve stream as the traces, on its metrics endpoint
+        let metrics_exporter = HttpExporterBuilder::default()
+            .with_endpoint(
+                self.openobserve_endpoint
+                    .replace("/v1/traces", "/v1/metrics"),
+            )
+            .with_timeout(std::time::Duration::from_secs(3))
+            .with_http_client(reqwest::Client::new())
+            .with_headers(headers)
+            .build_metrics_exporter(Temporality::Cumulative)?;
+
         let resource = Resource::builder_empty()
             .with_attributes([
                 KeyValue::new("service.name", service_name.to_string()),
@@ -83,6 +305,13 @@ impl Settings {
             ])
             .build();
 
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(metrics_exporter)
+            .with_resource(resource.clone())
+            .build();
+        global::set_meter_provider(meter_provider.clone());
+        let _ = METER_PROVIDER.set(meter_provider);
+
         let provider = SdkTracerProvider::builder()
             .with_batch_exporter(exporter)
             .with_resource(resource)
@@ -136,5 +365,10 @@ impl Settings {
                 tracing::info!("Telemetry shutdown complete");
             }
         }
+        if let Some(provider) = METER_PROVIDER.get()
+            && let Err(e) = provider.shutdown()
+        {
+            tracing::error!(error = %e, "Failed to shutdown meter provider");
+        }
     }
 }
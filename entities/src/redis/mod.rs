@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum TaskType {
     Email,
+    DataExport,
+    DataImport,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,6 +17,11 @@ pub enum EmailType {
     MonthlyRecap,
     WeeklyRecap,
     YearlyRecap,
+    WatchRequest,
+    SecurityAlert,
+    MagicLink,
+    AccountDeletion,
+    DataExport,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -42,6 +50,28 @@ pub struct JobEmailResetPassword {
     pub token: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobEmailMagicLink {
+    pub email: String,
+    pub username: String,
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobEmailAccountDeletion {
+    pub email: String,
+    pub username: String,
+    pub delete_after: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobEmailDataExport {
+    pub email: String,
+    pub username: String,
+    pub token: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JobEmailMonthlyRecap {
     pub email: String,
@@ -60,10 +90,61 @@ pub struct JobEmailYearlyRecap {
     pub username: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobEmailWatchRequest {
+    pub email: String,
+    pub username: String,
+    pub requester_username: String,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobEmailSecurityAlert {
+    pub email: String,
+    pub username: String,
+    pub alert: SecurityAlert,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum SecurityAlert {
+    AccountLocked {
+        locked_minutes: u64,
+        ip: Option<String>,
+    },
+    NewDeviceLogin {
+        user_agent: String,
+        ip: Option<String>,
+    },
+}
+
+/// Build an archive of everything stored about the user
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobDataExport {
+    pub user_id: Uuid,
+}
+
+/// Process an uploaded import, or only build its report for a dry run
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobDataImport {
+    pub import_id: Uuid,
+}
+
+/// Set while an export of the user is being prepared, so only one runs at a time
+pub fn data_export_pending_key(user_id: &Uuid) -> String {
+    format!("data_export_pending:{}", user_id)
+}
+
+/// Finished export archive, only downloadable by its owner until the key expires
+pub fn data_export_key(user_id: &Uuid, token: &str) -> String {
+    format!("data_export:{}:{}", user_id, token)
+}
+
 impl fmt::Display for TaskType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             TaskType::Email => write!(f, "Email"),
+            TaskType::DataExport => write!(f, "DataExport"),
+            TaskType::DataImport => write!(f, "DataImport"),
         }
     }
 }
@@ -77,6 +158,11 @@ impl fmt::Display for EmailType {
             EmailType::MonthlyRecap => write!(f, "MonthlyRecap"),
             EmailType::WeeklyRecap => write!(f, "WeeklyRecap"),
             EmailType::YearlyRecap => write!(f, "YearlyRecap"),
+            EmailType::WatchRequest => write!(f, "WatchRequest"),
+            EmailType::SecurityAlert => write!(f, "SecurityAlert"),
+            EmailType::MagicLink => write!(f, "MagicLink"),
+            EmailType::AccountDeletion => write!(f, "AccountDeletion"),
+            EmailType::DataExport => write!(f, "DataExport"),
         }
     }
 }
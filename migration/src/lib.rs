@@ -17,6 +17,22 @@ mod m20251129_175041_create_email_recap_pref;
 mod m20251129_234020_create_gym_exercise;
 mod m20251129_234021_create_gym_session;
 mod m20251129_234022_create_gym_set;
+mod m20251201_101500_add_watch_permission_scopes;
+mod m20251202_183000_create_watch_request;
+mod m20251204_090000_create_user_login_device;
+mod m20251206_140000_create_two_factor;
+mod m20251207_100000_create_magic_link_tokens;
+mod m20251208_090000_create_account_deletion_request;
+mod m20251209_090000_create_data_import;
+mod m20251210_090000_add_meals_import_kind;
+mod m20251211_090000_seed_exercise_catalog;
+mod m20251212_090000_add_catalog_visibility;
+mod m20251213_090000_create_gym_personal_record;
+mod m20251214_090000_add_gym_set_details;
+mod m20251215_090000_create_workout_template;
+mod m20251216_090000_create_training_program;
+mod m20251217_090000_add_gym_session_details;
+mod m20251218_090000_add_gym_set_number_unique;
 
 pub struct Migrator;
 
@@ -39,6 +55,22 @@ impl MigratorTrait for Migrator {
             Box::new(m20251129_234020_create_gym_exercise::Migration),
             Box::new(m20251129_234021_create_gym_session::Migration),
             Box::new(m20251129_234022_create_gym_set::Migration),
+            Box::new(m20251201_101500_add_watch_permission_scopes::Migration),
+            Box::new(m20251202_183000_create_watch_request::Migration),
+            Box::new(m20251204_090000_create_user_login_device::Migration),
+            Box::new(m20251206_140000_create_two_factor::Migration),
+            Box::new(m20251207_100000_create_magic_link_tokens::Migration),
+            Box::new(m20251208_090000_create_account_deletion_request::Migration),
+            Box::new(m20251209_090000_create_data_import::Migration),
+            Box::new(m20251210_090000_add_meals_import_kind::Migration),
+            Box::new(m20251211_090000_seed_exercise_catalog::Migration),
+            Box::new(m20251212_090000_add_catalog_visibility::Migration),
+            Box::new(m20251213_090000_create_gym_personal_record::Migration),
+            Box::new(m20251214_090000_add_gym_set_details::Migration),
+            Box::new(m20251215_090000_create_workout_template::Migration),
+            Box::new(m20251216_090000_create_training_program::Migration),
+            Box::new(m20251217_090000_add_gym_session_details::Migration),
+            Box::new(m20251218_090000_add_gym_set_number_unique::Migration),
         ]
     }
 }
@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+use crate::helpers::{schedule_cron_job, unschedule_cron_job};
+
+const CRON_NAME: &str = "delete_expired_watch_permissions";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Existing permissions keep granting everything, so every scope defaults to true
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserWatchPermissions::Table)
+                    .add_column(
+                        ColumnDef::new(UserWatchPermissions::CanViewWeight)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .add_column(
+                        ColumnDef::new(UserWatchPermissions::CanViewMeals)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .add_column(
+                        ColumnDef::new(UserWatchPermissions::CanViewGym)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .add_column(
+                        ColumnDef::new(UserWatchPermissions::CanViewBodyInfos)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .add_column(
+                        ColumnDef::new(UserWatchPermissions::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Expired permissions are already ignored by the API, this only keeps the table clean
+        schedule_cron_job(
+            manager,
+            CRON_NAME,
+            "0 3 * * *",
+            "DELETE FROM user_watch_permissions WHERE expires_at < NOW()",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        unschedule_cron_job(manager, CRON_NAME).await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserWatchPermissions::Table)
+                    .drop_column(UserWatchPermissions::CanViewWeight)
+                    .drop_column(UserWatchPermissions::CanViewMeals)
+                    .drop_column(UserWatchPermissions::CanViewGym)
+                    .drop_column(UserWatchPermissions::CanViewBodyInfos)
+                    .drop_column(UserWatchPermissions::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserWatchPermissions {
+    Table,
+    CanViewWeight,
+    CanViewMeals,
+    CanViewGym,
+    CanViewBodyInfos,
+    ExpiresAt,
+}
@@ -0,0 +1,148 @@
+use crate::helpers::{create_updated_at_trigger, drop_updated_at_trigger};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+static WATCH_REQUEST_STATUS_ENUM: &str = "watch_request_status_enum";
+static TABLE_NAME: &str = "watch_request";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "CREATE TYPE {} AS ENUM ('pending', 'accepted', 'declined');",
+                WATCH_REQUEST_STATUS_ENUM
+            ))
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WatchRequest::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WatchRequest::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()")),
+                    )
+                    .col(ColumnDef::new(WatchRequest::RequesterId).uuid().not_null())
+                    .col(ColumnDef::new(WatchRequest::TargetId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(WatchRequest::Status)
+                            .custom(Alias::new(WATCH_REQUEST_STATUS_ENUM))
+                            .not_null()
+                            .default(Expr::cust("'pending'")),
+                    )
+                    .col(ColumnDef::new(WatchRequest::Message).text().null())
+                    .col(
+                        ColumnDef::new(WatchRequest::RespondedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(WatchRequest::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(WatchRequest::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_watch_request_requester_id")
+                            .from(WatchRequest::Table, WatchRequest::RequesterId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_watch_request_target_id")
+                            .from(WatchRequest::Table, WatchRequest::TargetId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_watch_request_requester_id")
+                    .table(WatchRequest::Table)
+                    .col(WatchRequest::RequesterId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_watch_request_target_id")
+                    .table(WatchRequest::Table)
+                    .col(WatchRequest::TargetId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Only one pending request per (requester, target) pair
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "CREATE UNIQUE INDEX uq_watch_request_pending ON {TABLE_NAME} (requester_id, target_id) WHERE status = 'pending';"
+            ))
+            .await?;
+
+        // Add trigger for updated_at
+        create_updated_at_trigger(manager, TABLE_NAME).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Drop trigger
+        drop_updated_at_trigger(manager, TABLE_NAME).await?;
+
+        manager
+            .drop_table(Table::drop().table(WatchRequest::Table).to_owned())
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "DROP TYPE IF EXISTS {};",
+                WATCH_REQUEST_STATUS_ENUM
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum WatchRequest {
+    Table,
+    Id,
+    RequesterId,
+    TargetId,
+    Status,
+    Message,
+    RespondedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
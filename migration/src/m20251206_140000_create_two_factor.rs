@@ -0,0 +1,165 @@
+use crate::helpers::{create_updated_at_trigger, drop_updated_at_trigger};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+static TABLE_NAME: &str = "user_two_factor";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserTwoFactor::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserTwoFactor::UserId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UserTwoFactor::Secret)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserTwoFactor::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(UserTwoFactor::LastUsedStep)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserTwoFactor::EnabledAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserTwoFactor::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(UserTwoFactor::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_two_factor_user_id")
+                            .from(UserTwoFactor::Table, UserTwoFactor::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserRecoveryCode::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserRecoveryCode::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()")),
+                    )
+                    .col(ColumnDef::new(UserRecoveryCode::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(UserRecoveryCode::CodeHash)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserRecoveryCode::UsedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserRecoveryCode::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_recovery_code_user_id")
+                            .from(UserRecoveryCode::Table, UserRecoveryCode::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_recovery_code_user_id")
+                    .table(UserRecoveryCode::Table)
+                    .col(UserRecoveryCode::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Add trigger for updated_at
+        create_updated_at_trigger(manager, TABLE_NAME).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Drop trigger
+        drop_updated_at_trigger(manager, TABLE_NAME).await?;
+
+        manager
+            .drop_table(Table::drop().table(UserRecoveryCode::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(UserTwoFactor::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserTwoFactor {
+    Table,
+    UserId,
+    Secret,
+    Enabled,
+    LastUsedStep,
+    EnabledAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum UserRecoveryCode {
+    Table,
+    Id,
+    UserId,
+    CodeHash,
+    UsedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
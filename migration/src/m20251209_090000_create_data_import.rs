@@ -0,0 +1,153 @@
+use crate::helpers::{create_updated_at_trigger, drop_updated_at_trigger};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+static IMPORT_KIND_ENUM: &str = "import_kind_enum";
+static IMPORT_STATUS_ENUM: &str = "import_status_enum";
+static TABLE_NAME: &str = "data_import";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "CREATE TYPE {} AS ENUM (
+                    'weights',
+                    'gym_sets'
+                );",
+                IMPORT_KIND_ENUM
+            ))
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "CREATE TYPE {} AS ENUM (
+                    'pending',
+                    'processing',
+                    'completed',
+                    'failed'
+                );",
+                IMPORT_STATUS_ENUM
+            ))
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(DataImport::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DataImport::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()")),
+                    )
+                    .col(ColumnDef::new(DataImport::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(DataImport::Kind)
+                            .custom(Alias::new(IMPORT_KIND_ENUM))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DataImport::Status)
+                            .custom(Alias::new(IMPORT_STATUS_ENUM))
+                            .not_null()
+                            .default(Expr::cust("'pending'")),
+                    )
+                    .col(
+                        ColumnDef::new(DataImport::DryRun)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(DataImport::Content).text().not_null())
+                    .col(ColumnDef::new(DataImport::Report).json_binary().null())
+                    .col(
+                        ColumnDef::new(DataImport::FinishedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(DataImport::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(DataImport::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_data_imports_user_id")
+                            .from(DataImport::Table, DataImport::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_data_imports_user_id")
+                    .table(DataImport::Table)
+                    .col(DataImport::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        create_updated_at_trigger(manager, TABLE_NAME).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_updated_at_trigger(manager, TABLE_NAME).await?;
+
+        manager
+            .drop_table(Table::drop().table(DataImport::Table).to_owned())
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!("DROP TYPE IF EXISTS {};", IMPORT_KIND_ENUM))
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!("DROP TYPE IF EXISTS {};", IMPORT_STATUS_ENUM))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum DataImport {
+    Table,
+    Id,
+    UserId,
+    Kind,
+    Status,
+    DryRun,
+    Content,
+    Report,
+    FinishedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+static IMPORT_KIND_ENUM: &str = "import_kind_enum";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "ALTER TYPE {} ADD VALUE IF NOT EXISTS 'meals';",
+                IMPORT_KIND_ENUM
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Postgres cannot drop a value from an enum, the type is recreated without it
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "DELETE FROM data_import WHERE kind = 'meals';
+                ALTER TYPE {0} RENAME TO {0}_old;
+                CREATE TYPE {0} AS ENUM (
+                    'weights',
+                    'gym_sets'
+                );
+                ALTER TABLE data_import
+                    ALTER COLUMN kind TYPE {0} USING kind::text::{0};
+                DROP TYPE {0}_old;",
+                IMPORT_KIND_ENUM
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
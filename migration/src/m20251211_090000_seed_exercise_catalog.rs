@@ -0,0 +1,306 @@
+use sea_orm_migration::{prelude::*, sea_orm::Statement};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+static EQUIPMENT_ENUM: &str = "equipment_enum";
+static MOVEMENT_PATTERN_ENUM: &str = "movement_pattern_enum";
+
+/// Same id as `entities::users_ext::SYSTEM_USER_ID`
+static SYSTEM_USER_ID: &str = "00000000-0000-0000-0000-000000000001";
+static SYSTEM_USERNAME: &str = "DimDim Health";
+static SYSTEM_EMAIL: &str = "system@dimdim-health.invalid";
+
+struct CatalogExercise {
+    name: &'static str,
+    equipment: &'static str,
+    movement_pattern: &'static str,
+    is_unilateral: bool,
+    primary_muscles: &'static [&'static str],
+    secondary_muscles: &'static [&'static str],
+}
+
+const fn exercise(
+    name: &'static str,
+    equipment: &'static str,
+    movement_pattern: &'static str,
+    is_unilateral: bool,
+    primary_muscles: &'static [&'static str],
+    secondary_muscles: &'static [&'static str],
+) -> CatalogExercise {
+    CatalogExercise {
+        name,
+        equipment,
+        movement_pattern,
+        is_unilateral,
+        primary_muscles,
+        secondary_muscles,
+    }
+}
+
+#[rustfmt::skip]
+static CATALOG: &[CatalogExercise] = &[
+    // Barbell
+    exercise("Bench Press", "barbell", "horizontal_push", false, &["chest"], &["triceps", "shoulders"]),
+    exercise("Incline Bench Press", "barbell", "horizontal_push", false, &["chest"], &["shoulders", "triceps"]),
+    exercise("Close-Grip Bench Press", "barbell", "horizontal_push", false, &["triceps"], &["chest", "shoulders"]),
+    exercise("Overhead Press", "barbell", "vertical_push", false, &["shoulders"], &["triceps", "traps"]),
+    exercise("Back Squat", "barbell", "squat", false, &["quadriceps", "glutes"], &["hamstrings", "lower_back"]),
+    exercise("Front Squat", "barbell", "squat", false, &["quadriceps"], &["glutes", "abs"]),
+    exercise("Deadlift", "barbell", "hinge", false, &["hamstrings", "glutes", "lower_back"], &["traps", "forearms", "quadriceps"]),
+    exercise("Romanian Deadlift", "barbell", "hinge", false, &["hamstrings", "glutes"], &["lower_back"]),
+    exercise("Hip Thrust", "barbell", "hinge", false, &["glutes"], &["hamstrings"]),
+    exercise("Barbell Row", "barbell", "horizontal_pull", false, &["back", "lats"], &["biceps", "lower_back"]),
+    exercise("Barbell Curl", "barbell", "isolation", false, &["biceps"], &["forearms"]),
+    exercise("Barbell Shrug", "barbell", "isolation", false, &["traps"], &["forearms"]),
+    exercise("Barbell Lunge", "barbell", "lunge", true, &["quadriceps", "glutes"], &["hamstrings"]),
+    // Dumbbell
+    exercise("Dumbbell Bench Press", "dumbbell", "horizontal_push", false, &["chest"], &["triceps", "shoulders"]),
+    exercise("Dumbbell Shoulder Press", "dumbbell", "vertical_push", false, &["shoulders"], &["triceps"]),
+    exercise("Dumbbell Row", "dumbbell", "horizontal_pull", true, &["lats", "back"], &["biceps"]),
+    exercise("Dumbbell Curl", "dumbbell", "isolation", false, &["biceps"], &["forearms"]),
+    exercise("Hammer Curl", "dumbbell", "isolation", false, &["biceps", "forearms"], &[]),
+    exercise("Lateral Raise", "dumbbell", "isolation", false, &["shoulders"], &["traps"]),
+    exercise("Dumbbell Fly", "dumbbell", "isolation", false, &["chest"], &["shoulders"]),
+    exercise("Overhead Triceps Extension", "dumbbell", "isolation", false, &["triceps"], &[]),
+    exercise("Goblet Squat", "dumbbell", "squat", false, &["quadriceps", "glutes"], &["abs"]),
+    exercise("Dumbbell Romanian Deadlift", "dumbbell", "hinge", false, &["hamstrings", "glutes"], &["lower_back"]),
+    exercise("Bulgarian Split Squat", "dumbbell", "lunge", true, &["quadriceps", "glutes"], &["hamstrings"]),
+    exercise("Walking Lunge", "dumbbell", "lunge", true, &["quadriceps", "glutes"], &["hamstrings", "calves"]),
+    exercise("Farmer Carry", "dumbbell", "carry", false, &["forearms", "traps"], &["abs", "obliques"]),
+    // Machine
+    exercise("Leg Press", "machine", "squat", false, &["quadriceps", "glutes"], &["hamstrings"]),
+    exercise("Hack Squat", "machine", "squat", false, &["quadriceps"], &["glutes"]),
+    exercise("Leg Extension", "machine", "isolation", false, &["quadriceps"], &[]),
+    exercise("Lying Leg Curl", "machine", "isolation", false, &["hamstrings"], &["calves"]),
+    exercise("Standing Calf Raise", "machine", "isolation", false, &["calves"], &[]),
+    exercise("Seated Calf Raise", "machine", "isolation", false, &["calves"], &[]),
+    exercise("Machine Chest Press", "machine", "horizontal_push", false, &["chest"], &["triceps", "shoulders"]),
+    exercise("Pec Deck", "machine", "isolation", false, &["chest"], &["shoulders"]),
+    // Cable
+    exercise("Lat Pulldown", "cable", "vertical_pull", false, &["lats"], &["biceps", "back"]),
+    exercise("Seated Cable Row", "cable", "horizontal_pull", false, &["back", "lats"], &["biceps"]),
+    exercise("Single-Arm Cable Row", "cable", "horizontal_pull", true, &["lats", "back"], &["biceps", "obliques"]),
+    exercise("Triceps Pushdown", "cable", "isolation", false, &["triceps"], &[]),
+    exercise("Cable Fly", "cable", "isolation", false, &["chest"], &["shoulders"]),
+    exercise("Face Pull", "cable", "horizontal_pull", false, &["shoulders", "traps"], &["back"]),
+    exercise("Cable Crunch", "cable", "core", false, &["abs"], &["obliques"]),
+    exercise("Cable Woodchop", "cable", "core", true, &["obliques"], &["abs", "shoulders"]),
+    // Bodyweight
+    exercise("Pull-Up", "bodyweight", "vertical_pull", false, &["lats"], &["biceps", "back"]),
+    exercise("Chin-Up", "bodyweight", "vertical_pull", false, &["lats", "biceps"], &["back"]),
+    exercise("Push-Up", "bodyweight", "horizontal_push", false, &["chest"], &["triceps", "shoulders"]),
+    exercise("Dip", "bodyweight", "vertical_push", false, &["chest", "triceps"], &["shoulders"]),
+    exercise("Inverted Row", "bodyweight", "horizontal_pull", false, &["back", "lats"], &["biceps"]),
+    exercise("Pistol Squat", "bodyweight", "squat", true, &["quadriceps", "glutes"], &["abs"]),
+    exercise("Back Extension", "bodyweight", "hinge", false, &["lower_back"], &["glutes", "hamstrings"]),
+    exercise("Plank", "bodyweight", "core", false, &["abs"], &["obliques", "shoulders"]),
+    exercise("Hanging Leg Raise", "bodyweight", "core", false, &["abs"], &["obliques", "forearms"]),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "CREATE TYPE {} AS ENUM (
+                    'barbell',
+                    'dumbbell',
+                    'machine',
+                    'cable',
+                    'bodyweight'
+                );",
+                EQUIPMENT_ENUM
+            ))
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "CREATE TYPE {} AS ENUM (
+                    'horizontal_push',
+                    'vertical_push',
+                    'horizontal_pull',
+                    'vertical_pull',
+                    'squat',
+                    'hinge',
+                    'lunge',
+                    'carry',
+                    'core',
+                    'isolation'
+                );",
+                MOVEMENT_PATTERN_ENUM
+            ))
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GymExercise::Table)
+                    .add_column(
+                        ColumnDef::new(GymExercise::Equipment)
+                            .custom(Alias::new(EQUIPMENT_ENUM))
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(GymExercise::MovementPattern)
+                            .custom(Alias::new(MOVEMENT_PATTERN_ENUM))
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(GymExercise::IsUnilateral)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // `ON CONFLICT (id)` only covers a previous run, a real account holding the name
+        // or the email would make the insert fail with a bare unique violation
+        let db = manager.get_connection();
+        let taken = db
+            .query_one_raw(Statement::from_string(
+                db.get_database_backend(),
+                format!(
+                    "SELECT EXISTS (
+                        SELECT 1 FROM users
+                        WHERE id <> '{}'
+                        AND (username = '{}' OR email = '{}')
+                    ) AS taken;",
+                    SYSTEM_USER_ID, SYSTEM_USERNAME, SYSTEM_EMAIL
+                ),
+            ))
+            .await?
+            .map(|row| row.try_get::<bool>("", "taken"))
+            .transpose()?
+            .unwrap_or(false);
+        if taken {
+            return Err(DbErr::Migration(format!(
+                "A user already has the username {} or the email {}, rename them before \
+                seeding the catalog",
+                SYSTEM_USERNAME, SYSTEM_EMAIL
+            )));
+        }
+
+        // Owns the catalog, the password hash matches no scheme and the email domain
+        // cannot receive mails so nobody can log in as it
+        db.execute_unprepared(&format!(
+            "INSERT INTO users (id, username, email, password_hash, email_verified)
+            VALUES ('{}', '{}', '{}', '!', true)
+            ON CONFLICT (id) DO NOTHING;",
+            SYSTEM_USER_ID, SYSTEM_USERNAME, SYSTEM_EMAIL
+        ))
+        .await?;
+
+        for exercise in CATALOG {
+            let muscles: Vec<String> = exercise
+                .primary_muscles
+                .iter()
+                .map(|muscle| format!("('{}', 'primary')", muscle))
+                .chain(
+                    exercise
+                        .secondary_muscles
+                        .iter()
+                        .map(|muscle| format!("('{}', 'secondary')", muscle)),
+                )
+                .collect();
+
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "WITH exercise AS (
+                        INSERT INTO gym_exercise (name, added_by, equipment, movement_pattern, is_unilateral)
+                        VALUES ('{}', '{}', '{}', '{}', {})
+                        RETURNING id
+                    )
+                    INSERT INTO exercise_muscle (exercise_id, muscle, role)
+                    SELECT exercise.id, muscles.muscle::muscle_enum, muscles.role::muscle_role_enum
+                    FROM exercise, (VALUES {}) AS muscles (muscle, role);",
+                    exercise.name,
+                    SYSTEM_USER_ID,
+                    exercise.equipment,
+                    exercise.movement_pattern,
+                    exercise.is_unilateral,
+                    muscles.join(", ")
+                ))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Deleting the system user cascades to its exercises and food items, and from
+        // there to the sets and meal items of the users who logged them
+        let db = manager.get_connection();
+        let in_use = db
+            .query_one_raw(Statement::from_string(
+                db.get_database_backend(),
+                format!(
+                    "SELECT EXISTS (
+                        SELECT 1 FROM gym_set
+                        JOIN gym_exercise ON gym_exercise.id = gym_set.exercise_id
+                        WHERE gym_exercise.added_by = '{0}'
+                    ) OR EXISTS (
+                        SELECT 1 FROM meal_item
+                        JOIN food_item ON food_item.id = meal_item.food_item_id
+                        WHERE food_item.added_by = '{0}'
+                    ) AS in_use;",
+                    SYSTEM_USER_ID
+                ),
+            ))
+            .await?
+            .map(|row| row.try_get::<bool>("", "in_use"))
+            .transpose()?
+            .unwrap_or(false);
+        if in_use {
+            return Err(DbErr::Migration(
+                "Catalog exercises or shared food items are still used, delete those sets and \
+                meal items before rolling back"
+                    .to_string(),
+            ));
+        }
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "DELETE FROM users WHERE id = '{}';",
+                SYSTEM_USER_ID
+            ))
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GymExercise::Table)
+                    .drop_column(GymExercise::Equipment)
+                    .drop_column(GymExercise::MovementPattern)
+                    .drop_column(GymExercise::IsUnilateral)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!("DROP TYPE IF EXISTS {};", EQUIPMENT_ENUM))
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!("DROP TYPE IF EXISTS {};", MOVEMENT_PATTERN_ENUM))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum GymExercise {
+    Table,
+    Equipment,
+    MovementPattern,
+    IsUnilateral,
+}
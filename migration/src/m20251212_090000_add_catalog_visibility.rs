@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+static VISIBILITY_ENUM: &str = "visibility_enum";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "CREATE TYPE {} AS ENUM (
+                    'private',
+                    'pending',
+                    'public'
+                );",
+                VISIBILITY_ENUM
+            ))
+            .await?;
+
+        // Everything created so far was already visible to everyone, only new items
+        // start private
+        for table in ["gym_exercise", "food_item"] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .add_column(
+                            ColumnDef::new(Alias::new("visibility"))
+                                .custom(Alias::new(VISIBILITY_ENUM))
+                                .not_null()
+                                .default("public"),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "ALTER TABLE {} ALTER COLUMN visibility SET DEFAULT 'private';",
+                    table
+                ))
+                .await?;
+
+            manager
+                .create_index(
+                    Index::create()
+                        .name(format!("idx_{}_visibility", table))
+                        .table(Alias::new(table))
+                        .col(Alias::new("visibility"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in ["gym_exercise", "food_item"] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .drop_column(Alias::new("visibility"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!("DROP TYPE IF EXISTS {};", VISIBILITY_ENUM))
+            .await?;
+
+        Ok(())
+    }
+}
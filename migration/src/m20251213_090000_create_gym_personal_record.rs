@@ -0,0 +1,214 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+static ONE_REP_MAX_FORMULA_ENUM: &str = "one_rep_max_formula_enum";
+static PERSONAL_RECORD_KIND_ENUM: &str = "personal_record_kind_enum";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "CREATE TYPE {} AS ENUM (
+                    'epley',
+                    'brzycki'
+                );",
+                ONE_REP_MAX_FORMULA_ENUM
+            ))
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "CREATE TYPE {} AS ENUM (
+                    'heaviest_weight',
+                    'best_one_rep_max',
+                    'most_reps',
+                    'best_session_volume'
+                );",
+                PERSONAL_RECORD_KIND_ENUM
+            ))
+            .await?;
+
+        // Rows are never updated, the history of an exercise is rebuilt whenever one of
+        // its sets changes
+        manager
+            .create_table(
+                Table::create()
+                    .table(GymPersonalRecord::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()")),
+                    )
+                    .col(ColumnDef::new(GymPersonalRecord::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::ExerciseId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::SessionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(GymPersonalRecord::SetId).uuid().null())
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::Kind)
+                            .custom(Alias::new(PERSONAL_RECORD_KIND_ENUM))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::Formula)
+                            .custom(Alias::new(ONE_REP_MAX_FORMULA_ENUM))
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::Value)
+                            .decimal_len(10, 2)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::PreviousValue)
+                            .decimal_len(10, 2)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::WeightKg)
+                            .decimal_len(6, 2)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::Repetitions)
+                            .integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::AchievedOn)
+                            .date()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GymPersonalRecord::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_gym_personal_record_user_id")
+                            .from(GymPersonalRecord::Table, GymPersonalRecord::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_gym_personal_record_exercise_id")
+                            .from(GymPersonalRecord::Table, GymPersonalRecord::ExerciseId)
+                            .to(GymExercise::Table, GymExercise::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_gym_personal_record_session_id")
+                            .from(GymPersonalRecord::Table, GymPersonalRecord::SessionId)
+                            .to(GymSession::Table, GymSession::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_gym_personal_record_set_id")
+                            .from(GymPersonalRecord::Table, GymPersonalRecord::SetId)
+                            .to(GymSet::Table, GymSet::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_gym_personal_record_user_exercise")
+                    .table(GymPersonalRecord::Table)
+                    .col(GymPersonalRecord::UserId)
+                    .col(GymPersonalRecord::ExerciseId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GymPersonalRecord::Table).to_owned())
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "DROP TYPE IF EXISTS {};",
+                PERSONAL_RECORD_KIND_ENUM
+            ))
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "DROP TYPE IF EXISTS {};",
+                ONE_REP_MAX_FORMULA_ENUM
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum GymPersonalRecord {
+    Table,
+    Id,
+    UserId,
+    ExerciseId,
+    SessionId,
+    SetId,
+    Kind,
+    Formula,
+    Value,
+    PreviousValue,
+    WeightKg,
+    Repetitions,
+    AchievedOn,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum GymExercise {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum GymSession {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum GymSet {
+    Table,
+    Id,
+}
@@ -0,0 +1,101 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+static SET_TYPE_ENUM: &str = "set_type_enum";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "CREATE TYPE {} AS ENUM (
+                    'normal',
+                    'warm_up',
+                    'drop',
+                    'failure'
+                );",
+                SET_TYPE_ENUM
+            ))
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GymSet::Table)
+                    .add_column(
+                        ColumnDef::new(GymSet::SetType)
+                            .custom(Alias::new(SET_TYPE_ENUM))
+                            .not_null()
+                            .default("normal"),
+                    )
+                    .add_column(ColumnDef::new(GymSet::Rpe).decimal_len(3, 1).null())
+                    .add_column(ColumnDef::new(GymSet::Rir).integer().null())
+                    .add_column(ColumnDef::new(GymSet::RestSeconds).integer().null())
+                    .add_column(ColumnDef::new(GymSet::DurationSeconds).integer().null())
+                    .add_column(
+                        ColumnDef::new(GymSet::DistanceMeters)
+                            .decimal_len(8, 2)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Timed holds and cardio are done without repetitions
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE gym_set DROP CONSTRAINT gym_set_repetitions_check;
+                ALTER TABLE gym_set ADD CONSTRAINT gym_set_repetitions_check CHECK (repetitions >= 0);",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DELETE FROM gym_set WHERE repetitions = 0;
+                ALTER TABLE gym_set DROP CONSTRAINT gym_set_repetitions_check;
+                ALTER TABLE gym_set ADD CONSTRAINT gym_set_repetitions_check CHECK (repetitions >= 1);",
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GymSet::Table)
+                    .drop_column(GymSet::SetType)
+                    .drop_column(GymSet::Rpe)
+                    .drop_column(GymSet::Rir)
+                    .drop_column(GymSet::RestSeconds)
+                    .drop_column(GymSet::DurationSeconds)
+                    .drop_column(GymSet::DistanceMeters)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(&format!("DROP TYPE IF EXISTS {};", SET_TYPE_ENUM))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum GymSet {
+    Table,
+    SetType,
+    Rpe,
+    Rir,
+    RestSeconds,
+    DurationSeconds,
+    DistanceMeters,
+}
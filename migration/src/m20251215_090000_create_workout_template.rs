@@ -0,0 +1,222 @@
+use crate::helpers::{create_updated_at_trigger, drop_updated_at_trigger};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+static TEMPLATE_TABLE_NAME: &str = "workout_template";
+static TEMPLATE_EXERCISE_TABLE_NAME: &str = "workout_template_exercise";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WorkoutTemplate::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WorkoutTemplate::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()")),
+                    )
+                    .col(ColumnDef::new(WorkoutTemplate::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(WorkoutTemplate::Name)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WorkoutTemplate::Description).text().null())
+                    .col(
+                        ColumnDef::new(WorkoutTemplate::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(WorkoutTemplate::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_workout_template_user_id")
+                            .from(WorkoutTemplate::Table, WorkoutTemplate::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_workout_template_user_id")
+                    .table(WorkoutTemplate::Table)
+                    .col(WorkoutTemplate::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        create_updated_at_trigger(manager, TEMPLATE_TABLE_NAME).await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WorkoutTemplateExercise::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WorkoutTemplateExercise::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()")),
+                    )
+                    .col(
+                        ColumnDef::new(WorkoutTemplateExercise::TemplateId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WorkoutTemplateExercise::ExerciseId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WorkoutTemplateExercise::Position)
+                            .integer()
+                            .not_null()
+                            .check(
+                                Expr::col(WorkoutTemplateExercise::Position).gte(Expr::value(1)),
+                            ),
+                    )
+                    .col(
+                        ColumnDef::new(WorkoutTemplateExercise::TargetSets)
+                            .integer()
+                            .not_null()
+                            .check(
+                                Expr::col(WorkoutTemplateExercise::TargetSets).gte(Expr::value(1)),
+                            ),
+                    )
+                    .col(
+                        ColumnDef::new(WorkoutTemplateExercise::TargetRepetitions)
+                            .integer()
+                            .not_null()
+                            .check(
+                                Expr::col(WorkoutTemplateExercise::TargetRepetitions)
+                                    .gte(Expr::value(0)),
+                            ),
+                    )
+                    .col(
+                        ColumnDef::new(WorkoutTemplateExercise::TargetWeightKg)
+                            .decimal_len(6, 2)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(WorkoutTemplateExercise::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(WorkoutTemplateExercise::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_workout_template_exercise_template_id")
+                            .from(
+                                WorkoutTemplateExercise::Table,
+                                WorkoutTemplateExercise::TemplateId,
+                            )
+                            .to(WorkoutTemplate::Table, WorkoutTemplate::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_workout_template_exercise_exercise_id")
+                            .from(
+                                WorkoutTemplateExercise::Table,
+                                WorkoutTemplateExercise::ExerciseId,
+                            )
+                            .to(GymExercise::Table, GymExercise::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_workout_template_exercise_template_id")
+                    .table(WorkoutTemplateExercise::Table)
+                    .col(WorkoutTemplateExercise::TemplateId)
+                    .to_owned(),
+            )
+            .await?;
+
+        create_updated_at_trigger(manager, TEMPLATE_EXERCISE_TABLE_NAME).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_updated_at_trigger(manager, TEMPLATE_EXERCISE_TABLE_NAME).await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(WorkoutTemplateExercise::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        drop_updated_at_trigger(manager, TEMPLATE_TABLE_NAME).await?;
+        manager
+            .drop_table(Table::drop().table(WorkoutTemplate::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WorkoutTemplate {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WorkoutTemplateExercise {
+    Table,
+    Id,
+    TemplateId,
+    ExerciseId,
+    Position,
+    TargetSets,
+    TargetRepetitions,
+    TargetWeightKg,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum GymExercise {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
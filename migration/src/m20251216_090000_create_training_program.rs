@@ -0,0 +1,226 @@
+use crate::helpers::{create_updated_at_trigger, drop_updated_at_trigger};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+static PROGRAM_TABLE_NAME: &str = "training_program";
+static PROGRAM_DAY_TABLE_NAME: &str = "training_program_day";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TrainingProgram::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TrainingProgram::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()")),
+                    )
+                    .col(ColumnDef::new(TrainingProgram::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(TrainingProgram::Name)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TrainingProgram::Description).text().null())
+                    .col(ColumnDef::new(TrainingProgram::StartDate).date().not_null())
+                    .col(
+                        ColumnDef::new(TrainingProgram::Weeks)
+                            .integer()
+                            .not_null()
+                            .check(Expr::col(TrainingProgram::Weeks).gte(Expr::value(1))),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgram::IncrementKg)
+                            .decimal_len(6, 2)
+                            .not_null()
+                            .default(2.5),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgram::FailuresBeforeDeload)
+                            .integer()
+                            .not_null()
+                            .default(3),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgram::DeloadPercent)
+                            .integer()
+                            .not_null()
+                            .default(10),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgram::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgram::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_training_program_user_id")
+                            .from(TrainingProgram::Table, TrainingProgram::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_training_program_user_id")
+                    .table(TrainingProgram::Table)
+                    .col(TrainingProgram::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        create_updated_at_trigger(manager, PROGRAM_TABLE_NAME).await?;
+
+        // A day without week comes back every week of the program
+        manager
+            .create_table(
+                Table::create()
+                    .table(TrainingProgramDay::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TrainingProgramDay::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()")),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgramDay::ProgramId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgramDay::TemplateId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgramDay::Week)
+                            .integer()
+                            .null()
+                            .check(Expr::col(TrainingProgramDay::Week).gte(Expr::value(1))),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgramDay::Weekday)
+                            .integer()
+                            .not_null()
+                            .check(
+                                Expr::col(TrainingProgramDay::Weekday)
+                                    .between(Expr::value(1), Expr::value(7)),
+                            ),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgramDay::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(TrainingProgramDay::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_training_program_day_program_id")
+                            .from(TrainingProgramDay::Table, TrainingProgramDay::ProgramId)
+                            .to(TrainingProgram::Table, TrainingProgram::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_training_program_day_template_id")
+                            .from(TrainingProgramDay::Table, TrainingProgramDay::TemplateId)
+                            .to(WorkoutTemplate::Table, WorkoutTemplate::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_training_program_day_program_id")
+                    .table(TrainingProgramDay::Table)
+                    .col(TrainingProgramDay::ProgramId)
+                    .to_owned(),
+            )
+            .await?;
+
+        create_updated_at_trigger(manager, PROGRAM_DAY_TABLE_NAME).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_updated_at_trigger(manager, PROGRAM_DAY_TABLE_NAME).await?;
+        manager
+            .drop_table(Table::drop().table(TrainingProgramDay::Table).to_owned())
+            .await?;
+
+        drop_updated_at_trigger(manager, PROGRAM_TABLE_NAME).await?;
+        manager
+            .drop_table(Table::drop().table(TrainingProgram::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TrainingProgram {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Description,
+    StartDate,
+    Weeks,
+    IncrementKg,
+    FailuresBeforeDeload,
+    DeloadPercent,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TrainingProgramDay {
+    Table,
+    Id,
+    ProgramId,
+    TemplateId,
+    Week,
+    Weekday,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WorkoutTemplate {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,122 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GymSession::Table)
+                    .add_column(
+                        ColumnDef::new(GymSession::StartedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(GymSession::EndedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .add_column(ColumnDef::new(GymSession::Notes).text().null())
+                    .add_column(
+                        ColumnDef::new(GymSession::PerceivedDifficulty)
+                            .integer()
+                            .null()
+                            .check(
+                                Expr::col(GymSession::PerceivedDifficulty)
+                                    .between(Expr::value(1), Expr::value(10)),
+                            ),
+                    )
+                    .add_column(ColumnDef::new(GymSession::UserWeightId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE gym_session ADD CONSTRAINT gym_session_ended_after_started_check CHECK (ended_at >= started_at);",
+            )
+            .await?;
+
+        // Deleting the weight only unlinks it
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_gym_session_user_weight_id")
+                    .from(GymSession::Table, GymSession::UserWeightId)
+                    .to(UserWeight::Table, UserWeight::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Several sessions can be logged the same day
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_gym_session_user_id_date")
+                    .table(GymSession::Table)
+                    .col(GymSession::UserId)
+                    .col(GymSession::Date)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_gym_session_user_id_date")
+                    .table(GymSession::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_gym_session_user_weight_id")
+                    .table(GymSession::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GymSession::Table)
+                    .drop_column(GymSession::StartedAt)
+                    .drop_column(GymSession::EndedAt)
+                    .drop_column(GymSession::Notes)
+                    .drop_column(GymSession::PerceivedDifficulty)
+                    .drop_column(GymSession::UserWeightId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GymSession {
+    Table,
+    UserId,
+    Date,
+    StartedAt,
+    EndedAt,
+    Notes,
+    PerceivedDifficulty,
+    UserWeightId,
+}
+
+#[derive(DeriveIden)]
+enum UserWeight {
+    Table,
+    Id,
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Exercises of a session with a repeated number are renumbered in their current order
+        db.execute_unprepared(
+            "UPDATE gym_set SET set_number = numbered.set_number
+            FROM (
+                SELECT id, ROW_NUMBER() OVER (
+                    PARTITION BY session_id, exercise_id
+                    ORDER BY set_number, created_at, id
+                )::integer AS set_number
+                FROM gym_set
+                WHERE (session_id, exercise_id) IN (
+                    SELECT session_id, exercise_id FROM gym_set
+                    GROUP BY session_id, exercise_id, set_number
+                    HAVING COUNT(*) > 1
+                )
+            ) AS numbered
+            WHERE gym_set.id = numbered.id;",
+        )
+        .await?;
+
+        // Deferred so that a transaction can swap the numbers of two sets
+        db.execute_unprepared(
+            "ALTER TABLE gym_set ADD CONSTRAINT gym_set_session_exercise_number_key
+            UNIQUE (session_id, exercise_id, set_number) DEFERRABLE INITIALLY DEFERRED;",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE gym_set DROP CONSTRAINT IF EXISTS gym_set_session_exercise_number_key;",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+use std::io::{Cursor, Write};
+
+use chrono::{Duration, Utc};
+use entities::{
+    EmailType, Job, JobDataExport, JobEmail, JobEmailDataExport, TaskType, data_export_key,
+    data_export_pending_key, data_import, email_preferences, food_item, gym_exercise,
+    gym_personal_record, gym_session, gym_set, meal, meal_item, training_program,
+    training_program_day, user_additional_infos, user_login_device, user_two_factor,
+    user_watch_permissions, user_weight, users, watch_request, workout_template,
+    workout_template_exercise,
+};
+use redis::AsyncCommands;
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::{info, warn};
+use uuid::Uuid;
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+use crate::worker_main::state::WorkerState;
+
+/// One entity of the export, written both as `<name>.json` and `<name>.csv`
+struct ExportFile {
+    name: &'static str,
+    rows: Vec<Map<String, Value>>,
+}
+
+pub async fn handle_data_export_job(
+    worker_state: WorkerState,
+    data: JobDataExport,
+) -> anyhow::Result<bool> {
+    info!("Handling data export for user: {}", data.user_id);
+
+    let result = export_user_data(&worker_state, &data.user_id).await;
+
+    // Whatever happened, the user can ask for a new export
+    let mut redis = worker_state.redis.clone();
+    if let Err(err) = redis
+        .del::<_, ()>(data_export_pending_key(&data.user_id))
+        .await
+    {
+        warn!("Failed to clear the pending data export: {}", err);
+    }
+
+    result
+}
+
+async fn export_user_data(worker_state: &WorkerState, user_id: &Uuid) -> anyhow::Result<bool> {
+    let Some(user) = users::Entity::find_by_id(*user_id)
+        .one(&worker_state.db)
+        .await?
+    else {
+        warn!("User {} not found, nothing to export", user_id);
+        return Ok(false);
+    };
+
+    let archive = build_user_archive(&worker_state.db, user.clone()).await?;
+
+    let token = Uuid::new_v4().simple().to_string();
+    let valid_for = Duration::hours(worker_state.data_export.link_valid_hours.into());
+    let mut redis = worker_state.redis.clone();
+    redis
+        .set_ex::<_, _, ()>(
+            data_export_key(user_id, &token),
+            archive,
+            valid_for.num_seconds() as u64,
+        )
+        .await?;
+
+    let job_email_data_export = JobEmailDataExport {
+        email: user.email,
+        username: user.username,
+        token,
+        expires_at: Utc::now() + valid_for,
+    };
+
+    let job_email = JobEmail {
+        email_type: EmailType::DataExport,
+        data: serde_json::to_value(job_email_data_export)?,
+    };
+
+    let job = Job {
+        task_type: TaskType::Email,
+        data: serde_json::to_value(job_email)?,
+    };
+
+    redis
+        .rpush::<_, _, ()>("jobs", serde_json::to_string(&job)?)
+        .await?;
+
+    info!("Data export of user {} is ready", user_id);
+    Ok(true)
+}
+
+/// ZIP of everything stored about the user, each entity as JSON and CSV
+pub async fn build_user_archive(
+    db: &DatabaseConnection,
+    user: users::Model,
+) -> anyhow::Result<Vec<u8>> {
+    let files = collect_user_data(db, user).await?;
+    build_archive(&files)
+}
+
+async fn collect_user_data(
+    db: &DatabaseConnection,
+    user: users::Model,
+) -> anyhow::Result<Vec<ExportFile>> {
+    let user_id = user.id;
+
+    let mut profile = to_rows([user])?;
+    for row in &mut profile {
+        row.remove("password_hash");
+    }
+
+    let additional_infos = user_additional_infos::Entity::find_by_id(user_id)
+        .all(db)
+        .await?;
+
+    let weights = user_weight::Entity::find()
+        .filter(user_weight::Column::UserId.eq(user_id))
+        .order_by_asc(user_weight::Column::RecordedAt)
+        .all(db)
+        .await?;
+
+    let meals = meal::Entity::find()
+        .filter(meal::Column::UserId.eq(user_id))
+        .order_by_asc(meal::Column::Date)
+        .all(db)
+        .await?;
+    let meal_ids: Vec<Uuid> = meals.iter().map(|meal| meal.id).collect();
+
+    // Each item carries the details of its food, prefixed with `food_`
+    let meal_items = meal_item::Entity::find()
+        .filter(meal_item::Column::MealId.is_in(meal_ids))
+        .order_by_asc(meal_item::Column::CreatedAt)
+        .find_also_related(food_item::Entity)
+        .all(db)
+        .await?;
+    let mut meal_item_rows = Vec::with_capacity(meal_items.len());
+    for (item, food) in meal_items {
+        let mut row = to_row(item)?;
+        if let Some(food) = food {
+            for (key, value) in to_row(food)? {
+                if key != "id" {
+                    row.insert(format!("food_{key}"), value);
+                }
+            }
+        }
+        meal_item_rows.push(row);
+    }
+
+    let food_items = food_item::Entity::find()
+        .filter(food_item::Column::AddedBy.eq(user_id))
+        .order_by_asc(food_item::Column::AddedAt)
+        .all(db)
+        .await?;
+
+    let gym_exercises = gym_exercise::Entity::find()
+        .filter(gym_exercise::Column::AddedBy.eq(user_id))
+        .order_by_asc(gym_exercise::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let gym_sessions = gym_session::Entity::find()
+        .filter(gym_session::Column::UserId.eq(user_id))
+        .order_by_asc(gym_session::Column::Date)
+        .all(db)
+        .await?;
+    let session_ids: Vec<Uuid> = gym_sessions.iter().map(|session| session.id).collect();
+
+    let gym_sets = gym_set::Entity::find()
+        .filter(gym_set::Column::SessionId.is_in(session_ids))
+        .order_by_asc(gym_set::Column::SessionId)
+        .order_by_asc(gym_set::Column::SetNumber)
+        .find_also_related(gym_exercise::Entity)
+        .all(db)
+        .await?;
+    let mut gym_set_rows = Vec::with_capacity(gym_sets.len());
+    for (set, exercise) in gym_sets {
+        let mut row = to_row(set)?;
+        row.insert(
+            "exercise_name".to_string(),
+            exercise.map(|exercise| exercise.name).into(),
+        );
+        gym_set_rows.push(row);
+    }
+
+    let workout_templates = workout_template::Entity::find()
+        .filter(workout_template::Column::UserId.eq(user_id))
+        .order_by_asc(workout_template::Column::CreatedAt)
+        .all(db)
+        .await?;
+    let template_ids: Vec<Uuid> = workout_templates
+        .iter()
+        .map(|template| template.id)
+        .collect();
+    let workout_template_exercises = workout_template_exercise::Entity::find()
+        .filter(workout_template_exercise::Column::TemplateId.is_in(template_ids))
+        .order_by_asc(workout_template_exercise::Column::TemplateId)
+        .order_by_asc(workout_template_exercise::Column::Position)
+        .all(db)
+        .await?;
+
+    let training_programs = training_program::Entity::find()
+        .filter(training_program::Column::UserId.eq(user_id))
+        .order_by_asc(training_program::Column::CreatedAt)
+        .all(db)
+        .await?;
+    let program_ids: Vec<Uuid> = training_programs.iter().map(|program| program.id).collect();
+    let training_program_days = training_program_day::Entity::find()
+        .filter(training_program_day::Column::ProgramId.is_in(program_ids))
+        .order_by_asc(training_program_day::Column::ProgramId)
+        .order_by_asc(training_program_day::Column::Week)
+        .order_by_asc(training_program_day::Column::Weekday)
+        .all(db)
+        .await?;
+
+    let watch_permissions = user_watch_permissions::Entity::find()
+        .filter(
+            Condition::any()
+                .add(user_watch_permissions::Column::UserWatchedId.eq(user_id))
+                .add(user_watch_permissions::Column::UserWatchingId.eq(user_id)),
+        )
+        .order_by_asc(user_watch_permissions::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let watch_requests = watch_request::Entity::find()
+        .filter(
+            Condition::any()
+                .add(watch_request::Column::RequesterId.eq(user_id))
+                .add(watch_request::Column::TargetId.eq(user_id)),
+        )
+        .order_by_asc(watch_request::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let personal_records = gym_personal_record::Entity::find()
+        .filter(gym_personal_record::Column::UserId.eq(user_id))
+        .order_by_asc(gym_personal_record::Column::AchievedOn)
+        .all(db)
+        .await?;
+
+    let data_imports = data_import::Entity::find()
+        .filter(data_import::Column::UserId.eq(user_id))
+        .order_by_asc(data_import::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let login_devices = user_login_device::Entity::find()
+        .filter(user_login_device::Column::UserId.eq(user_id))
+        .order_by_asc(user_login_device::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    // Only whether it is enabled and since when, never the secret
+    let mut two_factor = to_rows(user_two_factor::Entity::find_by_id(user_id).all(db).await?)?;
+    for row in &mut two_factor {
+        row.remove("secret");
+        row.remove("last_used_step");
+    }
+
+    let email_preferences = email_preferences::Entity::find_by_id(user_id)
+        .all(db)
+        .await?;
+
+    Ok(vec![
+        ExportFile {
+            name: "profile",
+            rows: profile,
+        },
+        ExportFile {
+            name: "additional_infos",
+            rows: to_rows(additional_infos)?,
+        },
+        ExportFile {
+            name: "weights",
+            rows: to_rows(weights)?,
+        },
+        ExportFile {
+            name: "meals",
+            rows: to_rows(meals)?,
+        },
+        ExportFile {
+            name: "meal_items",
+            rows: meal_item_rows,
+        },
+        ExportFile {
+            name: "food_items",
+            rows: to_rows(food_items)?,
+        },
+        ExportFile {
+            name: "gym_exercises",
+            rows: to_rows(gym_exercises)?,
+        },
+        ExportFile {
+            name: "gym_sessions",
+            rows: to_rows(gym_sessions)?,
+        },
+        ExportFile {
+            name: "gym_sets",
+            rows: gym_set_rows,
+        },
+        ExportFile {
+            name: "workout_templates",
+            rows: to_rows(workout_templates)?,
+        },
+        ExportFile {
+            name: "workout_template_exercises",
+            rows: to_rows(workout_template_exercises)?,
+        },
+        ExportFile {
+            name: "training_programs",
+            rows: to_rows(training_programs)?,
+        },
+        ExportFile {
+            name: "training_program_days",
+            rows: to_rows(training_program_days)?,
+        },
+        ExportFile {
+            name: "watch_permissions",
+            rows: to_rows(watch_permissions)?,
+        },
+        ExportFile {
+            name: "watch_requests",
+            rows: to_rows(watch_requests)?,
+        },
+        ExportFile {
+            name: "gym_personal_records",
+            rows: to_rows(personal_records)?,
+        },
+        ExportFile {
+            name: "data_imports",
+            rows: to_rows(data_imports)?,
+        },
+        ExportFile {
+            name: "login_devices",
+            rows: to_rows(login_devices)?,
+        },
+        ExportFile {
+            name: "two_factor",
+            rows: two_factor,
+        },
+        ExportFile {
+            name: "email_preferences",
+            rows: to_rows(email_preferences)?,
+        },
+    ])
+}
+
+fn to_row<T: Serialize>(model: T) -> anyhow::Result<Map<String, Value>> {
+    match serde_json::to_value(model)? {
+        Value::Object(row) => Ok(row),
+        other => anyhow::bail!("Expected an object to export, got {other}"),
+    }
+}
+
+fn to_rows<T: Serialize>(
+    models: impl IntoIterator<Item = T>,
+) -> anyhow::Result<Vec<Map<String, Value>>> {
+    models.into_iter().map(to_row).collect()
+}
+
+fn build_archive(files: &[ExportFile]) -> anyhow::Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for file in files {
+        zip.start_file(format!("{}.json", file.name), options)?;
+        zip.write_all(&serde_json::to_vec_pretty(&file.rows)?)?;
+
+        zip.start_file(format!("{}.csv", file.name), options)?;
+        zip.write_all(&to_csv(&file.rows)?)?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// The columns are the keys of the first row, every row of an entity has the same ones
+fn to_csv(rows: &[Map<String, Value>]) -> anyhow::Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    if let Some(first) = rows.first() {
+        let headers: Vec<&String> = first.keys().collect();
+        writer.write_record(&headers)?;
+
+        for row in rows {
+            writer.write_record(headers.iter().map(|header| match row.get(*header) {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(value)) => value.clone(),
+                Some(value) => value.to_string(),
+            }))?;
+        }
+    }
+
+    Ok(writer.into_inner()?)
+}
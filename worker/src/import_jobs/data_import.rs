@@ -0,0 +1,569 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use chrono::{NaiveDate, Utc};
+use entities::{
+    JobDataImport, data_import, food_item, gym_exercise, gym_personal_record, gym_session, gym_set,
+    meal, meal_item,
+    sea_orm_active_enums::{ImportKindEnum, ImportStatusEnum, MealTypeEnum, VisibilityEnum},
+    user_weight,
+};
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, Condition, DatabaseTransaction, EntityTrait, QueryFilter, QuerySelect,
+    TransactionTrait,
+    prelude::Decimal,
+};
+use serde::Serialize;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    import_jobs::{
+        exercise_matching::{ExerciseMatcher, normalize},
+        rows::{
+            ImportFormat, MealRow, RowError, group_by_day, parse_gym_set_rows, parse_meal_rows,
+            parse_weight_rows,
+        },
+    },
+    worker_main::state::WorkerState,
+};
+
+/// Rows inserted per statement
+const INSERT_BATCH_SIZE: usize = 1000;
+
+#[derive(Debug, Default, Serialize)]
+pub struct ImportReport {
+    pub format: Option<ImportFormat>,
+    pub total_rows: usize,
+    pub imported_rows: usize,
+    /// Rows of the days already logged, importing the same export twice adds nothing
+    pub skipped_rows: usize,
+    pub skipped_dates: Vec<NaiveDate>,
+    pub created_sessions: usize,
+    pub created_exercises: Vec<String>,
+    pub matched_exercises: Vec<ExerciseMatch>,
+    pub created_meals: usize,
+    pub created_food_items: usize,
+    pub errors: Vec<RowError>,
+}
+
+/// An exercise of the file that was matched with a differently written existing one
+#[derive(Debug, Serialize)]
+pub struct ExerciseMatch {
+    pub name_in_file: String,
+    pub exercise_id: Uuid,
+    pub exercise: String,
+}
+
+pub async fn handle_data_import_job(
+    worker_state: WorkerState,
+    data: JobDataImport,
+) -> anyhow::Result<bool> {
+    info!("Handling data import: {}", data.import_id);
+
+    let Some(data_import) = data_import::Entity::find_by_id(data.import_id)
+        .one(&worker_state.db)
+        .await?
+    else {
+        warn!("Data import {} not found", data.import_id);
+        return Ok(false);
+    };
+
+    // A job delivered twice must not import the file twice
+    if data_import.status != ImportStatusEnum::Pending {
+        warn!(
+            "Data import {} is {:?}, skipping",
+            data_import.id, data_import.status
+        );
+        return Ok(false);
+    }
+
+    let mut processing: data_import::ActiveModel = data_import.clone().into();
+    processing.status = Set(ImportStatusEnum::Processing);
+    processing.update(&worker_state.db).await?;
+
+    let result = run_import(&worker_state, &data_import).await;
+
+    let mut finished: data_import::ActiveModel = data_import.clone().into();
+    finished.finished_at = Set(Some(Utc::now().into()));
+    match &result {
+        Ok(report) => {
+            finished.status = Set(ImportStatusEnum::Completed);
+            finished.report = Set(Some(serde_json::to_value(report)?));
+        }
+        Err(err) => {
+            finished.status = Set(ImportStatusEnum::Failed);
+            finished.report = Set(Some(json!({ "error": err.to_string() })));
+        }
+    }
+    finished.update(&worker_state.db).await?;
+
+    let report = result?;
+    info!(
+        "Data import {} done: {} of {} rows imported (dry run: {})",
+        data_import.id, report.imported_rows, report.total_rows, data_import.dry_run
+    );
+    Ok(true)
+}
+
+/// A dry run goes through the same inserts and rolls them back, so its report is
+/// exactly what applying it would do
+async fn run_import(
+    worker_state: &WorkerState,
+    data_import: &data_import::Model,
+) -> anyhow::Result<ImportReport> {
+    let txn = worker_state.db.begin().await?;
+
+    let report = match data_import.kind {
+        ImportKindEnum::Weights => {
+            import_weights(&txn, &data_import.user_id, &data_import.content).await?
+        }
+        ImportKindEnum::GymSets => {
+            import_gym_sets(&txn, &data_import.user_id, &data_import.content).await?
+        }
+        ImportKindEnum::Meals => {
+            import_meals(&txn, &data_import.user_id, &data_import.content).await?
+        }
+    };
+
+    if data_import.dry_run {
+        txn.rollback().await?;
+    } else {
+        txn.commit().await?;
+    }
+
+    Ok(report)
+}
+
+async fn import_weights(
+    txn: &DatabaseTransaction,
+    user_id: &Uuid,
+    content: &str,
+) -> anyhow::Result<ImportReport> {
+    let (rows, errors) = parse_weight_rows(content)?;
+    let mut report = ImportReport {
+        format: Some(ImportFormat::Generic),
+        total_rows: rows.len() + errors.len(),
+        errors,
+        ..Default::default()
+    };
+
+    // Only one weight can be recorded per day
+    let mut recorded_dates: HashSet<NaiveDate> = user_weight::Entity::find()
+        .select_only()
+        .column(user_weight::Column::RecordedAt)
+        .filter(user_weight::Column::UserId.eq(*user_id))
+        .into_tuple::<NaiveDate>()
+        .all(txn)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut weights = Vec::with_capacity(rows.len());
+    for row in rows {
+        if !recorded_dates.insert(row.date) {
+            report.errors.push(RowError {
+                line: row.line,
+                message: format!("A weight is already recorded for {}", row.date),
+            });
+            continue;
+        }
+
+        weights.push(user_weight::ActiveModel {
+            id: NotSet,
+            user_id: Set(*user_id),
+            weight_in_kg: Set(row.weight_in_kg),
+            recorded_at: Set(row.date),
+            created_at: NotSet,
+            updated_at: NotSet,
+        });
+    }
+
+    report.imported_rows = weights.len();
+    for batch in weights.chunks(INSERT_BATCH_SIZE) {
+        user_weight::Entity::insert_many(batch.to_vec())
+            .exec(txn)
+            .await?;
+    }
+
+    report.errors.sort_by_key(|error| error.line);
+    Ok(report)
+}
+
+async fn import_gym_sets(
+    txn: &DatabaseTransaction,
+    user_id: &Uuid,
+    content: &str,
+) -> anyhow::Result<ImportReport> {
+    let (format, rows, errors) = parse_gym_set_rows(content)?;
+    let mut report = ImportReport {
+        format: Some(format),
+        total_rows: rows.len() + errors.len(),
+        errors,
+        ..Default::default()
+    };
+
+    let logged_dates: HashSet<NaiveDate> = gym_session::Entity::find()
+        .select_only()
+        .column(gym_session::Column::Date)
+        .filter(gym_session::Column::UserId.eq(*user_id))
+        .into_tuple::<NaiveDate>()
+        .all(txn)
+        .await?
+        .into_iter()
+        .collect();
+
+    // Only match exercises the user could also pick by hand
+    let exercises = gym_exercise::Entity::find()
+        .filter(
+            Condition::any()
+                .add(gym_exercise::Column::Visibility.eq(VisibilityEnum::Public))
+                .add(gym_exercise::Column::AddedBy.eq(*user_id)),
+        )
+        .all(txn)
+        .await?;
+    let mut matcher = ExerciseMatcher::new(
+        exercises
+            .into_iter()
+            .map(|exercise| (exercise.id, exercise.name)),
+    );
+    let mut resolved: HashMap<String, Uuid> = HashMap::new();
+    let mut imported_exercises: BTreeSet<Uuid> = BTreeSet::new();
+
+    let (days, logged_days) = group_by_day(rows, &logged_dates);
+    for (date, rows) in logged_days {
+        report.skipped_rows += rows.len();
+        report.skipped_dates.push(date);
+    }
+
+    for (date, rows) in days {
+        let mut sets = Vec::with_capacity(rows.len());
+        let mut used_numbers: HashSet<(Uuid, i32)> = HashSet::new();
+        let mut next_numbers: HashMap<Uuid, i32> = HashMap::new();
+
+        for row in rows {
+            let exercise_id = match resolved.get(&row.exercise) {
+                Some(exercise_id) => *exercise_id,
+                None => {
+                    let exercise_id =
+                        resolve_exercise(txn, user_id, &row.exercise, &mut matcher, &mut report)
+                            .await?;
+                    resolved.insert(row.exercise.clone(), exercise_id);
+                    exercise_id
+                }
+            };
+
+            let next_number = next_numbers.entry(exercise_id).or_insert(1);
+            let set_number = row.set_number.unwrap_or(*next_number);
+            if !used_numbers.insert((exercise_id, set_number)) {
+                report.errors.push(RowError {
+                    line: row.line,
+                    message: format!(
+                        "Set {} of {} is already in the file for {}",
+                        set_number, row.exercise, date
+                    ),
+                });
+                continue;
+            }
+            *next_number = (*next_number).max(set_number + 1);
+
+            imported_exercises.insert(exercise_id);
+            sets.push((exercise_id, set_number, row));
+        }
+
+        if sets.is_empty() {
+            continue;
+        }
+
+        let session = gym_session::ActiveModel {
+            id: NotSet,
+            user_id: Set(*user_id),
+            date: Set(date),
+            started_at: NotSet,
+            ended_at: NotSet,
+            notes: NotSet,
+            perceived_difficulty: NotSet,
+            user_weight_id: NotSet,
+            created_at: NotSet,
+            updated_at: NotSet,
+        }
+        .insert(txn)
+        .await?;
+        report.created_sessions += 1;
+        report.imported_rows += sets.len();
+
+        let sets: Vec<gym_set::ActiveModel> = sets
+            .into_iter()
+            .map(|(exercise_id, set_number, row)| gym_set::ActiveModel {
+                id: NotSet,
+                session_id: Set(session.id),
+                exercise_id: Set(exercise_id),
+                set_number: Set(set_number),
+                repetitions: Set(row.repetitions),
+                weight_kg: Set(row.weight_kg),
+                set_type: Set(row.set_type),
+                rpe: NotSet,
+                rir: NotSet,
+                rest_seconds: NotSet,
+                duration_seconds: NotSet,
+                distance_meters: NotSet,
+                created_at: NotSet,
+                updated_at: NotSet,
+            })
+            .collect();
+        for batch in sets.chunks(INSERT_BATCH_SIZE) {
+            gym_set::Entity::insert_many(batch.to_vec())
+                .exec(txn)
+                .await?;
+        }
+    }
+
+    // Imported sets may beat the records of the sets logged by hand
+    for exercise_id in imported_exercises {
+        gym_personal_record::Entity::recompute(txn, *user_id, exercise_id).await?;
+    }
+
+    report.errors.sort_by_key(|error| error.line);
+    Ok(report)
+}
+
+/// The known exercise closest to the name, created for the user when there is none
+async fn resolve_exercise(
+    txn: &DatabaseTransaction,
+    user_id: &Uuid,
+    name: &str,
+    matcher: &mut ExerciseMatcher,
+    report: &mut ImportReport,
+) -> anyhow::Result<Uuid> {
+    if let Some((exercise_id, exercise)) = matcher.find(name) {
+        if normalize(exercise) != normalize(name) {
+            report.matched_exercises.push(ExerciseMatch {
+                name_in_file: name.to_string(),
+                exercise_id,
+                exercise: exercise.to_string(),
+            });
+        }
+        return Ok(exercise_id);
+    }
+
+    let exercise = gym_exercise::ActiveModel {
+        id: NotSet,
+        name: Set(name.to_string()),
+        description: Set(None),
+        added_by: Set(*user_id),
+        created_at: NotSet,
+        updated_at: NotSet,
+        equipment: Set(None),
+        movement_pattern: Set(None),
+        is_unilateral: Set(false),
+        visibility: Set(VisibilityEnum::Private),
+    }
+    .insert(txn)
+    .await?;
+
+    report.created_exercises.push(exercise.name.clone());
+    matcher.add(exercise.id, exercise.name);
+    Ok(exercise.id)
+}
+
+/// A food of the user with the same name and nutrition is reused
+#[derive(PartialEq, Eq, Hash)]
+struct FoodKey {
+    name: String,
+    calories_per100g: i32,
+    protein_per100g: i32,
+    carbs_per100g: i32,
+    fat_per100g: i32,
+}
+
+impl FoodKey {
+    fn new(name: &str, per100g: [i32; 4]) -> Self {
+        let [
+            calories_per100g,
+            protein_per100g,
+            carbs_per100g,
+            fat_per100g,
+        ] = per100g;
+        Self {
+            name: normalize(name),
+            calories_per100g,
+            protein_per100g,
+            carbs_per100g,
+            fat_per100g,
+        }
+    }
+}
+
+async fn import_meals(
+    txn: &DatabaseTransaction,
+    user_id: &Uuid,
+    content: &str,
+) -> anyhow::Result<ImportReport> {
+    let (format, rows, errors) = parse_meal_rows(content)?;
+    let mut report = ImportReport {
+        format: Some(format),
+        total_rows: rows.len() + errors.len(),
+        errors,
+        ..Default::default()
+    };
+
+    let logged_meals: HashSet<(NaiveDate, String)> = meal::Entity::find()
+        .filter(meal::Column::UserId.eq(*user_id))
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|meal| (meal.date, meal.kind.to_value()))
+        .collect();
+
+    let mut foods: HashMap<FoodKey, Uuid> = food_item::Entity::find()
+        .filter(food_item::Column::AddedBy.eq(*user_id))
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|food| {
+            let per100g = [
+                food.calories_per100g,
+                food.protein_per100g,
+                food.carbs_per100g,
+                food.fat_per100g,
+            ];
+            (FoodKey::new(&food.name, per100g), food.id)
+        })
+        .collect();
+
+    let mut rows_by_meal: BTreeMap<(NaiveDate, String), (MealTypeEnum, Vec<MealRow>)> =
+        BTreeMap::new();
+    for row in rows {
+        rows_by_meal
+            .entry((row.date, row.kind.to_value()))
+            .or_insert_with(|| (row.kind.clone(), Vec::new()))
+            .1
+            .push(row);
+    }
+
+    let mut skipped_dates = BTreeSet::new();
+    for ((date, kind_value), (kind, rows)) in rows_by_meal {
+        if logged_meals.contains(&(date, kind_value)) {
+            report.skipped_rows += rows.len();
+            skipped_dates.insert(date);
+            continue;
+        }
+
+        let mut items = Vec::with_capacity(rows.len());
+        for row in rows {
+            let (name, description, quantity_in_grams) = match &row.food {
+                Some(food) => (food.name.clone(), None, food.quantity_in_grams),
+                // The totals of a meal become a food eaten 100 g of
+                None => (
+                    format!("{:?} {}", kind, date),
+                    Some(format!("Meal totals imported from {:?}", format)),
+                    100,
+                ),
+            };
+
+            let per100g = match per_100g(&row, quantity_in_grams) {
+                Ok(per100g) => per100g,
+                Err(message) => {
+                    report.errors.push(RowError {
+                        line: row.line,
+                        message,
+                    });
+                    continue;
+                }
+            };
+
+            let key = FoodKey::new(&name, per100g);
+            let food_item_id = match foods.get(&key) {
+                Some(food_item_id) => *food_item_id,
+                None => {
+                    let [calories, protein, carbs, fat] = per100g;
+                    let food = food_item::ActiveModel {
+                        id: NotSet,
+                        name: Set(name),
+                        description: Set(description),
+                        scan_code: Set(None),
+                        calories_per100g: Set(calories),
+                        protein_per100g: Set(protein),
+                        carbs_per100g: Set(carbs),
+                        fat_per100g: Set(fat),
+                        added_by: Set(*user_id),
+                        updated_at: NotSet,
+                        added_at: NotSet,
+                        visibility: Set(VisibilityEnum::Private),
+                    }
+                    .insert(txn)
+                    .await?;
+                    report.created_food_items += 1;
+                    foods.insert(key, food.id);
+                    food.id
+                }
+            };
+
+            items.push((food_item_id, quantity_in_grams));
+        }
+
+        if items.is_empty() {
+            continue;
+        }
+
+        let meal = meal::ActiveModel {
+            id: NotSet,
+            user_id: Set(*user_id),
+            kind: Set(kind),
+            date: Set(date),
+            description: Set(None),
+            created_at: NotSet,
+            updated_at: NotSet,
+        }
+        .insert(txn)
+        .await?;
+        report.created_meals += 1;
+        report.imported_rows += items.len();
+
+        let items: Vec<meal_item::ActiveModel> = items
+            .into_iter()
+            .map(|(food_item_id, quantity_in_grams)| meal_item::ActiveModel {
+                id: NotSet,
+                meal_id: Set(meal.id),
+                food_item_id: Set(food_item_id),
+                quantity_in_grams: Set(quantity_in_grams),
+                updated_at: NotSet,
+                created_at: NotSet,
+            })
+            .collect();
+        for batch in items.chunks(INSERT_BATCH_SIZE) {
+            meal_item::Entity::insert_many(batch.to_vec())
+                .exec(txn)
+                .await?;
+        }
+    }
+
+    report.skipped_dates = skipped_dates.into_iter().collect();
+    report.errors.sort_by_key(|error| error.line);
+    Ok(report)
+}
+
+/// Calories, protein, carbs and fat per 100 g, within the limits of a food item
+fn per_100g(row: &MealRow, quantity_in_grams: i32) -> Result<[i32; 4], String> {
+    let per100g = |amount: Decimal, max: i32, name: &str| -> Result<i32, String> {
+        let value = (amount * Decimal::ONE_HUNDRED / Decimal::from(quantity_in_grams))
+            .round()
+            .try_into()
+            .unwrap_or(i32::MAX);
+        if value > max {
+            return Err(format!(
+                "The {name} must be at most {max} per 100 g, got {value}"
+            ));
+        }
+        Ok(value)
+    };
+
+    Ok([
+        per100g(row.calories, 10000, "calories")?,
+        per100g(row.protein, 1000, "protein")?,
+        per100g(row.carbs, 1000, "carbs")?,
+        per100g(row.fat, 1000, "fat")?,
+    ])
+}
@@ -0,0 +1,134 @@
+use uuid::Uuid;
+
+/// Names closer than this are considered the same exercise, `Bench press` and
+/// `Bench-Press` match exactly once normalized, `Benchpress` only fuzzily
+const MIN_SIMILARITY: f64 = 0.8;
+
+struct Candidate {
+    normalized: String,
+    id: Uuid,
+    name: String,
+}
+
+/// Matches the exercise names of an import with the known exercises
+pub struct ExerciseMatcher {
+    candidates: Vec<Candidate>,
+}
+
+impl ExerciseMatcher {
+    pub fn new(exercises: impl IntoIterator<Item = (Uuid, String)>) -> Self {
+        let mut matcher = Self {
+            candidates: Vec::new(),
+        };
+        for (id, name) in exercises {
+            matcher.add(id, name);
+        }
+        matcher
+    }
+
+    pub fn add(&mut self, id: Uuid, name: String) {
+        self.candidates.push(Candidate {
+            normalized: normalize(&name),
+            id,
+            name,
+        });
+    }
+
+    /// The exercise with the same normalized name, otherwise the most similar one
+    pub fn find(&self, name: &str) -> Option<(Uuid, &str)> {
+        let normalized = normalize(name);
+        if normalized.is_empty() {
+            return None;
+        }
+
+        if let Some(candidate) = self
+            .candidates
+            .iter()
+            .find(|candidate| candidate.normalized == normalized)
+        {
+            return Some((candidate.id, &candidate.name));
+        }
+
+        self.candidates
+            .iter()
+            .map(|candidate| (similarity(&normalized, &candidate.normalized), candidate))
+            .filter(|(score, _)| *score >= MIN_SIMILARITY)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, candidate)| (candidate.id, candidate.name.as_str()))
+    }
+}
+
+/// Lowercase words separated by a single space
+pub fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 1.0 for the same names, 0.0 for names without anything in common
+fn similarity(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f64 / longest as f64
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_exercise() {
+        let bench = Uuid::new_v4();
+        let curl = Uuid::new_v4();
+        let squat = Uuid::new_v4();
+        let row = Uuid::new_v4();
+        let matcher = ExerciseMatcher::new([
+            (bench, "Bench Press".to_string()),
+            (curl, "Curl".to_string()),
+            (squat, "Squat".to_string()),
+            (row, "Row".to_string()),
+        ]);
+
+        assert_eq!(matcher.find("bench-press"), Some((bench, "Bench Press")));
+        assert_eq!(matcher.find("Benchpress"), Some((bench, "Bench Press")));
+        assert_eq!(matcher.find("Squats"), Some((squat, "Squat")));
+        assert_eq!(matcher.find("  "), None);
+    }
+
+    #[test]
+    fn test_min_similarity() {
+        // One letter out of five is right on the threshold, one out of four is too much
+        assert_eq!(similarity("curls", "curl"), MIN_SIMILARITY);
+        assert!(similarity("rows", "row") < MIN_SIMILARITY);
+
+        let curl = Uuid::new_v4();
+        let matcher = ExerciseMatcher::new([
+            (curl, "Curl".to_string()),
+            (Uuid::new_v4(), "Row".to_string()),
+        ]);
+        assert_eq!(matcher.find("Curls"), Some((curl, "Curl")));
+        assert_eq!(matcher.find("Rows"), None);
+        assert_eq!(matcher.find("Bench"), None);
+    }
+}
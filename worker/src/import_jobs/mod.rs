@@ -0,0 +1,3 @@
+pub mod data_import;
+pub mod exercise_matching;
+pub mod rows;
@@ -0,0 +1,657 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    str::FromStr,
+};
+
+use chrono::NaiveDate;
+use csv::StringRecord;
+use entities::sea_orm_active_enums::{MealTypeEnum, SetTypeEnum};
+use sea_orm::prelude::Decimal;
+use serde::Serialize;
+
+/// A line of the file that was skipped, `line` counts the header as line 1
+#[derive(Debug, Clone, Serialize)]
+pub struct RowError {
+    pub line: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct WeightRow {
+    pub line: u64,
+    pub date: NaiveDate,
+    pub weight_in_kg: Decimal,
+}
+
+#[derive(Debug, Clone)]
+pub struct GymSetRow {
+    pub line: u64,
+    pub date: NaiveDate,
+    pub exercise: String,
+    /// Numbered in the order of the file when missing
+    pub set_number: Option<i32>,
+    pub repetitions: i32,
+    pub weight_kg: Decimal,
+    /// Normal when the file does not say
+    pub set_type: SetTypeEnum,
+}
+
+/// What the rows of a meal are, the amounts are for the quantity eaten
+#[derive(Debug, Clone)]
+pub struct MealRow {
+    pub line: u64,
+    pub date: NaiveDate,
+    pub kind: MealTypeEnum,
+    /// Files without foods only have the totals of each meal
+    pub food: Option<FoodRow>,
+    pub calories: Decimal,
+    pub protein: Decimal,
+    pub carbs: Decimal,
+    pub fat: Decimal,
+}
+
+#[derive(Debug, Clone)]
+pub struct FoodRow {
+    pub name: String,
+    pub quantity_in_grams: i32,
+}
+
+/// The application a file was exported from, found from its columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ImportFormat {
+    Generic,
+    Strong,
+    Hevy,
+    MyFitnessPal,
+}
+
+const DATE_COLUMNS: &[&str] = &["date", "day", "starttime"];
+const WEIGHT_COLUMNS: &[&str] = &["weight", "weightkg", "weightinkg", "kg"];
+const WEIGHT_LBS_COLUMNS: &[&str] = &["weightlbs", "weightinlbs", "lbs"];
+const WEIGHT_UNIT_COLUMNS: &[&str] = &["weightunit", "unit"];
+const EXERCISE_COLUMNS: &[&str] = &["exercise", "exercisename", "exercisetitle"];
+const SET_NUMBER_COLUMNS: &[&str] = &["set", "setnumber", "setorder", "setindex"];
+const REPETITIONS_COLUMNS: &[&str] = &["reps", "repetitions"];
+const SET_TYPE_COLUMNS: &[&str] = &["settype"];
+const MEAL_COLUMNS: &[&str] = &["meal", "mealtype", "mealname"];
+const FOOD_COLUMNS: &[&str] = &["food", "foodname", "item"];
+const QUANTITY_COLUMNS: &[&str] = &["grams", "quantity", "quantityg", "quantityingrams"];
+const CALORIES_COLUMNS: &[&str] = &["calories", "kcal", "energykcal"];
+const PROTEIN_COLUMNS: &[&str] = &["protein", "proteing"];
+const CARBS_COLUMNS: &[&str] = &["carbs", "carbsg", "carbohydrates", "carbohydratesg"];
+const FAT_COLUMNS: &[&str] = &["fat", "fatg"];
+
+const KG_PER_LB: Decimal = Decimal::from_parts(45359237, 0, 0, false, 8);
+
+/// Columns of a file, found by their header whatever the order
+struct Columns {
+    headers: Vec<String>,
+}
+
+impl Columns {
+    fn new(headers: &StringRecord) -> Self {
+        Self {
+            headers: headers.iter().map(normalize_header).collect(),
+        }
+    }
+
+    fn has(&self, header: &str) -> bool {
+        self.headers.iter().any(|column| column == header)
+    }
+
+    fn find(&self, aliases: &[&str]) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|header| aliases.contains(&header.as_str()))
+    }
+
+    fn require(&self, aliases: &[&str]) -> anyhow::Result<usize> {
+        self.find(aliases).ok_or_else(|| {
+            anyhow::anyhow!("Missing column, expected one of: {}", aliases.join(", "))
+        })
+    }
+}
+
+/// `Weight (kg)` and `weight_kg` are the same column
+fn normalize_header(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Spreadsheets using a decimal comma export with semicolons
+fn reader(content: &str) -> csv::Reader<&[u8]> {
+    let header = content.lines().next().unwrap_or_default();
+    let delimiter = if header.contains(';') && !header.contains(',') {
+        b';'
+    } else {
+        b','
+    };
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes())
+}
+
+fn line_of(record: &StringRecord) -> u64 {
+    record
+        .position()
+        .map(|position| position.line())
+        .unwrap_or(0)
+}
+
+fn field<'a>(record: &'a StringRecord, column: usize, name: &str) -> Result<&'a str, String> {
+    match record.get(column) {
+        Some(value) if !value.is_empty() => Ok(value),
+        _ => Err(format!("Missing {name}")),
+    }
+}
+
+/// Dates are accepted as `2024-01-31`, `31/01/2024`, `31.01.2024`, `2024/01/31` or
+/// `31 Jan 2024`, anything after the date like a time is ignored
+pub fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    let candidates = [
+        value,
+        value.split([',', 'T']).next().unwrap_or(value),
+        value.split(' ').next().unwrap_or(value),
+    ];
+    let formats = ["%Y-%m-%d", "%d/%m/%Y", "%d.%m.%Y", "%Y/%m/%d", "%d %b %Y"];
+    candidates
+        .iter()
+        .flat_map(|candidate| formats.iter().map(move |format| (candidate.trim(), format)))
+        .find_map(|(candidate, format)| NaiveDate::parse_from_str(candidate, format).ok())
+        .ok_or_else(|| format!("Invalid date: {value}"))
+}
+
+/// Both `82.5` and `82,5` are accepted
+pub fn parse_decimal(value: &str, name: &str) -> Result<Decimal, String> {
+    Decimal::from_str(&value.replace(',', "."))
+        .map(|decimal| decimal.round_dp(2))
+        .map_err(|_| format!("Invalid {name}: {value}"))
+}
+
+fn parse_integer(value: &str, name: &str) -> Result<i32, String> {
+    value
+        .parse::<i32>()
+        .map_err(|_| format!("Invalid {name}: {value}"))
+}
+
+pub fn parse_weight_rows(content: &str) -> anyhow::Result<(Vec<WeightRow>, Vec<RowError>)> {
+    let mut reader = reader(content);
+    let columns = Columns::new(reader.headers()?);
+    let date_column = columns.require(DATE_COLUMNS)?;
+    let weight_column = columns.require(WEIGHT_COLUMNS)?;
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = line_of(&record);
+        let row = (|| {
+            let date = parse_date(field(&record, date_column, "date")?)?;
+            let weight_in_kg = parse_decimal(field(&record, weight_column, "weight")?, "weight")?;
+            if weight_in_kg <= Decimal::ZERO || weight_in_kg >= Decimal::new(1000, 0) {
+                return Err(format!(
+                    "Weight must be between 0 and 1000 kg, got {weight_in_kg}"
+                ));
+            }
+            Ok(WeightRow {
+                line,
+                date,
+                weight_in_kg,
+            })
+        })();
+
+        match row {
+            Ok(row) => rows.push(row),
+            Err(message) => errors.push(RowError { line, message }),
+        }
+    }
+
+    Ok((rows, errors))
+}
+
+fn detect_gym_format(columns: &Columns) -> ImportFormat {
+    if columns.has("exercisetitle") && columns.has("setindex") {
+        ImportFormat::Hevy
+    } else if columns.has("workoutname") && columns.has("setorder") {
+        ImportFormat::Strong
+    } else {
+        ImportFormat::Generic
+    }
+}
+
+/// Generic files, Strong and Hevy workout exports, one set per row
+pub fn parse_gym_set_rows(
+    content: &str,
+) -> anyhow::Result<(ImportFormat, Vec<GymSetRow>, Vec<RowError>)> {
+    let mut reader = reader(content);
+    let columns = Columns::new(reader.headers()?);
+    let format = detect_gym_format(&columns);
+    let date_column = columns.require(DATE_COLUMNS)?;
+    let exercise_column = columns.require(EXERCISE_COLUMNS)?;
+    let set_number_column = columns.find(SET_NUMBER_COLUMNS);
+    let repetitions_column = columns.require(REPETITIONS_COLUMNS)?;
+    let weight_column = columns.find(WEIGHT_COLUMNS);
+    let weight_lbs_column = columns.find(WEIGHT_LBS_COLUMNS);
+    let weight_unit_column = columns.find(WEIGHT_UNIT_COLUMNS);
+    let set_type_column = columns.find(SET_TYPE_COLUMNS);
+    if weight_column.is_none() && weight_lbs_column.is_none() {
+        columns.require(WEIGHT_COLUMNS)?;
+    }
+    // Hevy counts the sets from 0
+    let set_number_offset = if format == ImportFormat::Hevy { 1 } else { 0 };
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = line_of(&record);
+        let row = (|| {
+            let date = parse_date(field(&record, date_column, "date")?)?;
+            let exercise = field(&record, exercise_column, "exercise")?.to_string();
+            if exercise.chars().count() > 255 {
+                return Err("Exercise name must be less than 255 characters".to_string());
+            }
+            let set_number = match set_number_column.and_then(|column| record.get(column)) {
+                Some(value) if !value.is_empty() => {
+                    Some(parse_integer(value, "set number")? + set_number_offset)
+                }
+                _ => None,
+            };
+            if set_number.is_some_and(|set_number| !(1..=100).contains(&set_number)) {
+                return Err("Set number must be between 1 and 100".to_string());
+            }
+            let repetitions = parse_integer(
+                field(&record, repetitions_column, "repetitions")?,
+                "repetitions",
+            )?;
+            if !(0..=1000).contains(&repetitions) {
+                return Err("Repetitions must be between 0 and 1000".to_string());
+            }
+            let weight_kg = parse_set_weight(
+                &record,
+                weight_column,
+                weight_lbs_column,
+                weight_unit_column,
+            )?;
+            if weight_kg < Decimal::ZERO || weight_kg >= Decimal::new(10000, 0) {
+                return Err("Weight must be between 0 and 10000 kg".to_string());
+            }
+            let set_type = match set_type_column.and_then(|column| record.get(column)) {
+                Some(value) if !value.is_empty() => parse_set_type(value)?,
+                _ => SetTypeEnum::Normal,
+            };
+            Ok(GymSetRow {
+                line,
+                date,
+                exercise,
+                set_number,
+                repetitions,
+                weight_kg,
+                set_type,
+            })
+        })();
+
+        match row {
+            Ok(row) => rows.push(row),
+            Err(message) => errors.push(RowError { line, message }),
+        }
+    }
+
+    Ok((format, rows, errors))
+}
+
+/// Sets grouped by day, oldest first, and apart the days already logged: importing the
+/// same export twice adds nothing
+pub fn group_by_day(
+    rows: Vec<GymSetRow>,
+    logged_dates: &HashSet<NaiveDate>,
+) -> (
+    BTreeMap<NaiveDate, Vec<GymSetRow>>,
+    BTreeMap<NaiveDate, Vec<GymSetRow>>,
+) {
+    let mut days: BTreeMap<NaiveDate, Vec<GymSetRow>> = BTreeMap::new();
+    let mut logged_days: BTreeMap<NaiveDate, Vec<GymSetRow>> = BTreeMap::new();
+    for row in rows {
+        let target = if logged_dates.contains(&row.date) {
+            &mut logged_days
+        } else {
+            &mut days
+        };
+        target.entry(row.date).or_default().push(row);
+    }
+    (days, logged_days)
+}
+
+/// Weights in pounds are converted, bodyweight exercises are often logged without any
+fn parse_set_weight(
+    record: &StringRecord,
+    weight_column: Option<usize>,
+    weight_lbs_column: Option<usize>,
+    weight_unit_column: Option<usize>,
+) -> Result<Decimal, String> {
+    let value = |column: Option<usize>| {
+        column
+            .and_then(|column| record.get(column))
+            .filter(|value| !value.is_empty())
+    };
+
+    if let Some(weight) = value(weight_column) {
+        let weight = parse_decimal(weight, "weight")?;
+        let in_lbs = value(weight_unit_column).is_some_and(|unit| {
+            unit.eq_ignore_ascii_case("lbs") || unit.eq_ignore_ascii_case("lb")
+        });
+        return Ok(if in_lbs {
+            (weight * KG_PER_LB).round_dp(2)
+        } else {
+            weight
+        });
+    }
+    if let Some(weight) = value(weight_lbs_column) {
+        return Ok((parse_decimal(weight, "weight")? * KG_PER_LB).round_dp(2));
+    }
+    Ok(Decimal::ZERO)
+}
+
+/// Hevy writes `normal`, `warmup`, `dropset` and `failure`
+fn parse_set_type(value: &str) -> Result<SetTypeEnum, String> {
+    match normalize_header(value).as_str() {
+        "normal" => Ok(SetTypeEnum::Normal),
+        "warmup" => Ok(SetTypeEnum::WarmUp),
+        "drop" | "dropset" => Ok(SetTypeEnum::Drop),
+        "failure" => Ok(SetTypeEnum::Failure),
+        _ => Err(format!("Unknown set type: {value}")),
+    }
+}
+
+fn parse_meal_kind(value: &str) -> Result<MealTypeEnum, String> {
+    match value.to_lowercase().as_str() {
+        "breakfast" => Ok(MealTypeEnum::Breakfast),
+        "lunch" => Ok(MealTypeEnum::Lunch),
+        "dinner" => Ok(MealTypeEnum::Dinner),
+        "snack" | "snacks" => Ok(MealTypeEnum::Snack),
+        _ => Err(format!("Unknown meal: {value}")),
+    }
+}
+
+/// Generic files with a food per row, or MyFitnessPal exports with the totals of
+/// each meal
+pub fn parse_meal_rows(
+    content: &str,
+) -> anyhow::Result<(ImportFormat, Vec<MealRow>, Vec<RowError>)> {
+    let mut reader = reader(content);
+    let columns = Columns::new(reader.headers()?);
+    let format = if columns.has("carbohydratesg") {
+        ImportFormat::MyFitnessPal
+    } else {
+        ImportFormat::Generic
+    };
+    let date_column = columns.require(DATE_COLUMNS)?;
+    let meal_column = columns.require(MEAL_COLUMNS)?;
+    let food_column = columns.find(FOOD_COLUMNS);
+    let quantity_column = match food_column {
+        Some(_) => Some(columns.require(QUANTITY_COLUMNS)?),
+        None => None,
+    };
+    let calories_column = columns.require(CALORIES_COLUMNS)?;
+    let protein_column = columns.require(PROTEIN_COLUMNS)?;
+    let carbs_column = columns.require(CARBS_COLUMNS)?;
+    let fat_column = columns.require(FAT_COLUMNS)?;
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = line_of(&record);
+        let row = (|| {
+            let date = parse_date(field(&record, date_column, "date")?)?;
+            let kind = parse_meal_kind(field(&record, meal_column, "meal")?)?;
+            let food = match (food_column, quantity_column) {
+                (Some(food_column), Some(quantity_column)) => {
+                    let name = field(&record, food_column, "food")?.to_string();
+                    if name.chars().count() > 255 {
+                        return Err("Food name must be less than 255 characters".to_string());
+                    }
+                    let quantity_in_grams =
+                        parse_decimal(field(&record, quantity_column, "quantity")?, "quantity")?
+                            .round()
+                            .try_into()
+                            .unwrap_or(0);
+                    if !(1..=100000).contains(&quantity_in_grams) {
+                        return Err("Quantity must be between 1 and 100000 grams".to_string());
+                    }
+                    Some(FoodRow {
+                        name,
+                        quantity_in_grams,
+                    })
+                }
+                _ => None,
+            };
+            let amount = |column: usize, name: &str| -> Result<Decimal, String> {
+                let amount = match record.get(column) {
+                    Some(value) if !value.is_empty() => parse_decimal(value, name)?,
+                    _ => Decimal::ZERO,
+                };
+                if amount < Decimal::ZERO {
+                    return Err(format!("The {name} cannot be negative"));
+                }
+                Ok(amount)
+            };
+            Ok(MealRow {
+                line,
+                date,
+                kind,
+                food,
+                calories: amount(calories_column, "calories")?,
+                protein: amount(protein_column, "protein")?,
+                carbs: amount(carbs_column, "carbs")?,
+                fat: amount(fat_column, "fat")?,
+            })
+        })();
+
+        match row {
+            Ok(row) => rows.push(row),
+            Err(message) => errors.push(RowError { line, message }),
+        }
+    }
+
+    Ok((format, rows, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_date() {
+        for value in [
+            "2024-01-31",
+            "31/01/2024",
+            "31.01.2024",
+            "2024/01/31",
+            "31 Jan 2024",
+            "2024-01-31 08:30:12",
+            "2024-01-31T08:30:12Z",
+            "31 Jan 2024, 08:30",
+        ] {
+            assert_eq!(parse_date(value), Ok(date(2024, 1, 31)), "{value}");
+        }
+
+        assert_eq!(
+            parse_date("2024-02-30"),
+            Err("Invalid date: 2024-02-30".to_string())
+        );
+        assert!(parse_date("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse_decimal("82.5", "weight"), Ok(Decimal::new(825, 1)));
+        assert_eq!(parse_decimal("82,5", "weight"), Ok(Decimal::new(825, 1)));
+        assert_eq!(parse_decimal("82,456", "weight"), Ok(Decimal::new(8246, 2)));
+        assert_eq!(
+            parse_decimal("heavy", "weight"),
+            Err("Invalid weight: heavy".to_string())
+        );
+    }
+
+    #[test]
+    fn test_semicolon_delimiter() {
+        let (rows, errors) = parse_weight_rows("Date;Weight (kg)\n31/01/2024;82,5\n").unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].date, date(2024, 1, 31));
+        assert_eq!(rows[0].weight_in_kg, Decimal::new(825, 1));
+    }
+
+    #[test]
+    fn test_row_errors_keep_their_line() {
+        let content = "date,weight\n\
+            2024-01-01,80\n\
+            tomorrow,80\n\
+            2024-01-03,\n\
+            2024-01-04,1200\n\
+            2024-01-05,79.5\n";
+        let (rows, errors) = parse_weight_rows(content).unwrap();
+
+        assert_eq!(
+            rows.iter().map(|row| row.line).collect::<Vec<_>>(),
+            vec![2, 6]
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.line, error.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, "Invalid date: tomorrow"),
+                (4, "Missing weight"),
+                (5, "Weight must be between 0 and 1000 kg, got 1200"),
+            ]
+        );
+
+        assert!(parse_weight_rows("day,kilos\n2024-01-01,80\n").is_err());
+    }
+
+    const STRONG: &str = "Date,Workout Name,Exercise Name,Set Order,Weight,Weight Unit,Reps,RPE,Distance,Distance Unit,Seconds,Notes,Workout Notes,Workout Duration\n\
+        2024-01-15 08:30:12,Push,Bench Press (Barbell),1,135,lbs,10,,,,0,,,45m\n\
+        2024-01-15 08:30:12,Push,Bench Press (Barbell),2,60,kg,8,,,,0,,,45m\n\
+        2024-01-16 18:02:40,Pull,Pull Up,1,,,12,,,,0,,,30m\n";
+
+    const HEVY: &str = "\"title\",\"start_time\",\"end_time\",\"description\",\"exercise_title\",\"superset_id\",\"exercise_notes\",\"set_index\",\"set_type\",\"weight_kg\",\"reps\",\"distance_km\",\"duration_seconds\",\"rpe\"\n\
+        \"Legs\",\"15 Jan 2024, 08:30\",\"15 Jan 2024, 09:30\",\"\",\"Squat (Barbell)\",,\"\",0,\"warmup\",40,10,,,\n\
+        \"Legs\",\"15 Jan 2024, 08:30\",\"15 Jan 2024, 09:30\",\"\",\"Squat (Barbell)\",,\"\",1,\"normal\",100,5,,,8\n";
+
+    const MY_FITNESS_PAL: &str = "Date,Meal,Calories,Fat (g),Saturated Fat,Polyunsaturated Fat,Monounsaturated Fat,Trans Fat,Cholesterol,Sodium (mg),Potassium,Carbohydrates (g),Fiber,Sugar,Protein (g),Vitamin A,Vitamin C,Calcium,Iron,Note\n\
+        2024-01-15,Breakfast,420,12.5,3,1,2,0,15,300,200,55.2,6,12,18.4,0,0,0,0,\n";
+
+    #[test]
+    fn test_detect_formats() {
+        let (format, rows, errors) = parse_gym_set_rows(STRONG).unwrap();
+        assert_eq!(format, ImportFormat::Strong);
+        assert!(errors.is_empty());
+        assert_eq!(rows.len(), 3);
+
+        let (format, _, errors) = parse_gym_set_rows(HEVY).unwrap();
+        assert_eq!(format, ImportFormat::Hevy);
+        assert!(errors.is_empty());
+
+        let (format, _, _) = parse_gym_set_rows("date,exercise,reps,weight\n").unwrap();
+        assert_eq!(format, ImportFormat::Generic);
+
+        let (format, rows, errors) = parse_meal_rows(MY_FITNESS_PAL).unwrap();
+        assert_eq!(format, ImportFormat::MyFitnessPal);
+        assert!(errors.is_empty());
+        assert_eq!(rows[0].kind, MealTypeEnum::Breakfast);
+        assert!(rows[0].food.is_none());
+        assert_eq!(rows[0].carbs, Decimal::new(552, 1));
+        assert_eq!(rows[0].protein, Decimal::new(184, 1));
+
+        let (format, _, _) =
+            parse_meal_rows("date,meal,food,grams,calories,protein,carbs,fat\n").unwrap();
+        assert_eq!(format, ImportFormat::Generic);
+    }
+
+    #[test]
+    fn test_hevy_set_index_starts_at_zero() {
+        let (_, rows, _) = parse_gym_set_rows(HEVY).unwrap();
+
+        assert_eq!(rows[0].date, date(2024, 1, 15));
+        assert_eq!(rows[0].exercise, "Squat (Barbell)");
+        assert_eq!(
+            rows.iter().map(|row| row.set_number).collect::<Vec<_>>(),
+            vec![Some(1), Some(2)]
+        );
+
+        // Other files count from 1
+        let (_, rows, _) = parse_gym_set_rows(STRONG).unwrap();
+        assert_eq!(rows[0].set_number, Some(1));
+    }
+
+    #[test]
+    fn test_set_types() {
+        let (_, rows, _) = parse_gym_set_rows(HEVY).unwrap();
+        assert_eq!(rows[0].set_type, SetTypeEnum::WarmUp);
+        assert_eq!(rows[1].set_type, SetTypeEnum::Normal);
+
+        let (_, rows, errors) = parse_gym_set_rows(
+            "date,exercise,reps,weight,set_type\n\
+            2024-01-15,Curl,10,20,dropset\n\
+            2024-01-15,Curl,8,20,failure\n\
+            2024-01-15,Curl,8,20,\n\
+            2024-01-15,Curl,8,20,superset\n",
+        )
+        .unwrap();
+        assert_eq!(
+            rows.iter()
+                .map(|row| row.set_type.clone())
+                .collect::<Vec<_>>(),
+            vec![SetTypeEnum::Drop, SetTypeEnum::Failure, SetTypeEnum::Normal]
+        );
+        assert_eq!(errors[0].line, 5);
+        assert_eq!(errors[0].message, "Unknown set type: superset");
+    }
+
+    #[test]
+    fn test_weights_in_lbs() {
+        let (_, rows, _) = parse_gym_set_rows(STRONG).unwrap();
+
+        // 135 lbs
+        assert_eq!(rows[0].weight_kg, Decimal::new(6123, 2));
+        assert_eq!(rows[1].weight_kg, Decimal::new(60, 0));
+        // Bodyweight
+        assert_eq!(rows[2].weight_kg, Decimal::ZERO);
+
+        let (_, rows, _) =
+            parse_gym_set_rows("date,exercise,reps,weight_lbs\n2024-01-15,Curl,10,45\n").unwrap();
+        assert_eq!(rows[0].weight_kg, Decimal::new(2041, 2));
+    }
+
+    #[test]
+    fn test_group_by_day_skips_logged_dates() {
+        let (_, rows, _) = parse_gym_set_rows(STRONG).unwrap();
+        let logged_dates = HashSet::from([date(2024, 1, 16)]);
+
+        let (days, logged_days) = group_by_day(rows, &logged_dates);
+
+        assert_eq!(
+            days.keys().copied().collect::<Vec<_>>(),
+            vec![date(2024, 1, 15)]
+        );
+        assert_eq!(days[&date(2024, 1, 15)].len(), 2);
+        assert_eq!(
+            logged_days.keys().copied().collect::<Vec<_>>(),
+            vec![date(2024, 1, 16)]
+        );
+        assert_eq!(logged_days[&date(2024, 1, 16)][0].exercise, "Pull Up");
+    }
+}
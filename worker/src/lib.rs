@@ -1,3 +1,5 @@
+pub mod export_jobs;
+pub mod import_jobs;
 pub mod mail_jobs;
 pub mod scheduled_jobs;
 pub mod worker_main;
@@ -0,0 +1,20 @@
+use entities::JobEmailAccountDeletion;
+use tracing::info;
+
+use crate::{mail_jobs::common_mail_jobs::send_email, worker_main::state::WorkerState};
+
+pub async fn handle_account_deletion_email(
+    worker_state: WorkerState,
+    data: JobEmailAccountDeletion,
+) -> anyhow::Result<bool> {
+    info!("Handling account deletion email for: {}", data.email);
+    let subject = "DimDim Health - Your account will be deleted".to_string();
+    let login_link = format!("{}/#/login", worker_state.frontend_url);
+    let content = format!(
+        "Hey {}.\nWe received a request to delete your account. Your account and all of its data will be permanently deleted on {} (UTC).\nChanged your mind? Simply log in before that date to cancel the deletion: {login_link}\nIf you didn't make this request, log in and change your password right away.\n\n Cheers,\n DimDim Health",
+        data.username,
+        data.delete_after.format("%Y-%m-%d %H:%M"),
+    );
+
+    send_email(worker_state, data.email, subject, content).await
+}
@@ -1,15 +1,19 @@
 use crate::{
     mail_jobs::{
-        email_change_mail::handle_email_change_email,
-        monthly_recap_mail::handle_monthly_recap_email, register_mail::handle_registration_email,
-        reset_password_mail::handle_reset_password_email,
+        account_deletion_mail::handle_account_deletion_email,
+        data_export_mail::handle_data_export_email, email_change_mail::handle_email_change_email,
+        magic_link_mail::handle_magic_link_email, monthly_recap_mail::handle_monthly_recap_email,
+        register_mail::handle_registration_email, reset_password_mail::handle_reset_password_email,
+        security_alert_mail::handle_security_alert_email,
+        watch_request_mail::handle_watch_request_email,
         weekly_recap_mail::handle_weekly_recap_email, yearly_recap_mail::handle_yearly_recap_email,
     },
     worker_main::state::WorkerState,
 };
 use entities::{
-    EmailType, JobEmail, JobEmailMonthlyRecap, JobEmailRegister, JobEmailResetPassword,
-    JobEmailWeeklyRecap, JobEmailYearlyRecap,
+    EmailType, JobEmail, JobEmailAccountDeletion, JobEmailDataExport, JobEmailMagicLink,
+    JobEmailMonthlyRecap, JobEmailRegister, JobEmailResetPassword, JobEmailSecurityAlert,
+    JobEmailWatchRequest, JobEmailWeeklyRecap, JobEmailYearlyRecap,
 };
 use lettre::{Message, SmtpTransport, Transport, message::header::ContentType};
 use tracing::info;
@@ -40,6 +44,26 @@ pub async fn handle_mail_job(worker_state: WorkerState, job: JobEmail) -> anyhow
             let payload: JobEmailYearlyRecap = serde_json::from_value(job.data)?;
             handle_yearly_recap_email(worker_state, payload).await
         }
+        EmailType::WatchRequest => {
+            let payload: JobEmailWatchRequest = serde_json::from_value(job.data)?;
+            handle_watch_request_email(worker_state, payload).await
+        }
+        EmailType::SecurityAlert => {
+            let payload: JobEmailSecurityAlert = serde_json::from_value(job.data)?;
+            handle_security_alert_email(worker_state, payload).await
+        }
+        EmailType::MagicLink => {
+            let payload: JobEmailMagicLink = serde_json::from_value(job.data)?;
+            handle_magic_link_email(worker_state, payload).await
+        }
+        EmailType::AccountDeletion => {
+            let payload: JobEmailAccountDeletion = serde_json::from_value(job.data)?;
+            handle_account_deletion_email(worker_state, payload).await
+        }
+        EmailType::DataExport => {
+            let payload: JobEmailDataExport = serde_json::from_value(job.data)?;
+            handle_data_export_email(worker_state, payload).await
+        }
     }
 }
 
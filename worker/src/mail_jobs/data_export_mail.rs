@@ -0,0 +1,23 @@
+use entities::JobEmailDataExport;
+use tracing::info;
+
+use crate::{mail_jobs::common_mail_jobs::send_email, worker_main::state::WorkerState};
+
+pub async fn handle_data_export_email(
+    worker_state: WorkerState,
+    data: JobEmailDataExport,
+) -> anyhow::Result<bool> {
+    info!("Handling data export email for: {}", data.email);
+    let subject = "DimDim Health - Your data export is ready".to_string();
+    let download_link = format!(
+        "{}/#/data-export?token={}",
+        worker_state.frontend_url, data.token
+    );
+    let content = format!(
+        "Hey {}.\nThe copy of your data you asked for is ready. Download it by clicking the following link while logged in: {download_link}\nThe archive contains a JSON and a CSV file for each kind of data, it can be downloaded until {} (UTC).\nIf you didn't make this request, please change your password right away.\n\n Cheers,\n DimDim Health",
+        data.username,
+        data.expires_at.format("%Y-%m-%d %H:%M"),
+    );
+
+    send_email(worker_state, data.email, subject, content).await
+}
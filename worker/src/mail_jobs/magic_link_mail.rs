@@ -0,0 +1,22 @@
+use entities::JobEmailMagicLink;
+use tracing::info;
+
+use crate::{mail_jobs::common_mail_jobs::send_email, worker_main::state::WorkerState};
+
+pub async fn handle_magic_link_email(
+    worker_state: WorkerState,
+    data: JobEmailMagicLink,
+) -> anyhow::Result<bool> {
+    info!("Handling magic link email for: {}", data.email);
+    let subject = format!("DimDim Health - Your login link {}", data.username);
+    let login_link = format!(
+        "{}/#/magic-link?token={}",
+        worker_state.frontend_url, data.token
+    );
+    let content = format!(
+        "Hey {}.\nWe received a request to log in to your account without a password. If you didn't make this request, you can safely ignore this email.\nLog in by clicking the following link: {login_link} (this link can only be used once and will expire in 15 minutes)\n\n Cheers,\n DimDim Health",
+        data.username
+    );
+
+    send_email(worker_state, data.email, subject, content).await
+}
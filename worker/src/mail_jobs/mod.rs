@@ -1,7 +1,12 @@
+pub mod account_deletion_mail;
 pub mod common_mail_jobs;
+pub mod data_export_mail;
 pub mod email_change_mail;
+pub mod magic_link_mail;
 pub mod monthly_recap_mail;
 pub mod register_mail;
 pub mod reset_password_mail;
+pub mod security_alert_mail;
+pub mod watch_request_mail;
 pub mod weekly_recap_mail;
 pub mod yearly_recap_mail;
@@ -0,0 +1,41 @@
+use entities::{JobEmailSecurityAlert, SecurityAlert};
+use tracing::info;
+
+use crate::{mail_jobs::common_mail_jobs::send_email, worker_main::state::WorkerState};
+
+pub async fn handle_security_alert_email(
+    worker_state: WorkerState,
+    data: JobEmailSecurityAlert,
+) -> anyhow::Result<bool> {
+    info!("Handling security alert email for: {}", data.email);
+    let reset_link = format!("{}/#/forgot-password", worker_state.frontend_url);
+
+    let (subject, details) = match data.alert {
+        SecurityAlert::AccountLocked { locked_minutes, ip } => (
+            "DimDim Health - Your account has been locked".to_string(),
+            format!(
+                "We detected too many failed login attempts on your account{}, so it is locked for the next {locked_minutes} minutes.",
+                from_ip(ip.as_deref())
+            ),
+        ),
+        SecurityAlert::NewDeviceLogin { user_agent, ip } => (
+            "DimDim Health - New login to your account".to_string(),
+            format!(
+                "Your account was just accessed from a new device{}.\nDevice: {user_agent}",
+                from_ip(ip.as_deref())
+            ),
+        ),
+    };
+
+    let content = format!(
+        "Hey {}.\n{details}\n\nIf this was you, you can ignore this email. Otherwise, please change your password right away: {reset_link}\n\n Cheers,\n DimDim Health",
+        data.username
+    );
+
+    send_email(worker_state, data.email, subject, content).await
+}
+
+fn from_ip(ip: Option<&str>) -> String {
+    ip.map(|ip| format!(" (IP address {ip})"))
+        .unwrap_or_default()
+}
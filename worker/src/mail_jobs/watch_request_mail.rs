@@ -0,0 +1,26 @@
+use entities::JobEmailWatchRequest;
+use tracing::info;
+
+use crate::{mail_jobs::common_mail_jobs::send_email, worker_main::state::WorkerState};
+
+pub async fn handle_watch_request_email(
+    worker_state: WorkerState,
+    data: JobEmailWatchRequest,
+) -> anyhow::Result<bool> {
+    info!("Handling watch request email for: {}", data.email);
+    let subject = format!(
+        "DimDim Health - {} wants to follow your progress",
+        data.requester_username
+    );
+    let requests_link = format!("{}/#/watch-requests", worker_state.frontend_url);
+    let message = data
+        .message
+        .map(|message| format!("Their message: \"{message}\"\n\n"))
+        .unwrap_or_default();
+    let content = format!(
+        "Hey {}.\n{} would like to watch your data on DimDim Health.\n\n{message}You can accept or decline this request here: {requests_link}\n\n Cheers,\n DimDim Health",
+        data.username, data.requester_username
+    );
+
+    send_email(worker_state, data.email, subject, content).await
+}
@@ -0,0 +1,280 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use entities::{
+    account_deletion_request, email_verification_token,
+    env_loader::MaintenanceSettings,
+    food_item, gym_exercise, magic_link_token, password_reset_token, refresh_token,
+    sea_orm_active_enums::{UserGroup, VisibilityEnum},
+    user_groups, users,
+    users_ext::SYSTEM_USER_ID,
+};
+use opentelemetry::{KeyValue, global, metrics::Counter};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QuerySelect, QueryTrait, TransactionTrait, sea_query::Expr,
+};
+use std::time::Duration;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::worker_main::state::WorkerState;
+
+/// What a maintenance run deleted
+#[derive(Debug, Default)]
+pub struct MaintenanceReport {
+    pub deleted_accounts: u64,
+    pub guest_users: u64,
+    pub refresh_tokens: u64,
+    pub password_reset_tokens: u64,
+    pub email_verification_tokens: u64,
+    pub magic_link_tokens: u64,
+}
+
+/// Scheduled job removing the accounts whose deletion is due, inactive guest accounts
+/// and expired tokens
+/// Runs every `interval_secs` seconds
+pub async fn process_maintenance(worker_state: WorkerState, settings: MaintenanceSettings) {
+    if !settings.enabled {
+        info!("Maintenance job disabled");
+        return;
+    }
+
+    info!("Starting maintenance scheduler");
+    let deleted_rows = global::meter("dimdim-health-worker")
+        .u64_counter("maintenance.deleted_rows")
+        .with_description("Rows deleted by the maintenance job")
+        .build();
+
+    loop {
+        match run_maintenance(&worker_state.db, &settings).await {
+            Ok(report) => {
+                info!(?report, "Maintenance run completed");
+                record_metrics(&deleted_rows, &report);
+            }
+            Err(e) => {
+                error!("Error during maintenance run: {}", e);
+            }
+        }
+
+        tokio::time::sleep(Duration::from_secs(settings.interval_secs)).await;
+    }
+}
+
+fn record_metrics(counter: &Counter<u64>, report: &MaintenanceReport) {
+    for (kind, count) in [
+        ("deleted_accounts", report.deleted_accounts),
+        ("guest_users", report.guest_users),
+        ("refresh_tokens", report.refresh_tokens),
+        ("password_reset_tokens", report.password_reset_tokens),
+        (
+            "email_verification_tokens",
+            report.email_verification_tokens,
+        ),
+        ("magic_link_tokens", report.magic_link_tokens),
+    ] {
+        counter.add(count, &[KeyValue::new("kind", kind)]);
+    }
+}
+
+pub async fn run_maintenance(
+    db: &DatabaseConnection,
+    settings: &MaintenanceSettings,
+) -> Result<MaintenanceReport, sea_orm::DbErr> {
+    let now = Utc::now();
+    let batch_size = settings.batch_size.max(1);
+
+    // The grace period is over, everything the user owns is removed with it by the
+    // cascading foreign keys, apart from the items other users still need
+    let due_deletions = account_deletion_request::Entity::find()
+        .select_only()
+        .column(account_deletion_request::Column::UserId)
+        .filter(account_deletion_request::Column::DeleteAfter.lte(now))
+        .into_query();
+    let deleted_accounts = delete_users_in_batches(
+        db,
+        Condition::all().add(users::Column::Id.in_subquery(due_deletions)),
+        batch_size,
+    )
+    .await?;
+
+    // Used refresh tokens are kept until they expire, they are needed to detect a reuse
+    let refresh_tokens = delete_in_batches::<refresh_token::Entity, _>(
+        db,
+        refresh_token::Column::Id,
+        Condition::all().add(refresh_token::Column::ExpiresAt.lt(now)),
+        batch_size,
+    )
+    .await?;
+
+    let password_reset_tokens = delete_in_batches::<password_reset_token::Entity, _>(
+        db,
+        password_reset_token::Column::Id,
+        Condition::all().add(password_reset_token::Column::ExpiresAt.lt(now)),
+        batch_size,
+    )
+    .await?;
+
+    let email_verification_tokens = delete_in_batches::<email_verification_token::Entity, _>(
+        db,
+        email_verification_token::Column::Id,
+        Condition::all().add(email_verification_token::Column::ExpiresAt.lt(now)),
+        batch_size,
+    )
+    .await?;
+
+    let magic_link_tokens = delete_in_batches::<magic_link_token::Entity, _>(
+        db,
+        magic_link_token::Column::Id,
+        Condition::any()
+            .add(magic_link_token::Column::ExpiresAt.lt(now))
+            .add(magic_link_token::Column::UsedAt.is_not_null()),
+        batch_size,
+    )
+    .await?;
+
+    // A guest is active as long as it refreshes its tokens, what it shared is kept like
+    // for any other deleted account
+    let inactive_since = now - ChronoDuration::days(settings.guest_inactive_days.into());
+    let guests = user_groups::Entity::find()
+        .select_only()
+        .column(user_groups::Column::UserId)
+        .filter(user_groups::Column::Group.eq(UserGroup::GuestGroup))
+        .into_query();
+    let recently_active = refresh_token::Entity::find()
+        .select_only()
+        .column(refresh_token::Column::UserId)
+        .filter(refresh_token::Column::CreatedAt.gte(inactive_since))
+        .into_query();
+    let guest_users = delete_users_in_batches(
+        db,
+        Condition::all()
+            .add(users::Column::Id.in_subquery(guests))
+            .add(users::Column::Id.not_in_subquery(recently_active))
+            .add(users::Column::CreatedAt.lt(inactive_since)),
+        batch_size,
+    )
+    .await?;
+
+    Ok(MaintenanceReport {
+        deleted_accounts,
+        guest_users,
+        refresh_tokens,
+        password_reset_tokens,
+        email_verification_tokens,
+        magic_link_tokens,
+    })
+}
+
+/// Delete the users matching the condition at most `batch_size` at a time, like
+/// `delete_in_batches`, handing their shared items over first
+async fn delete_users_in_batches(
+    db: &DatabaseConnection,
+    condition: Condition,
+    batch_size: u64,
+) -> Result<u64, sea_orm::DbErr> {
+    let mut deleted = 0;
+
+    loop {
+        let ids: Vec<Uuid> = users::Entity::find()
+            .select_only()
+            .column(users::Column::Id)
+            .filter(condition.clone())
+            .limit(batch_size)
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        if ids.is_empty() {
+            break;
+        }
+
+        let batch_len = ids.len() as u64;
+        let txn = db.begin().await?;
+        reassign_shared_items(&txn, &ids).await?;
+        let result = users::Entity::delete_many()
+            .filter(users::Column::Id.is_in(ids))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+        deleted += result.rows_affected;
+
+        if batch_len < batch_size {
+            break;
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Public exercises and food items, or the ones other users rely on, are given to the
+/// system user so that deleting their creators does not cascade to other users' sets,
+/// templates and meals
+async fn reassign_shared_items<C: ConnectionTrait>(
+    db: &C,
+    user_ids: &[Uuid],
+) -> Result<(), sea_orm::DbErr> {
+    gym_exercise::Entity::update_many()
+        .col_expr(gym_exercise::Column::AddedBy, Expr::value(SYSTEM_USER_ID))
+        .filter(gym_exercise::Column::AddedBy.is_in(user_ids.to_vec()))
+        .filter(
+            Condition::any()
+                .add(gym_exercise::Column::Visibility.eq(VisibilityEnum::Public))
+                .add(gym_exercise::Entity::used_by_others(user_ids.to_vec())),
+        )
+        .exec(db)
+        .await?;
+
+    food_item::Entity::update_many()
+        .col_expr(food_item::Column::AddedBy, Expr::value(SYSTEM_USER_ID))
+        .filter(food_item::Column::AddedBy.is_in(user_ids.to_vec()))
+        .filter(
+            Condition::any()
+                .add(food_item::Column::Visibility.eq(VisibilityEnum::Public))
+                .add(food_item::Entity::used_by_others(user_ids.to_vec())),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Delete the rows matching the condition at most `batch_size` at a time
+async fn delete_in_batches<E, C>(
+    db: &DatabaseConnection,
+    id_column: C,
+    condition: Condition,
+    batch_size: u64,
+) -> Result<u64, sea_orm::DbErr>
+where
+    E: EntityTrait,
+    C: ColumnTrait,
+{
+    let mut deleted = 0;
+
+    loop {
+        let ids: Vec<Uuid> = E::find()
+            .select_only()
+            .column(id_column)
+            .filter(condition.clone())
+            .limit(batch_size)
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        if ids.is_empty() {
+            break;
+        }
+
+        let batch_len = ids.len() as u64;
+        let result = E::delete_many()
+            .filter(id_column.is_in(ids))
+            .exec(db)
+            .await?;
+        deleted += result.rows_affected;
+
+        if batch_len < batch_size {
+            break;
+        }
+    }
+
+    Ok(deleted)
+}
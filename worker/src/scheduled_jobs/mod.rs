@@ -1,3 +1,4 @@
+pub mod maintenance_processor;
 pub mod monthly_recap_processor;
 pub mod weekly_recap_processor;
 pub mod yearly_recap_processor;
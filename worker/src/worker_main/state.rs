@@ -1,4 +1,4 @@
-use entities::env_loader::Settings;
+use entities::env_loader::{DataExportSettings, Settings};
 use log::info;
 use migration::sea_orm::{self, ConnectOptions, Database, DatabaseConnection};
 use redis::{RedisError, aio::ConnectionManager};
@@ -12,6 +12,7 @@ pub struct WorkerState {
     pub redis: ConnectionManager,
 
     pub frontend_url: String,
+    pub data_export: DataExportSettings,
 
     pub gmail_from: Mailbox,
     pub gmail_creds: Credentials,
@@ -26,6 +27,7 @@ impl WorkerState {
             db,
             redis,
             settings.frontend_url.clone(),
+            settings.data_export.clone(),
             settings.gmail_email.clone(),
             settings.gmail_password.clone(),
         )
@@ -36,6 +38,7 @@ impl WorkerState {
         db: DatabaseConnection,
         redis: ConnectionManager,
         frontend_url: String,
+        data_export: DataExportSettings,
         gmail_email: String,
         gmail_password: String,
     ) -> anyhow::Result<Self> {
@@ -50,6 +53,7 @@ impl WorkerState {
             db,
             redis,
             frontend_url,
+            data_export,
             gmail_from,
             gmail_creds,
         })
@@ -1,4 +1,4 @@
-use entities::{Job, JobEmail, TaskType, env_loader::Settings};
+use entities::{Job, JobDataExport, JobDataImport, JobEmail, TaskType, env_loader::Settings};
 use redis::{AsyncCommands, aio::ConnectionManager};
 use std::sync::Arc;
 use std::time::Duration;
@@ -7,7 +7,10 @@ use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument, warn};
 
 use crate::{
+    export_jobs::data_export::handle_data_export_job,
+    import_jobs::data_import::handle_data_import_job,
     mail_jobs::common_mail_jobs::handle_mail_job,
+    scheduled_jobs::maintenance_processor::process_maintenance,
     scheduled_jobs::monthly_recap_processor::process_monthly_recap_queue,
     scheduled_jobs::weekly_recap_processor::process_weekly_recap_queue,
     scheduled_jobs::yearly_recap_processor::process_yearly_recap_queue,
@@ -68,6 +71,15 @@ pub async fn worker_main() {
         tokio::spawn(async move { process_yearly_recap_queue(yearly_processor_state).await });
     handles.push(yearly_processor_handle);
 
+    // Spawn the maintenance job purging inactive guests and expired tokens
+    let maintenance_state = worker_state.clone();
+    let maintenance_settings = settings.maintenance.clone();
+    let maintenance_handle =
+        tokio::spawn(
+            async move { process_maintenance(maintenance_state, maintenance_settings).await },
+        );
+    handles.push(maintenance_handle);
+
     for i in 0..settings.number_workers {
         let worker_id = format!("worker-{i}");
         let worker_state = worker_state.clone();
@@ -220,6 +232,14 @@ async fn process(worker_state: WorkerState, job: Job, worker_id: &str) -> anyhow
             debug!(email_type = ?job_email, "Handling email job");
             handle_mail_job(worker_state, job_email).await
         }
+        TaskType::DataExport => {
+            let job_data_export: JobDataExport = serde_json::from_value(job.data)?;
+            handle_data_export_job(worker_state, job_data_export).await
+        }
+        TaskType::DataImport => {
+            let job_data_import: JobDataImport = serde_json::from_value(job.data)?;
+            handle_data_import_job(worker_state, job_data_import).await
+        }
     };
 
     match &job_result {
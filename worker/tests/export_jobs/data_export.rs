@@ -0,0 +1,57 @@
+use std::io::{Cursor, Read};
+
+use crate::helpers::{test_data::TestData, test_db::get_db};
+use chrono::Utc;
+use dimdim_health_worker::export_jobs::data_export::build_user_archive;
+use serde_json::Value;
+use zip::ZipArchive;
+
+fn read_json(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> Vec<Value> {
+    let mut content = String::new();
+    archive
+        .by_name(name)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+#[tokio::test]
+async fn test_archive_holds_everything_stored() {
+    let db = &get_db().await;
+    let td = TestData::new(db);
+
+    let user = td.user(Utc::now()).await;
+    td.login_device(user.id, "203.0.113.7").await;
+    td.two_factor(user.id).await;
+
+    let archive = build_user_archive(db, user.clone()).await.unwrap();
+    let mut archive = ZipArchive::new(Cursor::new(archive)).unwrap();
+
+    for name in [
+        "profile",
+        "weights",
+        "meals",
+        "gym_sessions",
+        "watch_permissions",
+        "watch_requests",
+        "gym_personal_records",
+        "data_imports",
+        "login_devices",
+        "two_factor",
+    ] {
+        assert!(archive.by_name(&format!("{name}.json")).is_ok(), "{name}");
+        assert!(archive.by_name(&format!("{name}.csv")).is_ok(), "{name}");
+    }
+
+    let profile = read_json(&mut archive, "profile.json");
+    assert_eq!(profile[0]["id"], user.id.to_string());
+    assert!(profile[0].get("password_hash").is_none());
+
+    let login_devices = read_json(&mut archive, "login_devices.json");
+    assert_eq!(login_devices[0]["last_ip"], "203.0.113.7");
+
+    let two_factor = read_json(&mut archive, "two_factor.json");
+    assert_eq!(two_factor[0]["enabled"], true);
+    assert!(two_factor[0].get("secret").is_none());
+}
@@ -0,0 +1,2 @@
+pub mod test_data;
+pub mod test_db;
@@ -0,0 +1,238 @@
+use chrono::{DateTime, Duration, Utc};
+use entities::{
+    account_deletion_request, food_item, gym_exercise, gym_session, gym_set, magic_link_token,
+    meal, meal_item, refresh_token,
+    sea_orm_active_enums::{
+        MealTypeEnum, SetTypeEnum, UserGroup, UserProfileImage, VisibilityEnum,
+    },
+    user_groups, user_login_device, user_two_factor, users,
+};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection, prelude::Decimal};
+use uuid::Uuid;
+
+/// Rows inserted straight into the database, the worker has no repositories
+pub struct TestData<'a> {
+    pub db: &'a DatabaseConnection,
+}
+
+impl<'a> TestData<'a> {
+    pub fn new(db: &'a DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn user(&self, created_at: DateTime<Utc>) -> users::Model {
+        let id = Uuid::new_v4();
+        users::ActiveModel {
+            id: Set(id),
+            username: Set(format!("u{}", &id.simple().to_string()[..16])),
+            email: Set(format!("{}@example.com", id.simple())),
+            password_hash: Set("notneeded".to_string()),
+            email_verified: Set(true),
+            profile_image: Set(UserProfileImage::Avatar1),
+            created_at: Set(created_at.into()),
+            updated_at: Set(created_at.into()),
+        }
+        .insert(self.db)
+        .await
+        .unwrap()
+    }
+
+    pub async fn guest(&self, created_at: DateTime<Utc>) -> users::Model {
+        let user = self.user(created_at).await;
+        user_groups::ActiveModel {
+            user_id: Set(user.id),
+            group: Set(UserGroup::GuestGroup),
+            created_at: Set(created_at.into()),
+        }
+        .insert(self.db)
+        .await
+        .unwrap();
+        user
+    }
+
+    pub async fn refresh_token(
+        &self,
+        user_id: Uuid,
+        created_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> refresh_token::Model {
+        refresh_token::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            token: Set(Uuid::new_v4().to_string()),
+            created_at: Set(created_at.into()),
+            expires_at: Set(expires_at.into()),
+            used_at: Set(None),
+        }
+        .insert(self.db)
+        .await
+        .unwrap()
+    }
+
+    pub async fn magic_link_token(
+        &self,
+        user_id: Uuid,
+        expires_at: DateTime<Utc>,
+        used_at: Option<DateTime<Utc>>,
+    ) -> magic_link_token::Model {
+        magic_link_token::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            token: Set(Uuid::new_v4().to_string()),
+            expires_at: Set(expires_at.into()),
+            used_at: Set(used_at.map(Into::into)),
+            created_at: Set(Utc::now().into()),
+        }
+        .insert(self.db)
+        .await
+        .unwrap()
+    }
+
+    pub async fn deletion_request(&self, user_id: Uuid, delete_after: DateTime<Utc>) {
+        account_deletion_request::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            delete_after: Set(delete_after.into()),
+            created_at: Set((delete_after - Duration::days(30)).into()),
+        }
+        .insert(self.db)
+        .await
+        .unwrap();
+    }
+
+    pub async fn exercise(
+        &self,
+        added_by: Uuid,
+        visibility: VisibilityEnum,
+    ) -> gym_exercise::Model {
+        gym_exercise::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(format!("Exercise {}", Uuid::new_v4().simple())),
+            description: Set(None),
+            added_by: Set(added_by),
+            created_at: Set(Utc::now().into()),
+            updated_at: Set(Utc::now().into()),
+            equipment: Set(None),
+            movement_pattern: Set(None),
+            is_unilateral: Set(false),
+            visibility: Set(visibility),
+        }
+        .insert(self.db)
+        .await
+        .unwrap()
+    }
+
+    pub async fn gym_set(&self, user_id: Uuid, exercise_id: Uuid) -> gym_set::Model {
+        let session = gym_session::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            date: Set(Utc::now().date_naive()),
+            created_at: Set(Utc::now().into()),
+            updated_at: Set(Utc::now().into()),
+            started_at: Set(None),
+            ended_at: Set(None),
+            notes: Set(None),
+            perceived_difficulty: Set(None),
+            user_weight_id: Set(None),
+        }
+        .insert(self.db)
+        .await
+        .unwrap();
+
+        gym_set::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            session_id: Set(session.id),
+            exercise_id: Set(exercise_id),
+            set_number: Set(1),
+            repetitions: Set(10),
+            weight_kg: Set(Decimal::new(500, 1)),
+            set_type: Set(SetTypeEnum::Normal),
+            rpe: Set(None),
+            rir: Set(None),
+            rest_seconds: Set(None),
+            duration_seconds: Set(None),
+            distance_meters: Set(None),
+            created_at: Set(Utc::now().into()),
+            updated_at: Set(Utc::now().into()),
+        }
+        .insert(self.db)
+        .await
+        .unwrap()
+    }
+
+    pub async fn food_item(&self, added_by: Uuid, visibility: VisibilityEnum) -> food_item::Model {
+        food_item::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(format!("Food {}", Uuid::new_v4().simple())),
+            description: Set(None),
+            scan_code: Set(None),
+            calories_per100g: Set(100),
+            protein_per100g: Set(10),
+            carbs_per100g: Set(10),
+            fat_per100g: Set(1),
+            added_by: Set(added_by),
+            updated_at: Set(Utc::now().into()),
+            added_at: Set(Utc::now().into()),
+            visibility: Set(visibility),
+        }
+        .insert(self.db)
+        .await
+        .unwrap()
+    }
+
+    pub async fn meal_item(&self, user_id: Uuid, food_item_id: Uuid) -> meal_item::Model {
+        let meal = meal::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            kind: Set(MealTypeEnum::Lunch),
+            date: Set(Utc::now().date_naive()),
+            description: Set(None),
+            created_at: Set(Utc::now().into()),
+            updated_at: Set(Utc::now().into()),
+        }
+        .insert(self.db)
+        .await
+        .unwrap();
+
+        meal_item::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            meal_id: Set(meal.id),
+            food_item_id: Set(food_item_id),
+            quantity_in_grams: Set(150),
+            updated_at: Set(Utc::now().into()),
+            created_at: Set(Utc::now().into()),
+        }
+        .insert(self.db)
+        .await
+        .unwrap()
+    }
+
+    pub async fn login_device(&self, user_id: Uuid, ip: &str) -> user_login_device::Model {
+        user_login_device::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            user_agent: Set("Mozilla/5.0 (X11; Linux x86_64)".to_string()),
+            last_ip: Set(Some(ip.to_string())),
+            created_at: Set(Utc::now().into()),
+            last_seen_at: Set(Utc::now().into()),
+        }
+        .insert(self.db)
+        .await
+        .unwrap()
+    }
+
+    pub async fn two_factor(&self, user_id: Uuid) -> user_two_factor::Model {
+        user_two_factor::ActiveModel {
+            user_id: Set(user_id),
+            secret: Set("JBSWY3DPEHPK3PXP".to_string()),
+            enabled: Set(true),
+            last_used_step: Set(Some(1)),
+            enabled_at: Set(Some(Utc::now().into())),
+            created_at: Set(Utc::now().into()),
+            updated_at: Set(Utc::now().into()),
+        }
+        .insert(self.db)
+        .await
+        .unwrap()
+    }
+}
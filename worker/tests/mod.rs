@@ -0,0 +1,3 @@
+mod export_jobs;
+mod helpers;
+mod scheduled_jobs;
@@ -0,0 +1,225 @@
+use crate::helpers::{test_data::TestData, test_db::get_db};
+use chrono::{Duration, Utc};
+use dimdim_health_worker::scheduled_jobs::maintenance_processor::run_maintenance;
+use entities::{
+    env_loader::MaintenanceSettings, food_item, gym_exercise, gym_set, magic_link_token, meal_item,
+    refresh_token, sea_orm_active_enums::VisibilityEnum, users, users_ext::SYSTEM_USER_ID,
+};
+use sea_orm::EntityTrait;
+
+#[tokio::test]
+async fn test_due_deletion_keeps_items_used_by_others() {
+    let db = &get_db().await;
+    let td = TestData::new(db);
+
+    let deleted = td.user(Utc::now()).await;
+    let other = td.user(Utc::now()).await;
+    td.deletion_request(deleted.id, Utc::now() - Duration::hours(1))
+        .await;
+
+    let public_exercise = td.exercise(deleted.id, VisibilityEnum::Public).await;
+    let private_exercise = td.exercise(deleted.id, VisibilityEnum::Private).await;
+    let other_set = td.gym_set(other.id, public_exercise.id).await;
+    td.gym_set(deleted.id, private_exercise.id).await;
+
+    // Made private again after another user added it to a meal
+    let food = td.food_item(deleted.id, VisibilityEnum::Private).await;
+    let other_meal_item = td.meal_item(other.id, food.id).await;
+
+    run_maintenance(db, &MaintenanceSettings::default())
+        .await
+        .unwrap();
+
+    assert!(
+        users::Entity::find_by_id(deleted.id)
+            .one(db)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let public_exercise = gym_exercise::Entity::find_by_id(public_exercise.id)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(public_exercise.added_by, SYSTEM_USER_ID);
+    assert!(
+        gym_set::Entity::find_by_id(other_set.id)
+            .one(db)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    let food = food_item::Entity::find_by_id(food.id)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(food.added_by, SYSTEM_USER_ID);
+    assert!(
+        meal_item::Entity::find_by_id(other_meal_item.id)
+            .one(db)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    // Nobody else relied on it, it goes with its creator
+    assert!(
+        gym_exercise::Entity::find_by_id(private_exercise.id)
+            .one(db)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_deletion_not_due_yet() {
+    let db = &get_db().await;
+    let td = TestData::new(db);
+
+    let user = td.user(Utc::now()).await;
+    td.deletion_request(user.id, Utc::now() + Duration::days(1))
+        .await;
+
+    run_maintenance(db, &MaintenanceSettings::default())
+        .await
+        .unwrap();
+
+    assert!(
+        users::Entity::find_by_id(user.id)
+            .one(db)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn test_inactive_guests_are_purged() {
+    let db = &get_db().await;
+    let td = TestData::new(db);
+    let settings = MaintenanceSettings::default();
+    let old = Utc::now() - Duration::days(i64::from(settings.guest_inactive_days) + 1);
+
+    let inactive = td.guest(old).await;
+    td.refresh_token(inactive.id, old, old + Duration::days(1))
+        .await;
+    let still_active = td.guest(old).await;
+    td.refresh_token(
+        still_active.id,
+        Utc::now() - Duration::days(1),
+        Utc::now() + Duration::days(6),
+    )
+    .await;
+    let new_guest = td.guest(Utc::now() - Duration::days(1)).await;
+    let old_user = td.user(old).await;
+
+    // A guest exercise someone else picked up outlives the guest
+    let exercise = td.exercise(inactive.id, VisibilityEnum::Private).await;
+    let other_set = td.gym_set(old_user.id, exercise.id).await;
+
+    run_maintenance(db, &settings).await.unwrap();
+
+    assert!(
+        users::Entity::find_by_id(inactive.id)
+            .one(db)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    for kept in [still_active.id, new_guest.id, old_user.id] {
+        assert!(
+            users::Entity::find_by_id(kept)
+                .one(db)
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    let exercise = gym_exercise::Entity::find_by_id(exercise.id)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(exercise.added_by, SYSTEM_USER_ID);
+    assert!(
+        gym_set::Entity::find_by_id(other_set.id)
+            .one(db)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn test_expired_tokens_are_removed() {
+    let db = &get_db().await;
+    let td = TestData::new(db);
+    let user = td.user(Utc::now()).await;
+
+    let expired = td
+        .refresh_token(
+            user.id,
+            Utc::now() - Duration::days(8),
+            Utc::now() - Duration::days(1),
+        )
+        .await;
+    let valid = td
+        .refresh_token(user.id, Utc::now(), Utc::now() + Duration::days(7))
+        .await;
+
+    let expired_link = td
+        .magic_link_token(user.id, Utc::now() - Duration::minutes(1), None)
+        .await;
+    let used_link = td
+        .magic_link_token(
+            user.id,
+            Utc::now() + Duration::minutes(10),
+            Some(Utc::now()),
+        )
+        .await;
+    let valid_link = td
+        .magic_link_token(user.id, Utc::now() + Duration::minutes(10), None)
+        .await;
+
+    run_maintenance(db, &MaintenanceSettings::default())
+        .await
+        .unwrap();
+
+    assert!(
+        refresh_token::Entity::find_by_id(expired.id)
+            .one(db)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        refresh_token::Entity::find_by_id(valid.id)
+            .one(db)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    for removed in [expired_link.id, used_link.id] {
+        assert!(
+            magic_link_token::Entity::find_by_id(removed)
+                .one(db)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+    assert!(
+        magic_link_token::Entity::find_by_id(valid_link.id)
+            .one(db)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}